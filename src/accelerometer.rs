@@ -0,0 +1,116 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use embedded_graphics::prelude::Point;
+use rppal::i2c::I2c;
+
+/// Supported I2C accelerometer chips.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Model {
+    Mpu6050,
+    Adxl345,
+}
+
+// MPU6050 registers
+const MPU6050_ADDRESS: u16 = 0x68;
+const MPU6050_PWR_MGMT_1: u8 = 0x6b;
+const MPU6050_ACCEL_XOUT_H: u8 = 0x3b;
+// Readings per g in the default +-2g range
+const MPU6050_SCALE: f32 = 16384.0;
+
+// ADXL345 registers
+const ADXL345_ADDRESS: u16 = 0x53;
+const ADXL345_POWER_CTL: u8 = 0x2d;
+const ADXL345_DATA_FORMAT: u8 = 0x31;
+const ADXL345_DATAX0: u8 = 0x32;
+// Readings per g in full resolution mode
+const ADXL345_SCALE: f32 = 256.0;
+
+/// Acceleration along an axis needed for the board to count as tilted, in g.
+const TILT_THRESHOLD: f32 = 0.25;
+
+/// Acceleration along the two axes parallel to the matrix, in g.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Acceleration {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// An accelerometer connected to the I2C bus of the Pi.
+pub struct Accelerometer {
+    i2c: I2c,
+    model: Model,
+}
+
+impl Accelerometer {
+    pub fn new(model: Model) -> Result<Self> {
+        let mut i2c = I2c::new()?;
+
+        match model {
+            Model::Mpu6050 => {
+                i2c.set_slave_address(MPU6050_ADDRESS)?;
+                // The chip starts up asleep
+                i2c.smbus_write_byte(MPU6050_PWR_MGMT_1, 0)?;
+            }
+            Model::Adxl345 => {
+                i2c.set_slave_address(ADXL345_ADDRESS)?;
+                // Full resolution keeps the scale the same in every range
+                i2c.smbus_write_byte(ADXL345_DATA_FORMAT, 0x08)?;
+                // Leave standby and start measuring
+                i2c.smbus_write_byte(ADXL345_POWER_CTL, 0x08)?;
+            }
+        }
+
+        Ok(Self { i2c, model })
+    }
+
+    /// Reads the current acceleration.
+    pub fn read(&mut self) -> Result<Acceleration> {
+        let mut buffer = [0; 4];
+
+        let (x, y, scale) = match self.model {
+            Model::Mpu6050 => {
+                self.i2c.block_read(MPU6050_ACCEL_XOUT_H, &mut buffer)?;
+
+                (
+                    i16::from_be_bytes([buffer[0], buffer[1]]),
+                    i16::from_be_bytes([buffer[2], buffer[3]]),
+                    MPU6050_SCALE,
+                )
+            }
+            Model::Adxl345 => {
+                self.i2c.block_read(ADXL345_DATAX0, &mut buffer)?;
+
+                (
+                    i16::from_le_bytes([buffer[0], buffer[1]]),
+                    i16::from_le_bytes([buffer[2], buffer[3]]),
+                    ADXL345_SCALE,
+                )
+            }
+        };
+
+        Ok(Acceleration {
+            x: x as f32 / scale,
+            y: y as f32 / scale,
+        })
+    }
+
+    /// Returns which way is down on the matrix, as a step of at most one pixel
+    /// along each axis, or `None` if the board is lying flat.
+    pub fn gravity(&mut self) -> Result<Option<Point>> {
+        let acceleration = self.read()?;
+
+        let axis = |value: f32| {
+            if value > TILT_THRESHOLD {
+                1
+            } else if value < -TILT_THRESHOLD {
+                -1
+            } else {
+                0
+            }
+        };
+
+        let down = Point::new(axis(acceleration.x), axis(acceleration.y));
+
+        Ok(Some(down).filter(|down| *down != Point::zero()))
+    }
+}
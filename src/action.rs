@@ -0,0 +1,35 @@
+use crate::mode::Mode;
+use serde::Deserialize;
+
+/// Something the user asked the display to do, no matter which mode is
+/// running.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// Show the next of the configured messages.
+    NextMessage,
+    /// Blank the display, or turn it back on.
+    TogglePower,
+    /// Turn the display back on if it was blanked.
+    PowerOn,
+    /// Blank the display and stop refreshing it, until it is turned back on.
+    PowerOff,
+    /// Switch to the next brightness level, wrapping around to the brightest.
+    CycleBrightness,
+    /// Make the display brighter by this many steps, or dimmer if negative.
+    AdjustBrightness(i32),
+    /// Set the brightness to this level, from `0` (off) to `255` (brightest).
+    SetBrightness(u8),
+    /// Switch to the next display mode.
+    NextMode,
+    /// Switch to this display mode.
+    SwitchMode(Mode),
+    /// Freeze the animation, or let it continue.
+    TogglePause,
+    /// Freeze the animation on the current frame.
+    Pause,
+    /// Let a frozen animation continue.
+    Resume,
+    /// Show the negative of the display, or go back to showing it normally.
+    ToggleInvert,
+}
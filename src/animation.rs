@@ -0,0 +1,31 @@
+use crate::{action::Action, framebuffer::FrameBuffer, input::InputEvent};
+
+/// Something which changes over time and can be drawn onto the matrix.
+pub trait Animation {
+    /// Advances the animation by one frame.
+    fn tick(&mut self);
+
+    /// Draws the current frame of the animation.
+    fn draw(&self, frame: &mut FrameBuffer);
+
+    /// Called for everything the user does with the input devices. Does
+    /// nothing by default.
+    fn handle_input(&mut self, _event: InputEvent) {}
+
+    /// Returns whether the animation is played with the buttons, in which
+    /// case pressing them doesn't trigger the gestures of the display. Not by
+    /// default.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+
+    /// Called for the actions the user asked for which aren't handled by the
+    /// display itself. Does nothing by default.
+    fn handle_action(&mut self, _action: Action) {}
+
+    /// Called with messages the user wants shown instead of the ones the
+    /// animation shows, which should be shown from the beginning of the first
+    /// even if they arrive halfway through scrolling another. There is always
+    /// at least one. Does nothing by default.
+    fn set_messages(&mut self, _messages: &[String]) {}
+}
@@ -0,0 +1,207 @@
+use crate::{config::AudioConfig, log};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    f32::consts::PI,
+    io::{BufReader, Read},
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread,
+};
+
+/// Number of samples in every block, which is a power of two so that blocks
+/// can be transformed as they are.
+pub const BLOCK_SAMPLES: usize = 512;
+
+/// Set once `arecord` stopped before recording anything, like when the
+/// microphone is missing, after which captures fail right away so that the
+/// modes which need one are skipped.
+static NO_AUDIO: AtomicBool = AtomicBool::new(false);
+
+/// Audio captured from a microphone, in blocks of [`BLOCK_SAMPLES`] mono
+/// samples from -1 to 1.
+///
+/// This runs the ALSA `arecord` command, which also captures from PulseAudio
+/// through its ALSA plugin, since there is no audio library to do it
+/// directly. It is killed when the capture is dropped.
+///
+/// Starting it doesn't wait for the first block, which takes a while to
+/// come, so there are no blocks for the first frames.
+pub struct Capture {
+    child: Child,
+    blocks: Receiver<Vec<f32>>,
+    // Set when `arecord` is killed on purpose
+    stopped: Arc<AtomicBool>,
+}
+
+impl Capture {
+    /// Starts capturing, or fails if no audio came from the device before.
+    pub fn new(config: &AudioConfig) -> Result<Self> {
+        if NO_AUDIO.load(Ordering::Relaxed) {
+            return Err(anyhow!("no audio from {}", config.device));
+        }
+
+        let mut child = Command::new("arecord")
+            .args(["-q", "-t", "raw", "-f", "S16_LE", "-c", "1", "-D"])
+            .arg(&config.device)
+            .args(["-r", &config.sample_rate.to_string()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run arecord")?;
+
+        let Some(stdout) = child.stdout.take() else {
+            return Err(anyhow!("the output of arecord isn't piped"));
+        };
+
+        let (tx, rx) = channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let device = config.device.clone();
+
+        // Reading blocks until enough samples were recorded, so it happens on
+        // its own thread
+        thread::spawn({
+            let stopped = stopped.clone();
+
+            move || match read_blocks(stdout, tx, &stopped) {
+                Ok(()) => {}
+                // This also happens whenever the capture is dropped
+                Err(err) if stopped.load(Ordering::Relaxed) => log::debug!("Audio: {:#}", err),
+                Err(err) => log::warn!("Audio from {}: {:#}", device, err),
+            }
+        });
+
+        Ok(Self {
+            child,
+            blocks: rx,
+            stopped,
+        })
+    }
+
+    /// Returns the blocks captured since the last call, oldest first.
+    pub fn blocks(&self) -> impl Iterator<Item = Vec<f32>> + '_ {
+        self.blocks.try_iter()
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        // Killing the command also ends the thread reading from it
+        self.stopped.store(true, Ordering::Relaxed);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Sends the blocks recorded by `arecord` until either it or the capture
+/// goes away, which sets `stopped` when it kills it.
+fn read_blocks(stdout: ChildStdout, blocks: Sender<Vec<f32>>, stopped: &AtomicBool) -> Result<()> {
+    let mut stdout = BufReader::new(stdout);
+    let mut bytes = [0; BLOCK_SAMPLES * 2];
+    let mut recorded = false;
+
+    loop {
+        if let Err(err) = stdout.read_exact(&mut bytes) {
+            // It stops right away when there is no such device
+            if !recorded && !stopped.load(Ordering::Relaxed) {
+                NO_AUDIO.store(true, Ordering::Relaxed);
+            }
+
+            return Err(err).context("arecord stopped recording");
+        }
+
+        recorded = true;
+
+        let block = bytes
+            .chunks_exact(2)
+            .map(|sample| i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32768.0)
+            .collect();
+
+        if blocks.send(block).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Returns the amplitude of the frequencies in a block, for as many bands as
+/// half its samples, evenly spaced from 0 up to half the sample rate. A sine
+/// wave going from -1 to 1 has an amplitude of 1.
+///
+/// The block is windowed first, so that frequencies between two bands don't
+/// leak all over the spectrum.
+///
+/// # Panics
+///
+/// If the number of samples isn't a power of two.
+pub fn spectrum(block: &[f32]) -> Vec<f32> {
+    let n = block.len();
+
+    assert!(
+        n.is_power_of_two(),
+        "blocks of {n} samples can't be transformed"
+    );
+
+    // Hann window, whose sum is half the number of samples
+    let mut bins: Vec<(f32, f32)> = block
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let window = 0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos();
+
+            (sample * window, 0.0)
+        })
+        .collect();
+
+    fft(&mut bins);
+
+    bins[..n / 2]
+        .iter()
+        .map(|(re, im)| (re * re + im * im).sqrt() * 4.0 / n as f32)
+        .collect()
+}
+
+/// Transforms complex values, as real and imaginary parts, into their
+/// frequencies in place, with the iterative radix-2 Cooley-Tukey algorithm.
+fn fft(values: &mut [(f32, f32)]) {
+    let n = values.len();
+
+    if n < 2 {
+        return;
+    }
+
+    let bits = n.trailing_zeros();
+
+    // Sorting the values by their bit-reversed index lets the butterflies
+    // work in place
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+
+    while size <= n {
+        let angle = -2.0 * PI / size as f32;
+
+        for start in (0..n).step_by(size) {
+            for k in 0..size / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (re, im) = values[start + k + size / 2];
+                let twiddled = (re * cos - im * sin, re * sin + im * cos);
+                let even = values[start + k];
+
+                values[start + k] = (even.0 + twiddled.0, even.1 + twiddled.1);
+                values[start + k + size / 2] = (even.0 - twiddled.0, even.1 - twiddled.1);
+            }
+        }
+
+        size *= 2;
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use rand::Rng;
+
+/// Width and height of the ball, in pixels.
+const BALL_SIZE: u32 = 2;
+
+/// Downwards acceleration, in pixels per frame squared.
+const GRAVITY: f32 = 0.35;
+
+/// Fraction of the vertical speed kept after bouncing off the floor.
+const BOUNCINESS: f32 = 0.8;
+
+/// Once a bounce is slower than this, the ball gets kicked back up again.
+const REST_SPEED: f32 = 0.6;
+
+/// A ball bouncing around the matrix under gravity.
+pub struct Ball {
+    x: f32,
+    y: f32,
+    speed_x: f32,
+    speed_y: f32,
+}
+
+impl Ball {
+    pub fn new() -> Self {
+        let mut ball = Self {
+            x: 0.0,
+            y: 0.0,
+            speed_x: 0.0,
+            speed_y: 0.0,
+        };
+
+        ball.kick();
+
+        ball
+    }
+
+    /// Launches the ball upwards in a random direction.
+    fn kick(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        self.speed_x = rng.gen_range(0.4..1.0) * if rng.gen() { 1.0 } else { -1.0 };
+        self.speed_y = -rng.gen_range(2.0..2.8);
+    }
+}
+
+impl Animation for Ball {
+    fn tick(&mut self) {
+        let max_x = (WIDTH as u32 - BALL_SIZE) as f32;
+        let max_y = (HEIGHT as u32 - BALL_SIZE) as f32;
+
+        self.speed_y += GRAVITY;
+        self.x += self.speed_x;
+        self.y += self.speed_y;
+
+        // Bounce off the side walls
+        if self.x < 0.0 {
+            self.x = -self.x;
+            self.speed_x = -self.speed_x;
+        } else if self.x > max_x {
+            self.x = 2.0 * max_x - self.x;
+            self.speed_x = -self.speed_x;
+        }
+
+        // Bounce off the ceiling
+        if self.y < 0.0 {
+            self.y = -self.y;
+            self.speed_y = -self.speed_y;
+        }
+
+        // Bounce off the floor, losing some energy every time
+        if self.y > max_y {
+            self.y = max_y;
+            self.speed_y = -self.speed_y * BOUNCINESS;
+
+            if self.speed_y.abs() < REST_SPEED {
+                self.kick();
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let top_left = Point::new(self.x.round() as i32, self.y.round() as i32);
+
+        let Ok(()) = Rectangle::new(top_left, Size::new(BALL_SIZE, BALL_SIZE))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(frame);
+    }
+}
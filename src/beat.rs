@@ -0,0 +1,125 @@
+use crate::{
+    animation::Animation,
+    audio::{self, Capture, BLOCK_SAMPLES},
+    config::AudioConfig,
+    framebuffer::FrameBuffer,
+};
+use anyhow::Result;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Highest frequency of the bass the beats are detected in, in hertz, which
+/// is where kick drums are.
+const BASS_FREQUENCY: f32 = 150.0;
+
+/// How far back the bass is averaged to compare new blocks with.
+const HISTORY: Duration = Duration::from_secs(1);
+
+/// How much louder than the average the bass has to get for a beat.
+const SENSITIVITY: f32 = 1.5;
+
+/// Bass quieter than this, as an amplitude, is never a beat, so that noise
+/// in a quiet room doesn't pulse.
+const QUIETEST_BASS: f32 = 0.01;
+
+/// Shortest time between two beats, which is 240 beats per minute.
+const MIN_BEAT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Brightness of what is shown between two beats, which it fades down to
+/// after every beat.
+const RESTING_BRIGHTNESS: u8 = 64;
+
+/// How fast the pulse fades after a beat, in pulses per second.
+const FADE_RATE: f32 = 4.0;
+
+/// Overlay pulsing whatever is shown below it in time with the music heard
+/// by a microphone.
+///
+/// What is shown lights up fully on every beat, and fades down until the next
+/// one. Beats are onsets of the bass: blocks whose bass is much louder than
+/// it was on average recently.
+///
+/// It has to be drawn straight over the layers below it, since it dims them.
+pub struct BeatPulse {
+    capture: Capture,
+    // Bins of the spectrum with the bass
+    bass_bins: usize,
+    // Bass of the recent blocks, the newest at the back
+    history: VecDeque<f32>,
+    history_len: usize,
+    last_beat: Option<Instant>,
+    // From 1 on a beat down to 0
+    pulse: f32,
+    last_tick: Instant,
+}
+
+impl BeatPulse {
+    pub fn new(config: &AudioConfig) -> Result<Self> {
+        let bin_width = config.sample_rate as f32 / BLOCK_SAMPLES as f32;
+        let history_len = (HISTORY.as_secs_f32() * config.sample_rate as f32 / BLOCK_SAMPLES as f32)
+            .ceil() as usize;
+
+        Ok(Self {
+            capture: Capture::new(config)?,
+            bass_bins: ((BASS_FREQUENCY / bin_width).ceil() as usize).clamp(2, BLOCK_SAMPLES / 2),
+            history: VecDeque::with_capacity(history_len),
+            history_len: history_len.max(1),
+            last_beat: None,
+            pulse: 0.0,
+            last_tick: Instant::now(),
+        })
+    }
+
+    /// Adds the bass of a block to the history, returning whether it is a
+    /// beat.
+    fn detect(&mut self, bass: f32) -> bool {
+        let average = self.history.iter().sum::<f32>() / self.history.len().max(1) as f32;
+
+        // A beat needs something to compare with
+        let onset = self.history.len() == self.history_len
+            && bass > QUIETEST_BASS
+            && bass > average * SENSITIVITY;
+
+        if self.history.len() == self.history_len {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(bass);
+
+        onset
+            && self
+                .last_beat
+                .is_none_or(|last| last.elapsed() >= MIN_BEAT_INTERVAL)
+    }
+}
+
+impl Animation for BeatPulse {
+    fn tick(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f32();
+        self.last_tick = Instant::now();
+
+        self.pulse = (self.pulse - FADE_RATE * elapsed).max(0.0);
+
+        let blocks: Vec<Vec<f32>> = self.capture.blocks().collect();
+
+        for block in blocks {
+            // The lowest bin is what the signal is offset by, not bass
+            let bass = audio::spectrum(&block)[1..self.bass_bins]
+                .iter()
+                .sum::<f32>();
+
+            if self.detect(bass) {
+                self.last_beat = Some(Instant::now());
+                self.pulse = 1.0;
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let range = (u8::MAX - RESTING_BRIGHTNESS) as f32;
+
+        frame.dim(RESTING_BRIGHTNESS + (range * self.pulse).round() as u8);
+    }
+}
@@ -0,0 +1,147 @@
+//! Controls a running display through the HTTP server of the daemon.
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    process::ExitCode,
+    time::Duration,
+};
+
+/// How long to wait for the daemon before giving up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Parser)]
+struct Args {
+    /// Address and port the HTTP server of the daemon listens on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    address: String,
+
+    /// Token of the HTTP server, if its configuration has one
+    #[arg(long)]
+    token: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replace the messages of the text mode with this one
+    Text { text: String },
+    /// Scroll a message once on top of whatever is shown
+    Announce { message: String },
+    /// Set the brightness, in percent
+    Brightness {
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
+        percent: u8,
+    },
+    /// Switch to a mode, by name
+    Mode { name: String },
+    /// Freeze the animation on the current frame
+    Pause,
+    /// Let a frozen animation continue
+    Resume,
+    /// Turn the display on or off
+    Power { state: Power },
+    /// Go back to the mode after a frame was shown, and drop the
+    /// announcements which weren't scrolled yet
+    Clear,
+    /// Print what the display is doing, as JSON
+    Status,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Power {
+    On,
+    Off,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let (method, path, body) = match args.command {
+        Command::Text { text } => ("POST", "/text", text),
+        Command::Announce { message } => ("POST", "/announce", message),
+        Command::Brightness { percent } => (
+            "POST",
+            "/brightness",
+            (percent as u32 * u8::MAX as u32 / 100).to_string(),
+        ),
+        Command::Mode { name } => ("POST", "/mode", name),
+        Command::Pause => ("POST", "/pause", String::new()),
+        Command::Resume => ("POST", "/resume", String::new()),
+        Command::Power { state: Power::On } => ("POST", "/power/on", String::new()),
+        Command::Power { state: Power::Off } => ("POST", "/power/off", String::new()),
+        Command::Clear => ("POST", "/clear", String::new()),
+        Command::Status => ("GET", "/status", String::new()),
+    };
+
+    match request(&args.address, args.token.as_deref(), method, path, &body) {
+        Ok((200, body)) => {
+            // Commands only answer with `OK`, which isn't worth printing
+            if method == "GET" {
+                println!("{}", body.trim_end());
+            }
+
+            ExitCode::SUCCESS
+        }
+        Ok((status, body)) => {
+            eprintln!("The display refused ({}): {}", status, body.trim_end());
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Sends a request to the daemon at `address`, with `token` as a bearer token
+/// if there is one, returning the status and the body of its response.
+fn request(
+    address: &str,
+    token: Option<&str>,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Result<(u16, String)> {
+    let mut stream = TcpStream::connect(address)
+        .with_context(|| format!("failed to connect to the display at {}", address))?;
+
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let authorization = token
+        .map(|token| format!("Authorization: Bearer {}\r\n", token))
+        .unwrap_or_default();
+
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        address,
+        authorization,
+        body.len(),
+        body
+    )?;
+
+    // The daemon closes the connection after every response
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("malformed response from the display"))?;
+
+    // `HTTP/1.1 200 OK`
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| anyhow!("malformed response from the display"))?;
+
+    Ok((status, body.to_string()))
+}
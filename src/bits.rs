@@ -0,0 +1,113 @@
+use crate::{
+    action::Action,
+    animation::Animation,
+    framebuffer::{FrameBuffer, WIDTH},
+    text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+
+/// A 64-bit value shown as its bits, one per pixel, like a register or a
+/// bitmask.
+///
+/// The value is the message, in decimal, or in hexadecimal, octal or binary
+/// with a `0x`, `0o` or `0b` prefix, with underscores between the digits if
+/// need be. Negative decimals are shown in two's complement. The bits are
+/// shown row by row, from the highest one on the top left to the lowest one
+/// on the bottom right, so that every row is a byte, the highest one on top.
+///
+/// Messages which aren't a value are scrolled instead. With several
+/// messages, the next one is shown with the next message action.
+pub struct Bits {
+    messages: Vec<String>,
+    current: usize,
+    shown: Shown,
+}
+
+enum Shown {
+    Value(u64),
+    Invalid(ScrollingText),
+}
+
+impl Bits {
+    /// Creates the animation, starting with the first of `messages`, which
+    /// can't be empty.
+    pub fn new(messages: Vec<String>) -> Self {
+        let shown = show(&messages[0]);
+
+        Self {
+            messages,
+            current: 0,
+            shown,
+        }
+    }
+}
+
+/// Returns how a message is shown.
+fn show(message: &str) -> Shown {
+    match parse(message) {
+        Some(value) => Shown::Value(value),
+        None => Shown::Invalid(ScrollingText::new(format!("Not a value: {}", message))),
+    }
+}
+
+/// Parses a value written like Rust would, or returns nothing if it isn't
+/// one which fits in 64 bits.
+fn parse(text: &str) -> Option<u64> {
+    let text = text.trim().replace('_', "");
+    let lower = text.to_ascii_lowercase();
+
+    let (digits, radix) = if let Some(digits) = lower.strip_prefix("0x") {
+        (digits, 16)
+    } else if let Some(digits) = lower.strip_prefix("0o") {
+        (digits, 8)
+    } else if let Some(digits) = lower.strip_prefix("0b") {
+        (digits, 2)
+    } else if lower.starts_with('-') {
+        return lower.parse::<i64>().ok().map(|value| value as u64);
+    } else {
+        (lower.as_str(), 10)
+    };
+
+    // Signs aren't digits, but `from_str_radix` takes them
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+
+    u64::from_str_radix(digits, radix).ok()
+}
+
+impl Animation for Bits {
+    fn tick(&mut self) {
+        if let Shown::Invalid(text) = &mut self.shown {
+            text.tick();
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match &self.shown {
+            Shown::Value(value) => {
+                for bit in 0..64 {
+                    if value & (1 << (63 - bit)) != 0 {
+                        let point = Point::new((bit % WIDTH) as i32, (bit / WIDTH) as i32);
+
+                        let Ok(()) = Pixel(point, BinaryColor::On).draw(frame);
+                    }
+                }
+            }
+            Shown::Invalid(text) => text.draw(frame),
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        if action == Action::NextMessage {
+            self.current = (self.current + 1) % self.messages.len();
+            self.shown = show(&self.messages[self.current]);
+        }
+    }
+
+    fn set_messages(&mut self, messages: &[String]) {
+        self.messages = messages.to_vec();
+        self.current = 0;
+        self.shown = show(&messages[0]);
+    }
+}
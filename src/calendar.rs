@@ -0,0 +1,616 @@
+use crate::{
+    animation::Animation, config::CalendarConfig, framebuffer::FrameBuffer, http, log,
+    text::ScrollingText,
+};
+use std::{
+    mem,
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// An event of a calendar.
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub summary: String,
+    start: DateTime,
+    /// How the event repeats, if it does in a way which is understood.
+    repeat: Option<Repeat>,
+}
+
+impl Event {
+    /// Returns when the first occurrence of the event after `time` starts,
+    /// as a Unix timestamp.
+    fn next_start(&self, time: i64) -> Option<i64> {
+        let first = self.start.timestamp(0)?;
+
+        let Some(repeat) = &self.repeat else {
+            return (first > time).then_some(first);
+        };
+
+        // The first period misses the days of the week before the start
+        let per_period = repeat.offsets.len() as u64;
+        let in_first_period = repeat.offsets.iter().filter(|&&offset| offset >= 0).count() as u64;
+
+        // Starting a period early, since daylight saving time and the
+        // offsets shift the occurrences by a bit
+        let mut period = ((time - first).div_euclid(repeat.period * 86_400) - 1).max(0);
+
+        loop {
+            // Number of the next occurrence, from 0 for the first one
+            let mut index = match period {
+                0 => 0,
+                _ => in_first_period + (period as u64 - 1) * per_period,
+            };
+
+            for &offset in &repeat.offsets {
+                let days = period * repeat.period + offset;
+
+                if days < 0 {
+                    continue;
+                }
+
+                if repeat.count.is_some_and(|count| index >= count) {
+                    return None;
+                }
+
+                let start = self.start.timestamp(days)?;
+
+                if repeat.until.is_some_and(|until| start > until) {
+                    return None;
+                }
+
+                if start > time {
+                    return Some(start);
+                }
+
+                index += 1;
+            }
+
+            period += 1;
+        }
+    }
+}
+
+/// A date and time of a calendar, as it is written, so that repeating it
+/// every day keeps the same local time when daylight saving time changes.
+#[derive(Clone, Copy, Debug)]
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    utc: bool,
+}
+
+impl DateTime {
+    /// Parses a date, like `20261015`, or a date and time, like
+    /// `20261015T093000` with a trailing `Z` for UTC.
+    fn parse(value: &str, parameters: &str) -> Option<Self> {
+        let number = |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+
+        let (year, month, day) = (number(0..4)?, number(4..6)?, number(6..8)?);
+
+        // Dates without a time are whole days, which start at local midnight
+        let (hour, minute, second) = if parameters.contains("VALUE=DATE") || value.len() == 8 {
+            (0, 0, 0)
+        } else {
+            (number(9..11)?, number(11..13)?, number(13..15)?)
+        };
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            utc: value.ends_with('Z'),
+        })
+    }
+
+    /// Returns the day of the week, from `0` for Monday to `6` for Sunday.
+    fn weekday(self) -> i64 {
+        // 1970-01-01 was a Thursday
+        (days_from_civil(self.year, self.month, self.day) + 3).rem_euclid(7)
+    }
+
+    /// Returns the Unix timestamp of the same time `days` days later.
+    fn timestamp(self, days: i64) -> Option<i64> {
+        let day = self.day + days;
+
+        if self.utc {
+            let days = days_from_civil(self.year, self.month, day);
+
+            Some(days * 86_400 + self.hour * 3_600 + self.minute * 60 + self.second)
+        } else {
+            local_timestamp(
+                self.year,
+                self.month,
+                day,
+                self.hour,
+                self.minute,
+                self.second,
+            )
+        }
+    }
+}
+
+/// How an event repeats, every so many days or weeks.
+#[derive(Clone, Debug)]
+struct Repeat {
+    /// Days from one period to the next, like 14 for every other week.
+    period: i64,
+    /// Days from the start of the event to its occurrences in a period, in
+    /// order, which are negative for the days of the first week before it.
+    offsets: Vec<i64>,
+    /// Number of occurrences, including the first one.
+    count: Option<u64>,
+    /// Latest start of an occurrence, as a Unix timestamp.
+    until: Option<i64>,
+}
+
+impl Repeat {
+    /// Parses a rule, like `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH`, of an event
+    /// starting at `start`. Only daily and weekly rules are understood,
+    /// with a count, an end, and the days of the week for weekly ones.
+    fn parse(rule: &str, start: DateTime) -> Option<Self> {
+        let mut days_per_period = None;
+        let mut interval = 1;
+        let mut weekdays = Vec::new();
+        let mut count = None;
+        let mut until = None;
+
+        for part in rule.split(';') {
+            let (name, value) = part.split_once('=')?;
+
+            match name {
+                "FREQ" => {
+                    days_per_period = Some(match value {
+                        "DAILY" => 1,
+                        "WEEKLY" => 7,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => interval = value.parse().ok().filter(|&interval| interval > 0)?,
+                "COUNT" => count = Some(value.parse().ok()?),
+                "UNTIL" => until = Some(DateTime::parse(value, "")?.timestamp(0)?),
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        const DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+                        weekdays.push(DAYS.iter().position(|&name| name == day)? as i64);
+                    }
+                }
+                // Weeks start on Monday
+                "WKST" if value == "MO" => {}
+                _ => return None,
+            }
+        }
+
+        let days_per_period = days_per_period?;
+
+        let mut offsets = match days_per_period {
+            7 if !weekdays.is_empty() => weekdays
+                .iter()
+                .map(|weekday| weekday - start.weekday())
+                .collect(),
+            _ if weekdays.is_empty() => vec![0],
+            _ => return None,
+        };
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        Some(Self {
+            period: days_per_period * interval,
+            offsets,
+            count,
+            until,
+        })
+    }
+}
+
+/// Returns the events of an iCalendar document which have a start time.
+///
+/// Events repeating daily or weekly have all their occurrences, but other
+/// recurring events only count with their first one, and exceptions to the
+/// rules are ignored. Times in a named time zone are taken as local time.
+fn parse_events(ical: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut summary = None;
+    let mut start = None;
+    let mut rule = None;
+
+    for line in unfold(ical) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        // Parameters like `;TZID=...` come between the name and the value
+        let (name, parameters) = name.split_once(';').unwrap_or((name, ""));
+
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                summary = None;
+                start = None;
+                rule = None;
+            }
+            "SUMMARY" => summary = Some(unescape(value)),
+            "DTSTART" => start = DateTime::parse(value, parameters),
+            "RRULE" => rule = Some(value.to_string()),
+            "END" if value == "VEVENT" => {
+                if let Some(start) = start.take() {
+                    events.push(Event {
+                        summary: summary.take().unwrap_or_default(),
+                        start,
+                        repeat: rule.take().and_then(|rule| Repeat::parse(&rule, start)),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Joins the lines which were folded, which start with a space or a tab.
+fn unfold(ical: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in ical.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Decodes the escaped characters of a text value.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push(' '),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+/// Returns the number of days between 1970-01-01 and the given date of the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // From Howard Hinnant's `days_from_civil`, with years starting in March
+    // so that leap days come last
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts a local date and time into a Unix timestamp.
+fn local_timestamp(
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+) -> Option<i64> {
+    // SAFETY: `tm` is plain data for which all zeros is a valid value
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+
+    tm.tm_year = (year - 1900) as i32;
+    tm.tm_mon = (month - 1) as i32;
+    tm.tm_mday = day as i32;
+    tm.tm_hour = hour as i32;
+    tm.tm_min = minute as i32;
+    tm.tm_sec = second as i32;
+    // Let the C library work out whether daylight saving time applies
+    tm.tm_isdst = -1;
+
+    // SAFETY: `tm` is a valid, initialized struct
+    let timestamp = unsafe { libc::mktime(&mut tm) };
+
+    (timestamp != -1).then_some(timestamp as i64)
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Formats the time left until an event, like `2d 4h` or `1h 20m`.
+fn format_countdown(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    let (days, hours, minutes) = (minutes / 1_440, minutes / 60 % 24, minutes % 60);
+
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Scrolls the title of the next event of an iCalendar feed, and how long it
+/// is until it starts.
+///
+/// The calendar is fetched again periodically, and the last one which could
+/// be fetched keeps being used in between and while offline.
+pub struct NextEvent {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<Vec<Event>>>,
+    // Events of the calendar, once it was fetched
+    events: Option<Vec<Event>>,
+    text: Option<ScrollingText>,
+    // Frames left until the current scroll is done
+    scroll_frames: u32,
+}
+
+impl NextEvent {
+    pub fn new(config: CalendarConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Fetching takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match http::get(&config.url) {
+                Ok(ical) => (
+                    Some(parse_events(&ical)),
+                    Duration::from_secs(config.interval_mins * 60),
+                ),
+                Err(err) => {
+                    log::warn!("Calendar: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            events: None,
+            text: None,
+            scroll_frames: 0,
+        }
+    }
+}
+
+impl Animation for NextEvent {
+    fn tick(&mut self) {
+        if let Some(events) = self.updates.try_iter().flatten().last() {
+            self.events = Some(events);
+        }
+
+        if let Some(text) = &mut self.text {
+            text.tick();
+            self.scroll_frames = self.scroll_frames.saturating_sub(1);
+        }
+
+        // The countdown only changes in between two scrolls, so that it
+        // doesn't make the text jump
+        if self.scroll_frames > 0 {
+            return;
+        }
+
+        let Some(events) = &self.events else {
+            return;
+        };
+
+        let now = now();
+        let next = events
+            .iter()
+            .filter_map(|event| Some((event, event.next_start(now)?)))
+            .min_by_key(|&(_, start)| start);
+
+        let message = match next {
+            Some((event, start)) => {
+                format!("{} in {}", event.summary, format_countdown(start - now))
+            }
+            None => "No events".to_string(),
+        };
+
+        let text = ScrollingText::new(message);
+
+        self.scroll_frames = text.width();
+        self.text = Some(text);
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some(text) = &self.text {
+            text.draw(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the Unix timestamp of a UTC date and time.
+    fn utc(value: &str) -> i64 {
+        DateTime::parse(value, "").unwrap().timestamp(0).unwrap()
+    }
+
+    fn event(lines: &[&str]) -> Event {
+        let ical = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            lines.join("\r\n")
+        );
+        let mut events = parse_events(&ical);
+
+        assert_eq!(events.len(), 1);
+        events.remove(0)
+    }
+
+    /// Returns the starts of the first `n` occurrences of `event` after
+    /// `time`.
+    fn starts(event: &Event, mut time: i64, n: usize) -> Vec<i64> {
+        let mut starts = Vec::new();
+
+        while let Some(start) = event.next_start(time).filter(|_| starts.len() < n) {
+            starts.push(start);
+            time = start;
+        }
+
+        starts
+    }
+
+    #[test]
+    fn parses_events() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\n\
+             BEGIN:VEVENT\n\
+             SUMMARY:Team\\, weekly\n\
+             \x20 meeting\n\
+             DTSTART:20261015T093000Z\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             SUMMARY:No start\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             DTSTART;VALUE=DATE:20261016\n\
+             END:VEVENT\n\
+             END:VCALENDAR\n",
+        );
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Team, weekly meeting");
+        assert_eq!(events[0].next_start(0), Some(utc("20261015T093000Z")));
+        assert_eq!(events[1].summary, "");
+        assert_eq!(events[1].start.day, 16);
+    }
+
+    #[test]
+    fn converts_utc_times() {
+        assert_eq!(utc("19700101T000000Z"), 0);
+        assert_eq!(utc("20261015T093000Z"), 1_792_056_600);
+        assert!(DateTime::parse("2026", "").is_none());
+    }
+
+    #[test]
+    fn single_events_are_only_next_before_they_start() {
+        let event = event(&["DTSTART:20261015T093000Z"]);
+        let start = utc("20261015T093000Z");
+
+        assert_eq!(event.next_start(start - 1), Some(start));
+        assert_eq!(event.next_start(start), None);
+    }
+
+    #[test]
+    fn repeats_daily() {
+        let event = event(&["DTSTART:20261015T093000Z", "RRULE:FREQ=DAILY"]);
+
+        assert_eq!(
+            event.next_start(utc("20270301T120000Z")),
+            Some(utc("20270302T093000Z"))
+        );
+    }
+
+    #[test]
+    fn repeats_at_intervals() {
+        let event = event(&["DTSTART:20261015T093000Z", "RRULE:FREQ=WEEKLY;INTERVAL=2"]);
+
+        assert_eq!(
+            starts(&event, utc("20261015T000000Z"), 3),
+            [
+                utc("20261015T093000Z"),
+                utc("20261029T093000Z"),
+                utc("20261112T093000Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats_on_days_of_the_week() {
+        // A Thursday
+        let event = event(&[
+            "DTSTART:20261015T093000Z",
+            "RRULE:FREQ=WEEKLY;BYDAY=MO,TH;WKST=MO",
+        ]);
+
+        assert_eq!(
+            starts(&event, utc("20261001T000000Z"), 4),
+            [
+                utc("20261015T093000Z"),
+                utc("20261019T093000Z"),
+                utc("20261022T093000Z"),
+                utc("20261026T093000Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_after_the_count() {
+        let event = event(&[
+            "DTSTART:20261015T093000Z",
+            "RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=3",
+        ]);
+
+        assert_eq!(starts(&event, 0, 10).len(), 3);
+        assert_eq!(event.next_start(utc("20261022T093000Z")), None);
+    }
+
+    #[test]
+    fn stops_at_the_end() {
+        let event = event(&[
+            "DTSTART:20261015T093000Z",
+            "RRULE:FREQ=DAILY;UNTIL=20261017T093000Z",
+        ]);
+
+        assert_eq!(starts(&event, 0, 10).len(), 3);
+    }
+
+    #[test]
+    fn other_rules_only_count_the_first_occurrence() {
+        for rule in [
+            "RRULE:FREQ=MONTHLY",
+            "RRULE:FREQ=DAILY;BYDAY=MO",
+            "RRULE:FREQ=WEEKLY;BYMONTH=1",
+            "RRULE:FREQ=DAILY;INTERVAL=0",
+        ] {
+            let event = event(&["DTSTART:20261015T093000Z", rule]);
+
+            assert!(event.repeat.is_none(), "{}", rule);
+            assert_eq!(starts(&event, 0, 10).len(), 1);
+        }
+    }
+
+    #[test]
+    fn formats_countdowns() {
+        assert_eq!(format_countdown(59), "0m");
+        assert_eq!(format_countdown(80 * 60), "1h 20m");
+        assert_eq!(format_countdown((2 * 24 + 4) * 3_600 + 59), "2d 4h");
+    }
+}
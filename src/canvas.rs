@@ -0,0 +1,101 @@
+use crate::framebuffer::{self, FrameBuffer, HEIGHT, WIDTH};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use std::convert::Infallible;
+
+/// A drawing surface larger than the matrix, of which only an 8x8 viewport
+/// is shown.
+///
+/// Like [`FrameBuffer`], every pixel stores a brightness level. The viewport
+/// can be panned across the canvas, but never past its edges.
+pub struct VirtualCanvas {
+    size: Size,
+    levels: Vec<u8>,
+    // Top left corner of the part of the canvas which is shown
+    viewport: Point,
+}
+
+impl VirtualCanvas {
+    /// Creates a blank canvas of `size`, which has to be at least as large as
+    /// the matrix.
+    pub fn new(size: Size) -> Self {
+        let size = Size::new(size.width.max(WIDTH as u32), size.height.max(HEIGHT as u32));
+
+        Self {
+            size,
+            levels: vec![0; (size.width * size.height) as usize],
+            viewport: Point::zero(),
+        }
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        self.bounding_box()
+            .contains(p)
+            .then(|| (p.y as u32 * self.size.width + p.x as u32) as usize)
+    }
+
+    /// Returns the brightness level of the pixel at `p`, or `0` if `p` is
+    /// outside of the canvas.
+    pub fn level(&self, p: Point) -> u8 {
+        self.index(p).map_or(0, |index| self.levels[index])
+    }
+
+    /// Sets the brightness level of the pixel at `p`. Points outside of the
+    /// canvas are ignored.
+    pub fn set_level(&mut self, p: Point, level: u8) {
+        if let Some(index) = self.index(p) {
+            self.levels[index] = level;
+        }
+    }
+
+    /// Moves the top left corner of the viewport to `top_left`, or as close
+    /// to it as the edges of the canvas allow.
+    pub fn set_viewport(&mut self, top_left: Point) {
+        let max_x = (self.size.width - WIDTH as u32) as i32;
+        let max_y = (self.size.height - HEIGHT as u32) as i32;
+
+        self.viewport = Point::new(top_left.x.clamp(0, max_x), top_left.y.clamp(0, max_y));
+    }
+
+    /// Moves the viewport by `offset`, stopping at the edges of the canvas.
+    pub fn pan(&mut self, offset: Point) {
+        self.set_viewport(self.viewport + offset);
+    }
+
+    /// Centers the viewport on `center`, as far as the edges of the canvas
+    /// allow.
+    pub fn center_on(&mut self, center: Point) {
+        self.set_viewport(center - Point::new(WIDTH as i32 / 2, HEIGHT as i32 / 2));
+    }
+
+    /// Draws the part of the canvas under the viewport on top of `frame`,
+    /// keeping the brighter of the two levels for every pixel.
+    pub fn draw_viewport(&self, frame: &mut FrameBuffer) {
+        for p in frame.bounding_box().points() {
+            let level = self.level(self.viewport + p).max(frame.level(p));
+
+            frame.set_level(p, level);
+        }
+    }
+}
+
+impl OriginDimensions for VirtualCanvas {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for VirtualCanvas {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, c) in pixels {
+            self.set_level(p, framebuffer::color_level(c));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    animation::Animation,
+    config::{CiConfig, CiProvider, CiRepo},
+    framebuffer::FrameBuffer,
+    http,
+    icons::{self, CHECK, CROSS},
+    json::Json,
+    log,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Number of frames every repository is shown for before the next one.
+const VIEW_FRAMES: u32 = 15;
+
+/// Number of frames the display flashes for when a build breaks.
+const FLASH_FRAMES: u32 = 10;
+
+/// Returns whether the latest finished build of `repo` passed. Cancelled
+/// builds don't count as passed.
+fn fetch(repo: &CiRepo) -> Result<bool> {
+    let status = match repo.provider {
+        CiProvider::Github => {
+            let mut url = format!(
+                "https://api.github.com/repos/{}/actions/runs?status=completed&per_page=1",
+                repo.repo
+            );
+
+            if let Some(branch) = &repo.branch {
+                url.push_str(&format!("&branch={}", http::encode(branch)));
+            }
+
+            let authorization = repo.token.as_ref().map(|token| format!("Bearer {}", token));
+            let mut headers = vec![("Accept", "application/vnd.github+json")];
+            headers.extend(
+                authorization
+                    .as_deref()
+                    .map(|value| ("Authorization", value)),
+            );
+
+            // `{"workflow_runs": [{"conclusion": "success", ...}]}`
+            Json::parse(&http::get_with_headers(&url, &headers)?)?
+                .get("workflow_runs")
+                .and_then(|runs| runs.index(0))
+                .and_then(|run| run.get("conclusion"))
+                .and_then(Json::as_str)
+                .map(str::to_string)
+        }
+        CiProvider::Gitlab => {
+            let mut url = format!(
+                "{}/api/v4/projects/{}/pipelines?scope=finished&per_page=1",
+                repo.server
+                    .as_deref()
+                    .unwrap_or("https://gitlab.com")
+                    .trim_end_matches('/'),
+                http::encode(&repo.repo)
+            );
+
+            if let Some(branch) = &repo.branch {
+                url.push_str(&format!("&ref={}", http::encode(branch)));
+            }
+
+            let headers: Vec<(&str, &str)> = repo
+                .token
+                .as_deref()
+                .map(|token| ("PRIVATE-TOKEN", token))
+                .into_iter()
+                .collect();
+
+            // `[{"status": "success", ...}]`
+            Json::parse(&http::get_with_headers(&url, &headers)?)?
+                .index(0)
+                .and_then(|pipeline| pipeline.get("status"))
+                .and_then(Json::as_str)
+                .map(str::to_string)
+        }
+    };
+
+    let status = status.ok_or_else(|| anyhow!("no finished build of {}", repo.repo))?;
+
+    Ok(matches!(status.as_str(), "success" | "skipped" | "neutral"))
+}
+
+/// Shows whether the latest builds of some repositories passed, one after the
+/// other, with a check mark or a cross.
+///
+/// It flashes when a build which passed breaks.
+pub struct CiStatus {
+    // Results of every round of checks, with nothing for the builds which
+    // couldn't be checked
+    updates: Receiver<Vec<Option<bool>>>,
+    // Whether the latest build of every repository passed, once it's known
+    builds: Vec<Option<bool>>,
+    frame_count: u32,
+    flash_frames: u32,
+}
+
+impl CiStatus {
+    pub fn new(config: CiConfig) -> Self {
+        let (tx, rx) = channel();
+        let builds = vec![None; config.repos.len()];
+
+        // Fetching takes a while, so it happens on its own thread. Every round
+        // is sent, even when every check failed, so that the thread notices
+        // when the mode is gone
+        thread::spawn(move || loop {
+            let round = config
+                .repos
+                .iter()
+                .map(|repo| match fetch(repo) {
+                    Ok(passed) => Some(passed),
+                    Err(err) => {
+                        log::warn!("CI of {}: {:#}", repo.repo, err);
+                        None
+                    }
+                })
+                .collect();
+
+            if tx.send(round).is_err() {
+                return;
+            }
+
+            thread::sleep(Duration::from_secs(config.interval_secs));
+        });
+
+        Self {
+            updates: rx,
+            builds,
+            frame_count: 0,
+            flash_frames: 0,
+        }
+    }
+}
+
+impl Animation for CiStatus {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+        self.flash_frames = self.flash_frames.saturating_sub(1);
+
+        for round in self.updates.try_iter() {
+            for (i, passed) in round.into_iter().enumerate() {
+                let Some(passed) = passed else {
+                    continue;
+                };
+
+                if self.builds[i] == Some(true) && !passed {
+                    self.flash_frames = FLASH_FRAMES;
+                }
+
+                self.builds[i] = Some(passed);
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.flash_frames > 0 {
+            if self.flash_frames % 2 == 1 {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            } else {
+                icons::draw_icon(&CROSS, Point::zero(), frame);
+            }
+
+            return;
+        }
+
+        let known: Vec<bool> = self.builds.iter().flatten().copied().collect();
+
+        if known.is_empty() {
+            return;
+        }
+
+        let passed = known[(self.frame_count / VIEW_FRAMES) as usize % known.len()];
+
+        icons::draw_icon(if passed { &CHECK } else { &CROSS }, Point::zero(), frame);
+    }
+}
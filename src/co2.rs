@@ -0,0 +1,244 @@
+use crate::{
+    animation::Animation,
+    config::Co2Config,
+    framebuffer::FrameBuffer,
+    icons::{self, WARNING},
+    text::ScrollingText,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use rppal::i2c::I2c;
+use serde::Deserialize;
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Supported I2C CO2 sensors.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Model {
+    /// Sensirion SCD40/SCD41, which measures CO2 directly.
+    Scd40,
+    /// ScioSense CCS811, which estimates CO2 from volatile organic compounds.
+    Ccs811,
+}
+
+// SCD40 commands, which are 16 bits long
+const SCD40_ADDRESS: u16 = 0x62;
+const SCD40_START_PERIODIC_MEASUREMENT: u16 = 0x21b1;
+const SCD40_GET_DATA_READY_STATUS: u16 = 0xe4b8;
+const SCD40_READ_MEASUREMENT: u16 = 0xec05;
+// Time the SCD40 needs to process a command before it can be read
+const SCD40_COMMAND_DELAY: Duration = Duration::from_millis(1);
+
+// CCS811 registers
+const CCS811_ADDRESS: u16 = 0x5a;
+const CCS811_STATUS: u8 = 0x00;
+const CCS811_MEAS_MODE: u8 = 0x01;
+const CCS811_ALG_RESULT_DATA: u8 = 0x02;
+const CCS811_HW_ID: u8 = 0x20;
+const CCS811_APP_START: u8 = 0xf4;
+const CCS811_HW_ID_VALUE: u8 = 0x81;
+const CCS811_DATA_READY: u8 = 0x08;
+// Measurement mode 1, a reading every second
+const CCS811_MODE_1: u8 = 0x10;
+
+/// How often the sensor is checked for a new reading. The SCD40 has one every
+/// 5 seconds and the CCS811 every second.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of frames the warning sign flashes for between two scrolls of the
+/// reading, while above the warning threshold.
+const WARNING_FRAMES: u32 = 10;
+
+/// A CO2 sensor connected to the I2C bus of the Pi.
+pub struct Co2Sensor {
+    i2c: I2c,
+    model: Model,
+}
+
+impl Co2Sensor {
+    pub fn new(model: Model) -> Result<Self> {
+        let mut i2c = I2c::new()?;
+
+        match model {
+            Model::Scd40 => {
+                i2c.set_slave_address(SCD40_ADDRESS)?;
+                i2c.write(&SCD40_START_PERIODIC_MEASUREMENT.to_be_bytes())?;
+            }
+            Model::Ccs811 => {
+                i2c.set_slave_address(CCS811_ADDRESS)?;
+
+                if i2c.smbus_read_byte(CCS811_HW_ID)? != CCS811_HW_ID_VALUE {
+                    return Err(anyhow!("no CCS811 found on the I2C bus"));
+                }
+
+                // The chip starts up in its boot loader
+                i2c.write(&[CCS811_APP_START])?;
+                thread::sleep(Duration::from_millis(1));
+                i2c.smbus_write_byte(CCS811_MEAS_MODE, CCS811_MODE_1)?;
+            }
+        }
+
+        Ok(Self { i2c, model })
+    }
+
+    /// Reads the CO2 concentration in ppm, or `None` if there is no new
+    /// reading since the last one.
+    pub fn read(&mut self) -> Result<Option<u16>> {
+        match self.model {
+            Model::Scd40 => {
+                let mut ready = [0; 3];
+                self.scd40_read(SCD40_GET_DATA_READY_STATUS, &mut ready)?;
+
+                // The lowest 11 bits are all zero while there is no reading
+                if u16::from_be_bytes([ready[0], ready[1]]) & 0x07ff == 0 {
+                    return Ok(None);
+                }
+
+                // CO2, temperature and humidity, each followed by its CRC
+                let mut measurement = [0; 9];
+                self.scd40_read(SCD40_READ_MEASUREMENT, &mut measurement)?;
+
+                Ok(Some(u16::from_be_bytes([measurement[0], measurement[1]])))
+            }
+            Model::Ccs811 => {
+                if self.i2c.smbus_read_byte(CCS811_STATUS)? & CCS811_DATA_READY == 0 {
+                    return Ok(None);
+                }
+
+                let mut result = [0; 2];
+                self.i2c.block_read(CCS811_ALG_RESULT_DATA, &mut result)?;
+
+                Ok(Some(u16::from_be_bytes(result)))
+            }
+        }
+    }
+
+    /// Sends an SCD40 `command` and reads its answer into `buffer`, checking
+    /// the CRC of every word.
+    fn scd40_read(&mut self, command: u16, buffer: &mut [u8]) -> Result<()> {
+        self.i2c.write(&command.to_be_bytes())?;
+        thread::sleep(SCD40_COMMAND_DELAY);
+        self.i2c.read(buffer)?;
+
+        for word in buffer.chunks(3) {
+            if sensirion_crc(&word[..2]) != word[2] {
+                return Err(anyhow!("SCD40 CRC mismatch"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// CRC-8 used by Sensirion sensors, with polynomial 0x31 and initial value
+/// 0xff.
+fn sensirion_crc(data: &[u8]) -> u8 {
+    data.iter().fold(0xff, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// Shows the CO2 concentration measured by an air quality sensor.
+///
+/// The concentration in ppm is scrolled over and over again. Above the
+/// configured limit, a warning sign flashes in between every scroll.
+pub struct Co2 {
+    config: Co2Config,
+    // Every reading, with nothing for the failed ones
+    readings: Receiver<Option<u16>>,
+    ppm: Option<u16>,
+    text: Option<ScrollingText>,
+    // Frames left until the current scroll of the reading is done
+    scroll_frames: u32,
+    warning_frames: u32,
+}
+
+impl Co2 {
+    pub fn new(config: Co2Config) -> Result<Self> {
+        let mut sensor = Co2Sensor::new(config.model)?;
+        let (tx, rx) = channel();
+
+        // I2C transfers block, so the sensor is polled on its own thread
+        thread::spawn(move || loop {
+            // Failed readings are sent too, so that the thread notices when
+            // the mode is gone and closes the device, and are skipped by the
+            // mode, keeping the previous one on screen
+            if tx.send(sensor.read().ok().flatten()).is_err() {
+                return;
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        });
+
+        Ok(Self {
+            config,
+            readings: rx,
+            ppm: None,
+            text: None,
+            scroll_frames: 0,
+            warning_frames: 0,
+        })
+    }
+}
+
+impl Animation for Co2 {
+    fn tick(&mut self) {
+        if let Some(ppm) = self.readings.try_iter().flatten().last() {
+            self.ppm = Some(ppm);
+        }
+
+        if self.warning_frames > 0 {
+            self.warning_frames -= 1;
+            return;
+        }
+
+        if let Some(text) = &mut self.text {
+            text.tick();
+            self.scroll_frames = self.scroll_frames.saturating_sub(1);
+        }
+
+        // The text only changes in between two scrolls, so that a new reading
+        // doesn't make it jump
+        if self.scroll_frames > 0 {
+            return;
+        }
+
+        let Some(ppm) = self.ppm else {
+            return;
+        };
+
+        if self.text.is_some() && ppm >= self.config.warning_ppm {
+            self.warning_frames = WARNING_FRAMES;
+        }
+
+        let text = ScrollingText::new(format!("{ppm}ppm"));
+        self.scroll_frames = text.width();
+        self.text = Some(text);
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.warning_frames > 0 {
+            if self.warning_frames % 2 == 1 {
+                frame.clear_screen();
+
+                icons::draw_icon(&WARNING, Point::zero(), frame);
+            }
+
+            return;
+        }
+
+        if let Some(text) = &self.text {
+            text.draw(frame);
+        }
+    }
+}
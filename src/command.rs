@@ -0,0 +1,59 @@
+use crate::{action::Action, framebuffer::FrameBuffer, text::Priority};
+use anyhow::{anyhow, Result};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Something the display is told to do by one of the ways it can be
+/// controlled, like the HTTP server, the Telegram bot or the IR remote.
+pub enum DisplayCommand {
+    /// Do what the user asked for, like with the buttons.
+    Action(Action),
+    /// Scroll a message once on top of whatever is shown.
+    Announce(String),
+    /// Scroll a message once on top of whatever is shown, before or after
+    /// the other ones depending on how urgent it is.
+    Alert(String, Priority),
+    /// Replace the messages of the text mode with this one.
+    SetText(String),
+    /// Replace the messages of the text mode with these, which are ignored
+    /// if there are none.
+    SetMessages(Vec<String>),
+    /// Set the brightness, from `0` (off) to `255` (brightest).
+    SetBrightness(u8),
+    /// Show this frame instead of the mode, until it is cleared.
+    ShowFrame(FrameBuffer),
+    /// Freeze the animation on the current frame.
+    Pause,
+    /// Let a frozen animation continue.
+    Resume,
+    /// Turn the display back on if it was off.
+    PowerOn,
+    /// Blank the display and stop refreshing it, which saves the most power.
+    PowerOff,
+    /// Go back to the mode after a frame was shown, and drop the
+    /// announcements which weren't scrolled yet.
+    Clear,
+}
+
+/// Sends commands to the drawing thread, which handles them before the next
+/// frame. It can be cloned to give one to everything that controls the
+/// display.
+#[derive(Clone)]
+pub struct DisplayHandle {
+    commands: Sender<DisplayCommand>,
+}
+
+impl DisplayHandle {
+    pub fn send(&self, command: DisplayCommand) -> Result<()> {
+        self.commands
+            .send(command)
+            .map_err(|_| anyhow!("the display isn't running anymore"))
+    }
+}
+
+/// Creates a handle to the display, and the receiving end the drawing thread
+/// takes the commands from.
+pub fn channel() -> (DisplayHandle, Receiver<DisplayCommand>) {
+    let (tx, rx) = mpsc::channel();
+
+    (DisplayHandle { commands: tx }, rx)
+}
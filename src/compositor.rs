@@ -0,0 +1,147 @@
+use crate::{action::Action, animation::Animation, framebuffer::FrameBuffer, input::InputEvent};
+use serde::Deserialize;
+use std::mem;
+
+/// How a layer is merged with the layers below it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Blend {
+    /// Drawn on its own and merged with the layers below, keeping the
+    /// brighter level of every pixel, so the layer can only add to them.
+    Or,
+    /// Drawn straight over the layers below, so the pixels it turns off, or
+    /// a frame it clears, hide what is under them.
+    Replace,
+}
+
+struct Layer {
+    animation: Box<dyn Animation>,
+    blend: Blend,
+    // How much the layer shows, from `0` (not at all) to `u8::MAX` (fully)
+    opacity: u8,
+}
+
+impl Layer {
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match self.blend {
+            Blend::Or => {
+                let mut buffer = FrameBuffer::new();
+
+                self.animation.draw(&mut buffer);
+                buffer.dim(self.opacity);
+                frame.overlay(&buffer);
+            }
+            Blend::Replace if self.opacity == u8::MAX => self.animation.draw(frame),
+            Blend::Replace => {
+                let mut buffer = frame.clone();
+
+                self.animation.draw(&mut buffer);
+                frame.blend(&buffer, self.opacity);
+            }
+        }
+    }
+}
+
+/// Animations stacked as layers, from a base one at the bottom, like a mode,
+/// to the ones on top of it, like status icons.
+///
+/// Replacing the base animation crossfades from the old one to the new one.
+pub struct Compositor {
+    layers: Vec<Layer>,
+    // Base animation being faded out, and the frames left until it's gone
+    fading_out: Option<(Box<dyn Animation>, u32)>,
+    crossfade_frames: u32,
+}
+
+impl Compositor {
+    pub fn new(base: Box<dyn Animation>, crossfade_frames: u32) -> Self {
+        Self {
+            layers: vec![Layer {
+                animation: base,
+                blend: Blend::Replace,
+                opacity: u8::MAX,
+            }],
+            fading_out: None,
+            crossfade_frames,
+        }
+    }
+
+    /// Adds a layer on top of the others.
+    pub fn push(&mut self, animation: Box<dyn Animation>, blend: Blend, opacity: u8) {
+        self.layers.push(Layer {
+            animation,
+            blend,
+            opacity,
+        });
+    }
+
+    /// Replaces the animation of the bottom layer, fading from the old one.
+    pub fn set_base(&mut self, animation: Box<dyn Animation>) {
+        let old = mem::replace(&mut self.layers[0].animation, animation);
+
+        self.fading_out = (self.crossfade_frames > 0).then_some((old, self.crossfade_frames));
+    }
+}
+
+impl Animation for Compositor {
+    fn tick(&mut self) {
+        if let Some((old, frames_left)) = &mut self.fading_out {
+            old.tick();
+            *frames_left -= 1;
+
+            if *frames_left == 0 {
+                self.fading_out = None;
+            }
+        }
+
+        for layer in &mut self.layers {
+            layer.animation.tick();
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let (base, layers) = self.layers.split_first().unwrap();
+
+        match &self.fading_out {
+            Some((old, frames_left)) => {
+                let mut new = frame.clone();
+                base.draw(&mut new);
+                old.draw(frame);
+
+                let progress = self.crossfade_frames - frames_left;
+                frame.blend(
+                    &new,
+                    (progress * u8::MAX as u32 / self.crossfade_frames) as u8,
+                );
+            }
+            None => base.draw(frame),
+        }
+
+        for layer in layers {
+            layer.draw(frame);
+        }
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        for layer in &mut self.layers {
+            layer.animation.handle_input(event);
+        }
+    }
+
+    /// Only the base animation, like a mode, can be played with the buttons.
+    fn is_interactive(&self) -> bool {
+        self.layers[0].animation.is_interactive()
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        for layer in &mut self.layers {
+            layer.animation.handle_action(action);
+        }
+    }
+
+    fn set_messages(&mut self, messages: &[String]) {
+        for layer in &mut self.layers {
+            layer.animation.set_messages(messages);
+        }
+    }
+}
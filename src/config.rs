@@ -0,0 +1,984 @@
+use crate::{
+    action::Action, co2, compositor::Blend, dither::Dithering, matrix, mode::Mode, text::Priority,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io, path::Path, path::PathBuf};
+
+/// Settings read from the TOML configuration file.
+///
+/// Every section is optional, so an empty file (or no file at all) gives the
+/// default configuration.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// What the display shows when it starts.
+    pub startup: StartupConfig,
+    /// IR remote control, only used if the section is present.
+    pub ir: Option<IrConfig>,
+    /// 5-way joystick, only used if the section is present.
+    pub joystick: Option<JoystickConfig>,
+    /// Disk usage mode.
+    pub disk: DiskConfig,
+    /// Network traffic mode.
+    pub network: NetworkConfig,
+    /// Host reachability mode.
+    pub ping: PingConfig,
+    /// SoC temperature mode.
+    pub temperature: TemperatureConfig,
+    /// DS18B20 temperature sensor mode.
+    pub ds18b20: Ds18b20Config,
+    /// DHT22 humidity and temperature mode.
+    pub dht22: Dht22Config,
+    /// HC-SR04 distance sensor, only used if the section is present.
+    pub distance: Option<DistanceConfig>,
+    /// CO2 sensor mode.
+    pub co2: Co2Config,
+    /// Microphone of the audio modes.
+    pub audio: AudioConfig,
+    /// Morse code mode.
+    pub morse: MorseConfig,
+    /// Tally counter mode.
+    pub tally: TallyConfig,
+    /// Log tail mode.
+    pub tail: TailConfig,
+    /// Weather mode, only used if the section is present.
+    pub weather: Option<WeatherConfig>,
+    /// Stock ticker mode.
+    pub stocks: StocksConfig,
+    /// Cryptocurrency ticker mode.
+    pub crypto: CryptoConfig,
+    /// RSS/Atom headlines mode, only used if the section is present.
+    pub rss: Option<RssConfig>,
+    /// GitHub notifications mode, only used if the section is present.
+    pub github: Option<GithubConfig>,
+    /// CI build status mode, only used if the section is present.
+    pub ci: Option<CiConfig>,
+    /// Unread email mode, only used if the section is present.
+    pub imap: Option<ImapConfig>,
+    /// Docker container health mode.
+    pub docker: DockerConfig,
+    /// MQTT value mode, only used if the section is present.
+    pub mqtt: Option<MqttConfig>,
+    /// Values fetched periodically from URLs or commands, for the data mode.
+    pub sources: Vec<SourceConfig>,
+    /// Data source mode, only used if the section is present.
+    pub data: Option<DataConfig>,
+    /// Art-Net or sACN receiver, only used if the section is present.
+    pub dmx: Option<DmxConfig>,
+    /// Open Pixel Control server, only used if the section is present.
+    pub opc: Option<OpcConfig>,
+    /// Serial port taking commands, only used if the section is present.
+    pub uart: Option<UartConfig>,
+    /// Desktop notifications announced on the display, only used if the
+    /// section is present.
+    pub notifications: Option<NotificationsConfig>,
+    /// Telegram bot, only used if the section is present.
+    pub telegram: Option<TelegramConfig>,
+    /// Next calendar event mode, only used if the section is present.
+    pub calendar: Option<CalendarConfig>,
+    /// Scripted animation mode, only used if the section is present.
+    pub script: Option<ScriptConfig>,
+    /// HTTP server for monitoring and control, only used if the section is
+    /// present.
+    pub server: Option<ServerConfig>,
+    /// mDNS advertisement of the HTTP server, only used if the section is
+    /// present.
+    pub mdns: Option<MdnsConfig>,
+    /// How the display is refreshed.
+    pub refresh: RefreshConfig,
+    /// How the layers drawn on top of the mode are merged with it.
+    pub layers: LayersConfig,
+    /// Modes cycled through one after the other, each for its own duration.
+    /// The display stays on the mode selected on the command line if this is
+    /// empty.
+    pub playlist: Vec<PlaylistEntry>,
+}
+
+impl Config {
+    /// Reads the configuration from `path`, or returns the default one if
+    /// there is no path.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+
+        toml::from_str(&contents)
+            .with_context(|| format!("failed to parse config file {}", path.display()))
+    }
+
+    /// Writes `startup` to the startup section of the configuration file at
+    /// `path`, keeping the other sections as they are. Comments in the file
+    /// are lost.
+    pub fn save_startup(path: &Path, startup: &StartupConfig) -> Result<()> {
+        let mut table: toml::Table = match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .parse()
+                .with_context(|| format!("failed to parse config file {}", path.display()))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read config file {}", path.display()))
+            }
+        };
+
+        table.insert("startup".to_string(), toml::Value::try_from(startup)?);
+
+        // The file is replaced at once, so that it's never left half written
+        let temporary = path.with_extension("tmp");
+
+        fs::write(&temporary, toml::to_string(&table)?)
+            .and_then(|()| fs::rename(&temporary, path))
+            .with_context(|| format!("failed to write config file {}", path.display()))
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct StartupConfig {
+    /// Mode shown instead of the one selected on the command line, unless
+    /// there is a playlist.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<Mode>,
+    /// Messages of the text mode, shown instead of the ones given on the
+    /// command line if there are any.
+    pub messages: Vec<String>,
+    /// Brightness, from `0` (off) to `255` (brightest).
+    pub brightness: u8,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            mode: None,
+            messages: Vec::new(),
+            brightness: u8::MAX,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct IrConfig {
+    /// Socket `lircd` listens on.
+    pub socket: PathBuf,
+    /// Action triggered by each key of the remote, by LIRC key name.
+    pub keys: HashMap<String, Action>,
+}
+
+impl Default for IrConfig {
+    fn default() -> Self {
+        let keys = [
+            ("KEY_POWER", Action::TogglePower),
+            ("KEY_OK", Action::TogglePause),
+            ("KEY_PLAYPAUSE", Action::TogglePause),
+            ("KEY_RIGHT", Action::NextMode),
+            ("KEY_NEXT", Action::NextMessage),
+            ("KEY_UP", Action::AdjustBrightness(1)),
+            ("KEY_DOWN", Action::AdjustBrightness(-1)),
+        ];
+
+        Self {
+            socket: PathBuf::from("/var/run/lirc/lircd"),
+            keys: keys
+                .into_iter()
+                .map(|(key, action)| (key.to_string(), action))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct JoystickConfig {
+    /// Input device of the joystick, found automatically for the Sense HAT.
+    pub device: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskConfig {
+    /// Mount point whose usage is shown.
+    pub mount_point: PathBuf,
+    /// Usage, in percent, above which the percentage is scrolled.
+    pub warning_percent: u32,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            mount_point: PathBuf::from("/"),
+            warning_percent: 90,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Network interface whose traffic is shown.
+    pub interface: String,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            interface: "eth0".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct PingConfig {
+    /// Hosts to ping, up to 16 of which can be shown.
+    pub hosts: Vec<String>,
+    /// Seconds to wait between two rounds of pings.
+    pub interval_secs: u64,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            hosts: Vec::new(),
+            interval_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemperatureConfig {
+    /// Temperature, in degrees Celsius, above which the display flashes.
+    pub overheat_celsius: f32,
+}
+
+impl Default for TemperatureConfig {
+    fn default() -> Self {
+        Self {
+            overheat_celsius: 75.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Ds18b20Config {
+    /// 1-Wire id of the sensor, like `28-0316a2794bff`. The first DS18B20 on
+    /// the bus is used if this is missing.
+    pub device: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dht22Config {
+    /// GPIO pin the data line of the sensor is connected to.
+    pub pin: u8,
+}
+
+impl Default for Dht22Config {
+    fn default() -> Self {
+        Self { pin: 18 }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DistanceConfig {
+    /// GPIO pin connected to the trigger pin of the sensor.
+    pub trigger_pin: u8,
+    /// GPIO pin connected to the echo pin of the sensor, through a voltage
+    /// divider since the sensor outputs 5V.
+    pub echo_pin: u8,
+    /// How the distance is shown.
+    #[serde(default)]
+    pub view: DistanceView,
+    /// Distance, in centimeters, below which the bar starts filling up.
+    #[serde(default = "default_range_cm")]
+    pub range_cm: f32,
+    /// Distance, in centimeters, below which the whole matrix flashes.
+    #[serde(default = "default_stop_cm")]
+    pub stop_cm: f32,
+}
+
+fn default_range_cm() -> f32 {
+    100.0
+}
+
+fn default_stop_cm() -> f32 {
+    10.0
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DistanceView {
+    /// Centimeters, as digits.
+    Digits,
+    /// A bar which fills up as things get closer.
+    #[default]
+    Bar,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Co2Config {
+    /// Sensor connected to the I2C bus.
+    pub model: co2::Model,
+    /// CO2 concentration, in ppm, above which a warning flashes.
+    pub warning_ppm: u16,
+}
+
+impl Default for Co2Config {
+    fn default() -> Self {
+        Self {
+            model: co2::Model::Scd40,
+            warning_ppm: 1000,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioConfig {
+    /// ALSA device captured from, like `hw:1,0` for a USB microphone.
+    pub device: String,
+    /// Samples captured per second.
+    pub sample_rate: u32,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            device: "default".to_string(),
+            sample_rate: 16000,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MorseConfig {
+    /// Speed of the code, in words per minute.
+    pub wpm: u32,
+    /// What flashes the code.
+    pub light: MorseLight,
+}
+
+impl Default for MorseConfig {
+    fn default() -> Self {
+        Self {
+            wpm: 15,
+            light: MorseLight::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MorseLight {
+    /// The whole matrix.
+    #[default]
+    Matrix,
+    /// The top right pixel, while the message scrolls by as text.
+    Corner,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct TallyConfig {
+    /// File the count is saved to.
+    pub path: PathBuf,
+}
+
+impl Default for TallyConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("/var/lib/led-matrix/tally"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct TailConfig {
+    /// File whose new lines are shown, unless one is given on the command
+    /// line.
+    pub file: PathBuf,
+    /// Words of which the lines have to contain at least one to be shown,
+    /// ignoring case. Every line is shown if this is empty.
+    pub keywords: Vec<String>,
+    /// Most lines shown in a minute. The lines coming faster are skipped, as
+    /// are those coming while too many are waiting to be scrolled.
+    pub max_lines_per_minute: u32,
+}
+
+impl Default for TailConfig {
+    fn default() -> Self {
+        Self {
+            file: PathBuf::from("/var/log/syslog"),
+            keywords: Vec::new(),
+            max_lines_per_minute: 6,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WeatherConfig {
+    /// Latitude of the place whose weather is shown, in degrees.
+    pub latitude: f64,
+    /// Longitude of the place whose weather is shown, in degrees.
+    pub longitude: f64,
+    /// Minutes to wait between two fetches of the weather.
+    #[serde(default = "default_weather_interval_mins")]
+    pub interval_mins: u64,
+}
+
+fn default_weather_interval_mins() -> u64 {
+    15
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct StocksConfig {
+    /// Symbols of the stocks whose prices are shown.
+    pub symbols: Vec<String>,
+    /// Minutes to wait between two fetches of the prices.
+    pub interval_mins: u64,
+    /// Where the prices are fetched from.
+    pub provider: StockProvider,
+}
+
+impl Default for StocksConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec!["AAPL".to_string(), "MSFT".to_string()],
+            interval_mins: 5,
+            provider: StockProvider::Yahoo,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "name", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum StockProvider {
+    /// Yahoo Finance, which needs no API key.
+    Yahoo,
+    /// Finnhub, with a free API key from finnhub.io.
+    Finnhub { api_key: String },
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CryptoConfig {
+    /// CoinGecko ids of the coins whose prices are shown, like `bitcoin`.
+    pub coins: Vec<String>,
+    /// Currency the prices are shown in, like `usd` or `eur`.
+    pub currency: String,
+    /// Seconds to wait between two fetches of the prices.
+    pub interval_secs: u64,
+}
+
+impl Default for CryptoConfig {
+    fn default() -> Self {
+        Self {
+            coins: vec!["bitcoin".to_string(), "ethereum".to_string()],
+            currency: "usd".to_string(),
+            interval_secs: 120,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RssConfig {
+    /// URL of the RSS or Atom feed.
+    pub url: String,
+    /// Number of the latest headlines which are shown.
+    #[serde(default = "default_rss_headlines")]
+    pub headlines: usize,
+    /// Minutes to wait between two fetches of the feed.
+    #[serde(default = "default_rss_interval_mins")]
+    pub interval_mins: u64,
+}
+
+fn default_rss_headlines() -> usize {
+    5
+}
+
+fn default_rss_interval_mins() -> u64 {
+    15
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GithubConfig {
+    /// Personal access token with the `notifications` scope.
+    pub token: String,
+    /// Seconds to wait between two checks of the notifications.
+    #[serde(default = "default_github_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_github_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CiConfig {
+    /// Repositories whose latest build is shown, one after the other.
+    pub repos: Vec<CiRepo>,
+    /// Seconds to wait between two checks of the builds.
+    #[serde(default = "default_ci_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_ci_interval_secs() -> u64 {
+    120
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CiRepo {
+    pub provider: CiProvider,
+    /// `owner/name` of the repository on GitHub, or path of the project on
+    /// GitLab, like `group/project`.
+    pub repo: String,
+    /// Branch whose builds are shown, or every branch if there is none.
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Token to read the builds with, which public repositories don't need.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Address of a self-hosted GitLab, if it isn't `https://gitlab.com`.
+    #[serde(default)]
+    pub server: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CiProvider {
+    /// GitHub Actions workflow runs.
+    Github,
+    /// GitLab CI pipelines.
+    Gitlab,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ImapConfig {
+    /// Host name of the IMAP server, with a port if it isn't 993, like
+    /// `imap.example.com`. The connection always uses TLS.
+    pub server: String,
+    pub user: String,
+    pub password: String,
+    /// Mailbox whose unread messages are counted.
+    #[serde(default = "default_imap_mailbox")]
+    pub mailbox: String,
+    /// Seconds to wait between two checks of the mailbox.
+    #[serde(default = "default_imap_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_imap_mailbox() -> String {
+    "INBOX".to_string()
+}
+
+fn default_imap_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DockerConfig {
+    /// Socket of the Docker daemon.
+    pub socket: PathBuf,
+    /// Seconds to wait between two checks of the containers.
+    pub interval_secs: u64,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self {
+            socket: PathBuf::from("/var/run/docker.sock"),
+            interval_secs: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MqttConfig {
+    /// Host name and port of the broker, like `homeassistant.local:1883`.
+    /// The connection doesn't use TLS.
+    pub broker: String,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Topic whose messages are shown, which can have wildcards.
+    pub topic: String,
+    /// JSONPath of the value shown out of every message, like
+    /// `$.temperature`, or `$` for the whole message.
+    #[serde(default = "default_mqtt_path")]
+    pub path: String,
+    /// How the value is shown, with `{}` replaced by it, or `{:.1}` by a
+    /// number with a decimal, like `{:.1}C`.
+    #[serde(default = "default_mqtt_format")]
+    pub format: String,
+}
+
+fn default_mqtt_path() -> String {
+    "$".to_string()
+}
+
+fn default_mqtt_format() -> String {
+    "{}".to_string()
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SourceConfig {
+    /// Name the value is used by in templates, like `{temperature}`.
+    pub name: String,
+    /// URL the value is fetched from.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Shell command whose output is the value, run instead of fetching a
+    /// URL, like `cut -d' ' -f1 /proc/loadavg`.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// JSONPath of the value in the response, like `$.main.temp`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Regex matching the value in the response, like `Temp: ([0-9.]+)`,
+    /// which is what the group matches if there is one. The first line of
+    /// the response is the value if there is neither a path nor a regex.
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// Seconds to wait between two fetches or runs of the command. Commands
+    /// still running after this long are killed.
+    #[serde(default = "default_source_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_source_interval_secs() -> u64 {
+    300
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DataConfig {
+    /// What is shown, with the names of the data sources in braces replaced
+    /// by their values, like `{temperature:.1}C`.
+    pub template: String,
+    #[serde(default)]
+    pub view: DataView,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataView {
+    /// A line of text, scrolled over and over again.
+    #[default]
+    Text,
+    /// A number, rounded, which is what values like counts or temperatures
+    /// fit best as.
+    Number,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TelegramConfig {
+    /// Token of the bot, as given by @BotFather.
+    pub token: String,
+    /// Ids of the chats the bot takes messages from. Messages from every chat
+    /// are taken if this is empty.
+    #[serde(default)]
+    pub allowed_chats: Vec<i64>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CalendarConfig {
+    /// URL of the calendar, in iCalendar format.
+    pub url: String,
+    /// Minutes to wait between two fetches of the calendar.
+    #[serde(default = "default_calendar_interval_mins")]
+    pub interval_mins: u64,
+}
+
+fn default_calendar_interval_mins() -> u64 {
+    15
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptConfig {
+    /// Expression giving the brightness of every pixel, from 0 to 1.
+    pub expression: String,
+    /// Values the expression can use by name, to tweak it without touching
+    /// the expression itself.
+    #[serde(default)]
+    pub variables: HashMap<String, f64>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Address and port the server listens on.
+    pub address: String,
+    /// Token the commands, like `/text` or `/power/off`, need as the `token`
+    /// query parameter or a bearer token, and the dashboard needs as a bearer
+    /// token to save the settings. Without one, anyone who can reach the
+    /// server can send commands, and the settings can't be saved.
+    pub token: Option<String>,
+    /// How the payloads posted to `/webhook` are announced, which is only
+    /// taken if the section is present.
+    pub webhook: Option<WebhookConfig>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// Token the senders have to give, as the `token` query parameter or a
+    /// bearer token.
+    pub token: String,
+    /// Rules turning a payload into an announcement, of which the first one
+    /// which matches is used.
+    #[serde(default)]
+    pub rules: Vec<WebhookRule>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookRule {
+    /// JSONPath which has to have a value in the payload for the rule to
+    /// match, like `$.pull_request`. Every payload matches if there is none.
+    #[serde(default)]
+    pub when: Option<String>,
+    /// What the value at `when` has to be, like `firing`, if it matters.
+    #[serde(default)]
+    pub equals: Option<String>,
+    /// What is announced, with JSONPaths in braces replaced by the values
+    /// in the payload, like `{$.repository.name}: {$.action}`.
+    pub message: String,
+    #[serde(default)]
+    pub priority: Priority,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DmxConfig {
+    /// Protocol DMX is received with.
+    pub protocol: DmxProtocol,
+    /// Universe the pixels are in. Art-Net counts universes from 0, while
+    /// sACN counts them from 1.
+    pub universe: u16,
+    /// Channel of the top left pixel, counting from 1. The other pixels
+    /// follow row by row, on the next 63 channels.
+    pub start_channel: u16,
+}
+
+impl Default for DmxConfig {
+    fn default() -> Self {
+        Self {
+            protocol: DmxProtocol::ArtNet,
+            universe: 0,
+            start_channel: 1,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DmxProtocol {
+    /// Art-Net, on UDP port 6454.
+    ArtNet,
+    /// sACN (E1.31), on UDP port 5568.
+    Sacn,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct OpcConfig {
+    /// Address and port the server listens on.
+    pub address: String,
+    /// Channel the display answers to, besides the broadcast channel 0.
+    pub channel: u8,
+}
+
+impl Default for OpcConfig {
+    fn default() -> Self {
+        Self {
+            address: "0.0.0.0:7890".to_string(),
+            channel: 1,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    /// Address of the D-Bus bus the notifications are sent on, like
+    /// `unix:path=/run/user/1000/bus`. The session bus of the display is used
+    /// if this is missing.
+    pub address: Option<String>,
+    /// Names of the apps whose notifications are announced, or every app if
+    /// this is empty.
+    pub apps: Vec<String>,
+    /// Names of the apps whose notifications are never announced.
+    pub ignored_apps: Vec<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct UartConfig {
+    /// Serial device the commands come from.
+    pub path: PathBuf,
+    /// Speed of the serial port, in bits per second.
+    pub baud_rate: u32,
+}
+
+impl Default for UartConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("/dev/serial0"),
+            baud_rate: 115_200,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MdnsConfig {
+    /// Name the display is advertised as, which is the hostname if this is
+    /// missing.
+    pub name: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            address: "0.0.0.0:8080".to_string(),
+            token: None,
+            webhook: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    /// Real-time `SCHED_FIFO` priority of the refresh thread, from 1 to 99,
+    /// so that other processes can't preempt it in the middle of a refresh.
+    /// It is scheduled normally if this is missing.
+    pub realtime_priority: Option<u8>,
+    /// CPU core the refresh thread is pinned to, counting from 0, which is
+    /// best kept free of other processes with the `isolcpus` kernel
+    /// parameter. The thread can run on any core if this is missing.
+    pub cpu: Option<usize>,
+    /// How the pins of the matrix are written.
+    pub backend: matrix::Backend,
+    /// Whether rows are lit for longer the more of their LEDs are lit, and
+    /// empty rows are waited for, so that every LED looks as bright no matter
+    /// how many others are lit. This lowers the refresh rate of sparse
+    /// frames.
+    pub normalize_brightness: bool,
+    /// Dead time after every row is turned off, in microseconds, during
+    /// which the columns are turned off too. This stops the next row from
+    /// faintly showing the previous one, at the cost of refresh rate.
+    pub blanking_us: u64,
+    /// Hardware PWM which dims the display, only used if the section is
+    /// present. The frames are dimmed otherwise.
+    pub pwm_dimming: Option<PwmDimmingConfig>,
+    /// Dithering which turns every pixel fully on or off, for panels which
+    /// can't show shades of gray well. Shades are shown with PWM if this is
+    /// missing.
+    pub dithering: Option<Dithering>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct PwmDimmingConfig {
+    /// Hardware PWM channel, 0 or 1, which has to be routed to a pin with
+    /// the `pwm` or `pwm-2chan` overlay.
+    pub channel: u8,
+    /// Frequency of the PWM signal, high enough not to be seen.
+    pub frequency_hz: f64,
+    /// Whether the LEDs are enabled while the signal is low rather than high.
+    pub active_low: bool,
+}
+
+impl Default for PwmDimmingConfig {
+    fn default() -> Self {
+        Self {
+            channel: 0,
+            frequency_hz: 10_000.0,
+            active_low: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayersConfig {
+    /// Twinkling pixels of `--sparkle`.
+    pub sparkle: LayerConfig,
+    /// Pulsing of `--beat-pulse`, which needs to be drawn over the layers
+    /// below it to dim them.
+    pub beat_pulse: LayerConfig,
+    /// Warning icon flashed when the Pi is throttled.
+    pub throttle_warning: LayerConfig,
+    /// IP addresses scrolled on startup.
+    pub startup_ip: LayerConfig,
+    /// Number of frames switching modes fades from one to the other for, or
+    /// 0 to switch at once.
+    pub crossfade_frames: u32,
+}
+
+impl Default for LayersConfig {
+    fn default() -> Self {
+        Self {
+            sparkle: LayerConfig {
+                blend: Blend::Or,
+                ..LayerConfig::default()
+            },
+            beat_pulse: LayerConfig::default(),
+            throttle_warning: LayerConfig::default(),
+            startup_ip: LayerConfig::default(),
+            crossfade_frames: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayerConfig {
+    /// How the layer is merged with the ones below it.
+    pub blend: Blend,
+    /// How much the layer shows, from 0 (not at all) to 255 (fully).
+    pub opacity: u8,
+}
+
+impl Default for LayerConfig {
+    fn default() -> Self {
+        Self {
+            blend: Blend::Replace,
+            opacity: u8::MAX,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct PlaylistEntry {
+    /// Mode to show.
+    pub mode: Mode,
+    /// Seconds the mode is shown for before switching to the next one.
+    pub duration_secs: u64,
+}
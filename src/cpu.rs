@@ -0,0 +1,91 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, WIDTH},
+    interval::Interval,
+    widget::BarGraph,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use std::{collections::VecDeque, fs, time::Duration};
+
+/// How often the CPU load is sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// System monitor showing the recent CPU load as a bar graph.
+///
+/// Every column is one sample, with the newest one on the right.
+pub struct CpuLoad {
+    // Load samples between 0 and 1, the newest one at the back
+    samples: VecDeque<f32>,
+    // Busy and total time counters from the previous sample
+    last_times: Option<(u64, u64)>,
+    interval: Interval,
+}
+
+impl CpuLoad {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(WIDTH),
+            last_times: None,
+            interval: Interval::new(SAMPLE_INTERVAL),
+        }
+    }
+
+    fn sample(&mut self) -> Result<()> {
+        let (busy, total) = read_cpu_times()?;
+
+        if let Some((last_busy, last_total)) = self.last_times {
+            let elapsed = total.saturating_sub(last_total).max(1);
+            let load = busy.saturating_sub(last_busy) as f32 / elapsed as f32;
+
+            if self.samples.len() == WIDTH {
+                self.samples.pop_front();
+            }
+
+            self.samples.push_back(load.clamp(0.0, 1.0));
+        }
+
+        self.last_times = Some((busy, total));
+
+        Ok(())
+    }
+}
+
+/// Reads the busy and total time counters of all CPUs combined, in clock
+/// ticks since boot.
+fn read_cpu_times() -> Result<(u64, u64)> {
+    let stat = fs::read_to_string("/proc/stat")?;
+
+    // The first line adds up every CPU:
+    // `cpu user nice system idle iowait irq softirq steal guest guest_nice`
+    let times = stat
+        .lines()
+        .next()
+        .filter(|line| line.starts_with("cpu "))
+        .ok_or_else(|| anyhow!("unexpected /proc/stat format"))?
+        .split_whitespace()
+        .skip(1)
+        .map(str::parse)
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    // Guest time is already included in the user times
+    let total: u64 = times.iter().take(8).sum();
+    let idle = times.iter().skip(3).take(2).sum::<u64>();
+
+    Ok((total - idle, total))
+}
+
+impl Animation for CpuLoad {
+    fn tick(&mut self) {
+        if self.interval.is_due() {
+            // Skip this sample if the counters can't be read
+            let _ = self.sample();
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Ok(()) = BarGraph::new(self.samples.iter().copied())
+            .with_max(1.0)
+            .draw(frame);
+    }
+}
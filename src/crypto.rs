@@ -0,0 +1,75 @@
+use crate::{
+    config::CryptoConfig,
+    http,
+    json::Json,
+    ticker::{Segment, Ticker},
+};
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+/// CoinGecko's price API, which needs no API key.
+const API_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// Price of a coin.
+#[derive(Clone, Copy, Debug)]
+pub struct CoinPrice {
+    pub price: f64,
+    /// Change of the price over the last 24 hours, in percent.
+    pub change_percent: f64,
+}
+
+/// Fetches the price of every configured coin, in the same order.
+fn fetch(config: &CryptoConfig) -> Result<Vec<CoinPrice>> {
+    let currency = config.currency.to_lowercase();
+    let url = format!(
+        "{}?ids={}&vs_currencies={}&include_24hr_change=true",
+        API_URL,
+        config.coins.join(","),
+        currency
+    );
+
+    let response = Json::parse(&http::get(&url)?)?;
+
+    config
+        .coins
+        .iter()
+        .map(|coin| {
+            let field = |name: &str| {
+                response
+                    .get(coin)
+                    .and_then(|prices| prices.get(name))
+                    .and_then(Json::as_f64)
+                    .ok_or_else(|| anyhow!("no {} for {} in the answer of CoinGecko", name, coin))
+            };
+
+            Ok(CoinPrice {
+                price: field(&currency)?,
+                change_percent: field(&format!("{currency}_24h_change"))?,
+            })
+        })
+        .collect()
+}
+
+/// Creates a ticker scrolling the price of every configured coin, with a
+/// triangle and the percentage it went up or down over the last day.
+pub fn ticker(config: CryptoConfig) -> Ticker {
+    Ticker::new(
+        "Crypto",
+        Duration::from_secs(config.interval_secs),
+        move || {
+            let prices = fetch(&config)?;
+            let mut segments = Vec::new();
+
+            for (coin, price) in config.coins.iter().zip(prices) {
+                segments.push(Segment::Text(format!("{} ", coin.to_uppercase())));
+                segments.push(Segment::change(price.change_percent));
+                segments.push(Segment::Text(format!(
+                    " {:.2} {:+.1}%  ",
+                    price.price, price.change_percent
+                )));
+            }
+
+            Ok(segments)
+        },
+    )
+}
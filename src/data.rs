@@ -0,0 +1,74 @@
+use crate::{
+    animation::Animation,
+    config::{DataConfig, DataView},
+    framebuffer::FrameBuffer,
+    source::Values,
+    ticker::{Segment, Ticker},
+    widget::show_number,
+};
+use embedded_graphics::prelude::*;
+use std::sync::mpsc::{channel, Sender};
+
+/// Shows the values of the data sources through a template, either scrolled
+/// as a line of text or as a number.
+pub struct DataDisplay {
+    config: DataConfig,
+    values: Values,
+    // Template with the latest values
+    line: String,
+    lines: Sender<Option<Vec<Segment>>>,
+    ticker: Ticker,
+    // Frames since the line changed, for the numbers which are animated
+    frame_count: u32,
+}
+
+impl DataDisplay {
+    pub fn new(config: DataConfig, values: Values) -> Self {
+        let (tx, rx) = channel();
+
+        Self {
+            config,
+            values,
+            line: String::new(),
+            lines: tx,
+            ticker: Ticker::with_updates(rx),
+            frame_count: 0,
+        }
+    }
+}
+
+impl Animation for DataDisplay {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        let line = self.values.render(&self.config.template);
+
+        if line != self.line {
+            // The ticker holds the receiver, so this can't fail
+            let _ = self.lines.send(Some(vec![Segment::Text(line.clone())]));
+
+            self.line = line;
+            self.frame_count = 0;
+        }
+
+        self.ticker.tick();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let number = match self.config.view {
+            DataView::Number => self.line.trim().parse::<f64>().ok(),
+            DataView::Text => None,
+        };
+
+        // Values which aren't numbers, like while they are still missing,
+        // are shown as text anyway
+        match number {
+            Some(number) => {
+                let Ok(()) = show_number(number.round() as i64)
+                    .at_frame(self.frame_count)
+                    .draw(frame);
+            }
+            None => self.ticker.draw(frame),
+        }
+    }
+}
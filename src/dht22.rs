@@ -0,0 +1,189 @@
+use crate::{
+    animation::Animation,
+    config::Dht22Config,
+    framebuffer::FrameBuffer,
+    icons::{self, DROP, THERMOMETER},
+    widget::show_number,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use rppal::gpio::{Gpio, IoPin, Level, Mode, PullUpDown};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the sensor is read. It can't be read more than once every two
+/// seconds.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long the data line is pulled low to ask the sensor for a reading.
+const START_SIGNAL: Duration = Duration::from_millis(2);
+
+/// Longest the sensor takes to change the level of the data line before a
+/// reading is given up on.
+const TIMEOUT: Duration = Duration::from_micros(200);
+
+/// High pulses longer than this are ones, and shorter ones are zeros. The
+/// sensor sends 26-28µs for a zero and 70µs for a one.
+const ONE_THRESHOLD: Duration = Duration::from_micros(48);
+
+/// Number of frames each of the icons and the readings are shown for.
+const VIEW_FRAMES: u32 = 10;
+
+/// A reading of the sensor.
+#[derive(Clone, Copy, Debug)]
+pub struct Reading {
+    /// Temperature, in degrees Celsius.
+    pub celsius: f32,
+    /// Relative humidity, in percent.
+    pub humidity: f32,
+}
+
+/// A DHT22 (AM2302) temperature and humidity sensor, bit-banged on a GPIO pin.
+pub struct Dht22 {
+    pin: IoPin,
+}
+
+impl Dht22 {
+    pub fn new(pin: u8) -> Result<Self> {
+        let mut pin = Gpio::new()?.get(pin)?.into_io(Mode::Input);
+
+        // The data line idles high
+        pin.set_pullupdown(PullUpDown::PullUp);
+
+        Ok(Self { pin })
+    }
+
+    /// Reads the temperature and humidity.
+    ///
+    /// The sensor's timing is tight enough that some readings fail if the
+    /// thread is preempted, so callers should expect to retry.
+    pub fn read(&mut self) -> Result<Reading> {
+        // Start signal, after which the sensor answers with 80µs low and 80µs
+        // high before sending the data
+        self.pin.set_mode(Mode::Output);
+        self.pin.set_low();
+        thread::sleep(START_SIGNAL);
+        self.pin.set_mode(Mode::Input);
+
+        self.wait_for(Level::Low)?;
+        self.wait_for(Level::High)?;
+        self.wait_for(Level::Low)?;
+
+        // Every bit is 50µs low followed by a high pulse whose length gives
+        // its value, most significant bit first
+        let mut data = [0u8; 5];
+
+        for bit in 0..40 {
+            self.wait_for(Level::High)?;
+
+            if self.wait_for(Level::Low)? > ONE_THRESHOLD {
+                data[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+
+        let checksum = data[..4]
+            .iter()
+            .fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+
+        if checksum != data[4] {
+            return Err(anyhow!("DHT22 checksum mismatch"));
+        }
+
+        // Both values are in tenths, with the temperature's sign in its top
+        // bit
+        let humidity = u16::from_be_bytes([data[0], data[1]]) as f32 / 10.0;
+        let celsius = u16::from_be_bytes([data[2] & 0x7f, data[3]]) as f32 / 10.0;
+        let celsius = if data[2] & 0x80 != 0 {
+            -celsius
+        } else {
+            celsius
+        };
+
+        Ok(Reading { celsius, humidity })
+    }
+
+    /// Waits until the data line is at `level`, returning how long that
+    /// took.
+    fn wait_for(&self, level: Level) -> Result<Duration> {
+        let start = Instant::now();
+
+        while self.pin.read() != level {
+            if start.elapsed() > TIMEOUT {
+                return Err(anyhow!("DHT22 timed out"));
+            }
+        }
+
+        Ok(start.elapsed())
+    }
+}
+
+/// Shows the temperature and humidity measured by a DHT22.
+///
+/// It cycles through a thermometer, the temperature in degrees Celsius, a
+/// drop and the relative humidity in percent.
+pub struct Humidity {
+    // Every reading, with nothing for the failed ones
+    readings: Receiver<Option<Reading>>,
+    reading: Option<Reading>,
+    frame_count: u32,
+}
+
+impl Humidity {
+    pub fn new(config: Dht22Config) -> Result<Self> {
+        let mut sensor = Dht22::new(config.pin)?;
+        let (tx, rx) = channel();
+
+        // Reading busy-waits on the data line, so it happens on its own
+        // thread
+        thread::spawn(move || loop {
+            // Failed readings are sent too, so that the thread notices when
+            // the mode is gone and frees the pin, and are skipped by the
+            // mode, keeping the previous one on screen
+            if tx.send(sensor.read().ok()).is_err() {
+                return;
+            }
+
+            thread::sleep(SAMPLE_INTERVAL);
+        });
+
+        Ok(Self {
+            readings: rx,
+            reading: None,
+            frame_count: 0,
+        })
+    }
+}
+
+impl Animation for Humidity {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if let Some(reading) = self.readings.try_iter().flatten().last() {
+            self.reading = Some(reading);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(reading) = self.reading else {
+            return;
+        };
+
+        match self.frame_count / VIEW_FRAMES % 4 {
+            0 => icons::draw_icon(&THERMOMETER, Point::zero(), frame),
+            1 => {
+                let Ok(()) = show_number(reading.celsius.round() as i64)
+                    .at_frame(self.frame_count % VIEW_FRAMES)
+                    .draw(frame);
+            }
+            2 => icons::draw_icon(&DROP, Point::zero(), frame),
+            _ => {
+                let Ok(()) = show_number(reading.humidity.round() as i64)
+                    .at_frame(self.frame_count % VIEW_FRAMES)
+                    .draw(frame);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    icons::{self, DICE},
+    input::InputEvent,
+};
+use embedded_graphics::prelude::*;
+use rand::Rng;
+
+/// Number of frames the die tumbles for before settling on a face.
+const TUMBLE_FRAMES: u32 = 8;
+
+/// A die which is rolled whenever any button is pressed.
+///
+/// While rolling it tumbles through random faces for a moment before settling
+/// on the result.
+pub struct Dice {
+    // Index into `DICE` of the face currently shown
+    face: usize,
+    tumble_frames: u32,
+    // Small offset which makes the die shake while tumbling
+    shake: Point,
+}
+
+impl Dice {
+    pub fn new() -> Self {
+        Self {
+            face: rand::thread_rng().gen_range(0..DICE.len()),
+            tumble_frames: 0,
+            shake: Point::zero(),
+        }
+    }
+}
+
+impl Animation for Dice {
+    fn tick(&mut self) {
+        if self.tumble_frames == 0 {
+            return;
+        }
+
+        self.tumble_frames -= 1;
+
+        let mut rng = rand::thread_rng();
+
+        if self.tumble_frames == 0 {
+            self.shake = Point::zero();
+        } else {
+            self.shake = Point::new(rng.gen_range(-1..=1), rng.gen_range(-1..=1));
+        }
+
+        // Always show a different face, otherwise the die looks stuck
+        let offset = rng.gen_range(1..DICE.len());
+
+        self.face = (self.face + offset) % DICE.len();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        icons::draw_icon(&DICE[self.face], self.shake, frame);
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        if !matches!(event, InputEvent::Pressed(_)) {
+            return;
+        }
+
+        self.tumble_frames = TUMBLE_FRAMES;
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config::PwmDimmingConfig;
+use anyhow::{anyhow, Result};
+use rppal::pwm::{Channel, Polarity, Pwm};
+
+/// Dims the whole display with one of the Pi's hardware PWM channels, wired
+/// to a line which enables the LEDs, like the gate of a transistor on their
+/// supply.
+///
+/// Unlike dimming the frame, this doesn't take any CPU time or refresh
+/// passes, so the refresh rate stays the same at any brightness.
+pub struct Dimmer {
+    pwm: Pwm,
+}
+
+impl Dimmer {
+    pub fn new(config: &PwmDimmingConfig) -> Result<Self> {
+        let channel = match config.channel {
+            0 => Channel::Pwm0,
+            1 => Channel::Pwm1,
+            channel => return Err(anyhow!("there is no PWM channel {}", channel)),
+        };
+
+        let polarity = if config.active_low {
+            Polarity::Inverse
+        } else {
+            Polarity::Normal
+        };
+
+        let pwm = Pwm::with_frequency(channel, config.frequency_hz, 1.0, polarity, true)?;
+
+        Ok(Self { pwm })
+    }
+
+    /// Sets the brightness of the display, from `0` to `u8::MAX`.
+    pub fn set_brightness(&self, brightness: u8) -> Result<()> {
+        self.pwm
+            .set_duty_cycle(brightness as f64 / u8::MAX as f64)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    animation::Animation,
+    config::DiskConfig,
+    framebuffer::{FrameBuffer, HEIGHT},
+    interval::Interval,
+    text::ScrollingText,
+    widget::ProgressBar,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt, path::Path, time::Duration};
+
+/// How often the disk usage is read.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of frames the bar is shown for between two scrolls of the
+/// percentage, while above the warning threshold.
+const BAR_FRAMES: u32 = 15;
+
+/// System monitor showing how full a mount point is.
+///
+/// The fill level is shown as a vertical bar. Once it crosses the warning
+/// threshold, the percentage is also scrolled in between.
+pub struct Disk {
+    config: DiskConfig,
+    // Fraction of the disk in use, once it was read
+    usage: Option<f32>,
+    interval: Interval,
+    // Scrolling percentage while above the threshold, and for how many more
+    // frames it is shown
+    warning: Option<(ScrollingText, u32)>,
+    bar_frames: u32,
+}
+
+impl Disk {
+    pub fn new(config: DiskConfig) -> Self {
+        Self {
+            config,
+            usage: None,
+            interval: Interval::new(SAMPLE_INTERVAL),
+            warning: None,
+            bar_frames: 0,
+        }
+    }
+}
+
+/// Reads the fraction of the file system mounted at `mount_point` which is in
+/// use, as seen by unprivileged users.
+fn read_usage(mount_point: &Path) -> Result<f32> {
+    let path = CString::new(mount_point.as_os_str().as_bytes())?;
+    let mut stats = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` is a valid C string and `stats` is large enough for the
+    // result, which is only read if the call succeeded
+    let stats = unsafe {
+        if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+            return Err(anyhow!(
+                "failed to read the usage of {}: {}",
+                mount_point.display(),
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        stats.assume_init()
+    };
+
+    // Blocks reserved for root count as used, since nobody else can use them
+    let total = stats.f_blocks as f64;
+    let available = stats.f_bavail as f64;
+
+    Ok((1.0 - available / total.max(1.0)) as f32)
+}
+
+impl Animation for Disk {
+    fn tick(&mut self) {
+        if self.interval.is_due() {
+            // Keep showing the previous reading if it can't be read
+            if let Ok(usage) = read_usage(&self.config.mount_point) {
+                self.usage = Some(usage);
+            }
+        }
+
+        if let Some((text, frames)) = &mut self.warning {
+            text.tick();
+            *frames -= 1;
+
+            if *frames == 0 {
+                self.warning = None;
+                self.bar_frames = BAR_FRAMES;
+            }
+
+            return;
+        }
+
+        self.bar_frames = self.bar_frames.saturating_sub(1);
+
+        let Some(usage) = self.usage else {
+            return;
+        };
+
+        let percent = (usage * 100.0).round() as u32;
+
+        if percent >= self.config.warning_percent && self.bar_frames == 0 {
+            let message = format!("{} {}%", self.config.mount_point.display(), percent);
+            let text = ScrollingText::new(message);
+            let frames = text.width();
+
+            self.warning = Some((text, frames));
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some((text, _)) = &self.warning {
+            text.draw(frame);
+            return;
+        }
+
+        let Some(usage) = self.usage else {
+            return;
+        };
+
+        let Ok(()) = ProgressBar::vertical(usage)
+            .in_area(Rectangle::new(
+                Point::new(2, 0),
+                Size::new(4, HEIGHT as u32),
+            ))
+            .outlined()
+            .draw(frame);
+    }
+}
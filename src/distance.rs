@@ -0,0 +1,147 @@
+use crate::{
+    animation::Animation,
+    config::{DistanceConfig, DistanceView},
+    framebuffer::FrameBuffer,
+    widget::{show_number, ProgressBar},
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use rppal::gpio::{Gpio, InputPin, Level, OutputPin};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the distance is measured. The sensor needs at least 60ms
+/// between two measurements so that old echoes die out.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Length of the pulse on the trigger pin which starts a measurement.
+const TRIGGER_PULSE: Duration = Duration::from_micros(10);
+
+/// Longest the echo pulse can take to start or end before the measurement is
+/// given up on. The sensor's range of 4m gives echoes of about 23ms.
+const TIMEOUT: Duration = Duration::from_millis(30);
+
+/// Microseconds it takes sound to travel to something 1cm away and back.
+const MICROS_PER_CM: f32 = 58.0;
+
+/// An HC-SR04 ultrasonic distance sensor.
+pub struct HcSr04 {
+    trigger: OutputPin,
+    echo: InputPin,
+}
+
+impl HcSr04 {
+    pub fn new(trigger: u8, echo: u8) -> Result<Self> {
+        let gpio = Gpio::new()?;
+
+        Ok(Self {
+            trigger: gpio.get(trigger)?.into_output_low(),
+            echo: gpio.get(echo)?.into_input(),
+        })
+    }
+
+    /// Measures the distance to the closest thing in front of the sensor, in
+    /// centimeters.
+    pub fn measure(&mut self) -> Result<f32> {
+        self.trigger.set_high();
+        thread::sleep(TRIGGER_PULSE);
+        self.trigger.set_low();
+
+        // The length of the echo pulse is the time the sound took to come
+        // back
+        self.wait_for(Level::High)?;
+        let echo = self.wait_for(Level::Low)?;
+
+        Ok(echo.as_micros() as f32 / MICROS_PER_CM)
+    }
+
+    /// Waits until the echo pin is at `level`, returning how long that took.
+    fn wait_for(&self, level: Level) -> Result<Duration> {
+        let start = Instant::now();
+
+        while self.echo.read() != level {
+            if start.elapsed() > TIMEOUT {
+                return Err(anyhow!("HC-SR04 timed out"));
+            }
+        }
+
+        Ok(start.elapsed())
+    }
+}
+
+/// Shows the distance measured by an HC-SR04, like a parking sensor.
+///
+/// The distance is either shown in centimeters, or as a bar which fills up
+/// as things get closer. Everything closer than the stop distance makes the
+/// whole matrix flash.
+pub struct Distance {
+    config: DistanceConfig,
+    measurements: Receiver<Option<f32>>,
+    cm: Option<f32>,
+    frame_count: u32,
+}
+
+impl Distance {
+    pub fn new(config: DistanceConfig) -> Result<Self> {
+        let mut sensor = HcSr04::new(config.trigger_pin, config.echo_pin)?;
+        let (tx, rx) = channel();
+
+        // Measuring busy-waits on the echo, so it happens on its own thread
+        thread::spawn(move || loop {
+            // Nothing in range also times out, which is shown as no reading
+            // rather than keeping the previous one
+            if tx.send(sensor.measure().ok()).is_err() {
+                return;
+            }
+
+            thread::sleep(SAMPLE_INTERVAL);
+        });
+
+        Ok(Self {
+            config,
+            measurements: rx,
+            cm: None,
+            frame_count: 0,
+        })
+    }
+}
+
+impl Animation for Distance {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if let Some(cm) = self.measurements.try_iter().last() {
+            self.cm = cm;
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(cm) = self.cm else {
+            return;
+        };
+
+        if cm <= self.config.stop_cm {
+            if self.frame_count.is_multiple_of(2) {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            }
+
+            return;
+        }
+
+        match self.config.view {
+            DistanceView::Digits => {
+                let Ok(()) = show_number(cm.round() as i64)
+                    .at_frame(self.frame_count)
+                    .draw(frame);
+            }
+            DistanceView::Bar => {
+                let closeness = 1.0 - (cm / self.config.range_cm).clamp(0.0, 1.0);
+
+                let Ok(()) = ProgressBar::horizontal(closeness).draw(frame);
+            }
+        }
+    }
+}
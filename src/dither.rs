@@ -0,0 +1,74 @@
+use crate::framebuffer::{FrameBuffer, HEIGHT, WIDTH};
+use embedded_graphics::prelude::*;
+use serde::Deserialize;
+
+/// How grayscale frames are turned into pixels which are either fully on or
+/// off.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dithering {
+    /// Ordered dithering with an 8x8 Bayer matrix, which gives a regular
+    /// pattern that doesn't shimmer when the frame changes a little.
+    Bayer,
+    /// Floyd–Steinberg error diffusion, which keeps more detail but whose
+    /// pattern can change a lot from one frame to the next.
+    FloydSteinberg,
+}
+
+/// Thresholds of ordered dithering, from 0 to 63, in the order pixels get
+/// lit as the level goes up.
+#[rustfmt::skip]
+const BAYER: [[u8; 8]; 8] = [
+    [ 0, 32,  8, 40,  2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44,  4, 36, 14, 46,  6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [ 3, 35, 11, 43,  1, 33,  9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47,  7, 39, 13, 45,  5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Turns every pixel of `frame` fully on or off, spreading the levels in
+/// between over their neighbours.
+pub fn dither(frame: &mut FrameBuffer, dithering: Dithering) {
+    match dithering {
+        Dithering::Bayer => {
+            for p in frame.bounding_box().points() {
+                let threshold = BAYER[p.y as usize][p.x as usize] as u16 * 4 + 2;
+                let lit = frame.level(p) as u16 > threshold;
+
+                frame.set_level(p, if lit { u8::MAX } else { 0 });
+            }
+        }
+        Dithering::FloydSteinberg => {
+            let mut levels = [[0i16; WIDTH]; HEIGHT];
+
+            for (y, row) in levels.iter_mut().enumerate() {
+                for (x, level) in row.iter_mut().enumerate() {
+                    *level = frame.level(Point::new(x as i32, y as i32)) as i16;
+                }
+            }
+
+            for y in 0..HEIGHT {
+                for x in 0..WIDTH {
+                    let lit = levels[y][x] > u8::MAX as i16 / 2;
+                    let output = if lit { u8::MAX } else { 0 };
+                    let error = levels[y][x] - output as i16;
+
+                    frame.set_level(Point::new(x as i32, y as i32), output);
+
+                    // The error goes to the pixels which aren't done yet: 7/16
+                    // to the right, and 3/16, 5/16 and 1/16 to the row below
+                    for (dx, dy, weight) in [(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)] {
+                        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+
+                        if (0..WIDTH as i32).contains(&nx) && (0..HEIGHT as i32).contains(&ny) {
+                            levels[ny as usize][nx as usize] += error * weight / 16;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    command::{DisplayCommand, DisplayHandle},
+    config::{DmxConfig, DmxProtocol},
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    log,
+};
+use anyhow::{Context, Result};
+use std::{
+    io,
+    net::{Ipv4Addr, UdpSocket},
+    thread,
+    time::Duration,
+};
+
+/// Ports the protocols are received on.
+const ART_NET_PORT: u16 = 6454;
+const SACN_PORT: u16 = 5568;
+
+/// Start of every Art-Net packet, followed by its opcode.
+const ART_NET_ID: &[u8] = b"Art-Net\0";
+const OP_DMX: u16 = 0x5000;
+
+/// Identifier of the ACN packets sACN is built on.
+const ACN_ID: &[u8] = b"ASC-E1.17\0\0\0";
+
+/// Set in the options of the last sACN packet a source sends.
+const STREAM_TERMINATED: u8 = 0x40;
+
+/// How long a source can stay silent before the display goes back to its
+/// mode, which E1.31 sets at 2.5 seconds.
+const DATA_LOSS_TIMEOUT: Duration = Duration::from_millis(2500);
+
+/// Starts receiving DMX over the network on a new thread, showing 64
+/// channels of a universe as the brightness of the pixels, so that lighting
+/// software can drive the display like a fixture.
+///
+/// The display goes back to its mode once the source stops sending.
+pub fn spawn(config: DmxConfig, display: DisplayHandle) -> Result<()> {
+    let port = match config.protocol {
+        DmxProtocol::ArtNet => ART_NET_PORT,
+        DmxProtocol::Sacn => SACN_PORT,
+    };
+
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))
+        .with_context(|| format!("failed to listen for DMX on port {}", port))?;
+
+    // sACN is usually multicast, to a group of its own for every universe
+    if config.protocol == DmxProtocol::Sacn {
+        let [high, low] = config.universe.to_be_bytes();
+
+        socket.join_multicast_v4(&Ipv4Addr::new(239, 255, high, low), &Ipv4Addr::UNSPECIFIED)?;
+    }
+
+    socket.set_read_timeout(Some(DATA_LOSS_TIMEOUT))?;
+
+    thread::spawn(move || {
+        if let Err(err) = receive(&socket, &config, &display) {
+            log::warn!("DMX: {:#}", err);
+        }
+    });
+
+    Ok(())
+}
+
+/// Shows the packets of the configured universe as they come in, until the
+/// display goes away.
+fn receive(socket: &UdpSocket, config: &DmxConfig, display: &DisplayHandle) -> Result<()> {
+    let mut packet = [0; 1024];
+    // Whether a frame from DMX is on the display
+    let mut showing = false;
+
+    loop {
+        let length = match socket.recv(&mut packet) {
+            Ok(length) => length,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                if showing {
+                    log::info!("DMX: the source went silent");
+
+                    display.send(DisplayCommand::Clear)?;
+                    showing = false;
+                }
+
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let packet = &packet[..length];
+
+        let parsed = match config.protocol {
+            DmxProtocol::ArtNet => parse_art_net(packet),
+            DmxProtocol::Sacn => parse_sacn(packet),
+        };
+
+        let Some(dmx) = parsed.filter(|dmx| dmx.universe == config.universe) else {
+            continue;
+        };
+
+        if dmx.terminated {
+            display.send(DisplayCommand::Clear)?;
+            showing = false;
+            continue;
+        }
+
+        // Channels count from 1, and those the packet doesn't have are off
+        let start = config.start_channel.max(1) as usize - 1;
+        let levels: Vec<u8> = (start..start + WIDTH * HEIGHT)
+            .map(|channel| dmx.channels.get(channel).copied().unwrap_or(0))
+            .collect();
+
+        if let Some(frame) = FrameBuffer::from_levels(&levels) {
+            display.send(DisplayCommand::ShowFrame(frame))?;
+            showing = true;
+        }
+    }
+}
+
+/// The DMX data of a packet.
+struct Dmx<'a> {
+    universe: u16,
+    /// Levels of the channels, starting with the first.
+    channels: &'a [u8],
+    /// Whether the source said it stops sending.
+    terminated: bool,
+}
+
+/// Parses an ArtDmx packet, or returns nothing for every other packet.
+fn parse_art_net(packet: &[u8]) -> Option<Dmx<'_>> {
+    if !packet.starts_with(ART_NET_ID) {
+        return None;
+    }
+
+    let opcode = u16::from_le_bytes([*packet.get(8)?, *packet.get(9)?]);
+
+    if opcode != OP_DMX {
+        return None;
+    }
+
+    // The sub-net and universe, and then the net
+    let universe = u16::from_le_bytes([*packet.get(14)?, *packet.get(15)?]) & 0x7FFF;
+    let length = u16::from_be_bytes([*packet.get(16)?, *packet.get(17)?]) as usize;
+
+    Some(Dmx {
+        universe,
+        channels: packet.get(18..18 + length).unwrap_or(&packet[18..]),
+        terminated: false,
+    })
+}
+
+/// Parses an E1.31 data packet, or returns nothing for every other packet.
+fn parse_sacn(packet: &[u8]) -> Option<Dmx<'_>> {
+    if packet.get(4..16)? != ACN_ID {
+        return None;
+    }
+
+    // Vectors of the root and framing layers of data packets
+    if packet.get(18..22)? != [0, 0, 0, 4] || packet.get(40..44)? != [0, 0, 0, 2] {
+        return None;
+    }
+
+    let options = *packet.get(112)?;
+    let universe = u16::from_be_bytes([*packet.get(113)?, *packet.get(114)?]);
+    // The count includes the start code, which has to be 0 for levels
+    let count = u16::from_be_bytes([*packet.get(123)?, *packet.get(124)?]) as usize;
+
+    if *packet.get(125)? != 0 {
+        return None;
+    }
+
+    Some(Dmx {
+        universe,
+        channels: packet
+            .get(126..125 + count.max(1))
+            .unwrap_or(&packet[126..]),
+        terminated: options & STREAM_TERMINATED != 0,
+    })
+}
@@ -0,0 +1,208 @@
+use crate::{
+    animation::Animation,
+    config::DockerConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    http,
+    json::Json,
+    log,
+    text::ScrollingText,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before trying again after a failed check.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Level of the pixels of the containers which aren't running.
+const STOPPED_LEVEL: u8 = u8::MAX / 8;
+
+/// Number of frames the pixels of restarting containers are on and off for.
+const BLINK_FRAMES: u32 = 2;
+
+/// Number of frames the containers are shown for in between two scrolls of
+/// the names of the unhealthy ones.
+const GRID_FRAMES: u32 = 15;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Restarting,
+    /// Stopped, paused, or not started yet.
+    Stopped,
+}
+
+struct Container {
+    name: String,
+    state: State,
+    /// Whether its health check fails.
+    unhealthy: bool,
+}
+
+/// Asks the Docker daemon for every container, sorted by name.
+fn fetch(config: &DockerConfig) -> Result<Vec<Container>> {
+    let socket = config.socket.to_string_lossy();
+
+    let response = http::curl(&[
+        ("unix-socket", &socket),
+        ("url", "http://localhost/containers/json?all=true"),
+    ])?;
+
+    let mut containers = Json::parse(&response)?
+        .as_array()
+        .ok_or_else(|| anyhow!("unexpected answer from the Docker daemon"))?
+        .iter()
+        .map(|container| {
+            // `"Names": ["/web"]`
+            let name = container
+                .get("Names")
+                .and_then(|names| names.index(0))
+                .and_then(Json::as_str)
+                .unwrap_or_default();
+
+            // `"Status": "Up 5 minutes (unhealthy)"`
+            let status = container
+                .get("Status")
+                .and_then(Json::as_str)
+                .unwrap_or_default();
+
+            Container {
+                name: name.trim_start_matches('/').to_string(),
+                state: match container.get("State").and_then(Json::as_str) {
+                    Some("running") => State::Running,
+                    Some("restarting") => State::Restarting,
+                    _ => State::Stopped,
+                },
+                unhealthy: status.contains("(unhealthy)"),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    containers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(containers)
+}
+
+/// Shows the health of the Docker containers of the Pi, with a pixel for
+/// every container, row by row in the order of their names.
+///
+/// The pixels of running containers are lit, those of restarting ones blink
+/// and those of the others are dim. The names of the unhealthy containers
+/// are scrolled every now and then.
+pub struct ContainerHealth {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<Vec<Container>>>,
+    containers: Vec<Container>,
+    // Names of the unhealthy containers, empty if there are none
+    unhealthy: String,
+    names: ScrollingText,
+    showing_names: bool,
+    // Frames until the containers or the names are shown instead
+    frames_left: u32,
+    frame_count: u32,
+}
+
+impl ContainerHealth {
+    pub fn new(config: DockerConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Checking takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch(&config) {
+                Ok(containers) => (Some(containers), Duration::from_secs(config.interval_secs)),
+                Err(err) => {
+                    log::warn!("Docker: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            containers: Vec::new(),
+            unhealthy: String::new(),
+            names: ScrollingText::new(""),
+            showing_names: false,
+            frames_left: GRID_FRAMES,
+            frame_count: 0,
+        }
+    }
+}
+
+impl Animation for ContainerHealth {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if let Some(containers) = self.updates.try_iter().flatten().last() {
+            let unhealthy = containers
+                .iter()
+                .filter(|container| container.unhealthy)
+                .map(|container| container.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            // The names keep scrolling from where they are if they're the
+            // same
+            if unhealthy != self.unhealthy {
+                self.names = ScrollingText::new(unhealthy.clone());
+                self.unhealthy = unhealthy;
+
+                if self.showing_names {
+                    self.frames_left = self.names.width();
+                }
+            }
+
+            self.containers = containers;
+        }
+
+        if self.showing_names {
+            self.names.tick();
+        }
+
+        self.frames_left = self.frames_left.saturating_sub(1);
+
+        if self.frames_left > 0 {
+            return;
+        }
+
+        self.showing_names = !self.showing_names && !self.unhealthy.is_empty();
+        self.frames_left = if self.showing_names {
+            self.names.width()
+        } else {
+            GRID_FRAMES
+        };
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.showing_names {
+            self.names.draw(frame);
+            return;
+        }
+
+        let blink_on = (self.frame_count / BLINK_FRAMES).is_multiple_of(2);
+
+        for (i, container) in self.containers.iter().take(WIDTH * HEIGHT).enumerate() {
+            let level = match container.state {
+                State::Running => u8::MAX,
+                State::Restarting if blink_on => u8::MAX,
+                State::Restarting => 0,
+                State::Stopped => STOPPED_LEVEL,
+            };
+
+            frame.set_level(Point::new((i % WIDTH) as i32, (i / WIDTH) as i32), level);
+        }
+    }
+}
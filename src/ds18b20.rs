@@ -0,0 +1,175 @@
+use crate::{
+    animation::Animation,
+    config::Ds18b20Config,
+    framebuffer::FrameBuffer,
+    icons::{self, ARROW_DOWN, ARROW_RIGHT, ARROW_UP},
+    widget::show_number,
+};
+use anyhow::{anyhow, Context, Result};
+use embedded_graphics::prelude::*;
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Where the kernel's 1-Wire driver lists the devices on the bus.
+const W1_DEVICES: &str = "/sys/bus/w1/devices";
+
+/// Prefix of the 1-Wire ids of DS18B20 sensors, which is their family code.
+const FAMILY_CODE: &str = "28-";
+
+/// How often the temperature is read.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of readings the trend is computed over, which at one reading every
+/// `SAMPLE_INTERVAL` is a minute.
+const TREND_SAMPLES: usize = 12;
+
+/// Change over `TREND_SAMPLES` readings, in degrees Celsius, needed for the
+/// temperature to count as rising or falling.
+const TREND_THRESHOLD: f32 = 0.3;
+
+/// Number of frames the temperature and the trend are each shown for before
+/// switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// A DS18B20 temperature sensor on the 1-Wire bus, read through the `w1-gpio`
+/// and `w1-therm` kernel drivers.
+pub struct Ds18b20 {
+    path: PathBuf,
+}
+
+impl Ds18b20 {
+    /// Opens the sensor with the 1-Wire id `device`, or the first DS18B20 on
+    /// the bus if there is no id.
+    pub fn new(device: Option<&str>) -> Result<Self> {
+        let device = match device {
+            Some(device) => device.to_string(),
+            None => fs::read_dir(W1_DEVICES)
+                .with_context(|| format!("failed to list the 1-Wire devices in {W1_DEVICES}"))?
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .find(|name| name.starts_with(FAMILY_CODE))
+                .ok_or_else(|| anyhow!("no DS18B20 found on the 1-Wire bus"))?,
+        };
+
+        let path = PathBuf::from(W1_DEVICES).join(device).join("w1_slave");
+
+        if !path.exists() {
+            return Err(anyhow!("no 1-Wire device at {}", path.display()));
+        }
+
+        Ok(Self { path })
+    }
+
+    /// Reads the temperature, in degrees Celsius.
+    ///
+    /// This blocks for the ~750ms the sensor takes to do a conversion.
+    pub fn read(&self) -> Result<f32> {
+        // The driver gives two lines: the raw bytes with whether their CRC
+        // matched, and then the same bytes followed by `t=` and the
+        // temperature in millidegrees
+        let contents = fs::read_to_string(&self.path)?;
+        let mut lines = contents.lines();
+
+        if !lines.next().is_some_and(|line| line.ends_with("YES")) {
+            return Err(anyhow!("CRC mismatch reading {}", self.path.display()));
+        }
+
+        let millidegrees: f32 = lines
+            .next()
+            .and_then(|line| line.split("t=").nth(1))
+            .ok_or_else(|| anyhow!("unexpected contents in {}", self.path.display()))?
+            .trim()
+            .parse()?;
+
+        Ok(millidegrees / 1000.0)
+    }
+}
+
+/// Shows the temperature measured by a DS18B20.
+///
+/// It alternates between the temperature in degrees Celsius and an arrow
+/// showing whether it went up, down or held steady over the last minute.
+pub struct ProbeTemperature {
+    // Every reading, with nothing for the failed ones
+    readings: Receiver<Option<f32>>,
+    // Most recent readings, the newest one at the back
+    history: VecDeque<f32>,
+    frame_count: u32,
+}
+
+impl ProbeTemperature {
+    pub fn new(config: Ds18b20Config) -> Result<Self> {
+        let sensor = Ds18b20::new(config.device.as_deref())?;
+        let (tx, rx) = channel();
+
+        // Every reading blocks during the conversion, so they happen on their
+        // own thread
+        thread::spawn(move || loop {
+            // Failed readings are sent too, so that the thread stops once the
+            // mode is gone even while the probe is missing, and are skipped
+            // by the mode, keeping the previous one on screen
+            if tx.send(sensor.read().ok()).is_err() {
+                return;
+            }
+
+            thread::sleep(SAMPLE_INTERVAL);
+        });
+
+        Ok(Self {
+            readings: rx,
+            history: VecDeque::with_capacity(TREND_SAMPLES),
+            frame_count: 0,
+        })
+    }
+
+    /// Change in temperature between the oldest and newest readings.
+    fn trend(&self) -> f32 {
+        match (self.history.front(), self.history.back()) {
+            (Some(oldest), Some(newest)) => newest - oldest,
+            _ => 0.0,
+        }
+    }
+}
+
+impl Animation for ProbeTemperature {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        for celsius in self.readings.try_iter().flatten() {
+            if self.history.len() == TREND_SAMPLES {
+                self.history.pop_front();
+            }
+
+            self.history.push_back(celsius);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(&celsius) = self.history.back() else {
+            return;
+        };
+
+        if (self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            let Ok(()) = show_number(celsius.round() as i64)
+                .at_frame(self.frame_count % VIEW_FRAMES)
+                .draw(frame);
+        } else {
+            let trend = self.trend();
+
+            let arrow = if trend >= TREND_THRESHOLD {
+                &ARROW_UP
+            } else if trend <= -TREND_THRESHOLD {
+                &ARROW_DOWN
+            } else {
+                &ARROW_RIGHT
+            };
+
+            icons::draw_icon(arrow, Point::zero(), frame);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    animation::Animation,
+    canvas::VirtualCanvas,
+    framebuffer::FrameBuffer,
+    input::{Button, Encoder, InputEvent},
+};
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use rand::Rng;
+
+/// Width and height of the cave, in pixels.
+const CAVE_SIZE: u32 = 64;
+
+/// Chance of every pixel starting as rock, before the cave is smoothed.
+const ROCK_CHANCE: f64 = 0.45;
+
+/// Number of smoothing passes which turn the noise into caverns.
+const SMOOTHING_PASSES: usize = 4;
+
+/// Level rock is drawn with, dim enough for the edges of the cave to stand
+/// out.
+const ROCK_LEVEL: u8 = 64;
+
+/// Generates a random cave, where `true` is rock, using a cellular automaton
+/// which fills pixels surrounded by rock and hollows out the others.
+fn generate_cave() -> Vec<Vec<bool>> {
+    let size = CAVE_SIZE as usize;
+    let mut rng = rand::thread_rng();
+
+    let mut cave: Vec<Vec<bool>> = (0..size)
+        .map(|_| (0..size).map(|_| rng.gen_bool(ROCK_CHANCE)).collect())
+        .collect();
+
+    for _ in 0..SMOOTHING_PASSES {
+        let rock_around = |x: usize, y: usize| {
+            let mut count = 0;
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+
+                    // Past the edges counts as rock, to close the cave
+                    let rock = usize::try_from(nx)
+                        .ok()
+                        .zip(usize::try_from(ny).ok())
+                        .and_then(|(nx, ny)| cave.get(ny)?.get(nx).copied())
+                        .unwrap_or(true);
+
+                    count += rock as u32;
+                }
+            }
+
+            count
+        };
+
+        cave = (0..size)
+            .map(|y| (0..size).map(|x| rock_around(x, y) >= 5).collect())
+            .collect();
+    }
+
+    cave
+}
+
+/// A large, randomly generated cave to look around, a screen at a time.
+///
+/// The direction buttons and the X and Y rotary encoders pan the view. Rock
+/// is dim, and the edges of the cave are fully lit.
+pub struct Explore {
+    canvas: VirtualCanvas,
+}
+
+impl Explore {
+    pub fn new() -> Self {
+        let mut canvas = VirtualCanvas::new(Size::new(CAVE_SIZE, CAVE_SIZE));
+
+        for (y, row) in generate_cave().iter().enumerate() {
+            for (x, &rock) in row.iter().enumerate() {
+                if rock {
+                    canvas.set_level(Point::new(x as i32, y as i32), ROCK_LEVEL);
+                }
+            }
+        }
+
+        // Outline the whole canvas, so that it's clear where the cave ends
+        let Ok(()) = Rectangle::new(Point::zero(), canvas.size())
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut canvas);
+
+        canvas.center_on(Point::new(CAVE_SIZE as i32 / 2, CAVE_SIZE as i32 / 2));
+
+        Self { canvas }
+    }
+}
+
+impl Animation for Explore {
+    fn tick(&mut self) {}
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        self.canvas.draw_viewport(frame);
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let offset = match event {
+            InputEvent::Pressed(Button::Up) => Point::new(0, -1),
+            InputEvent::Pressed(Button::Down) => Point::new(0, 1),
+            InputEvent::Pressed(Button::Left) => Point::new(-1, 0),
+            InputEvent::Pressed(Button::Right) => Point::new(1, 0),
+            InputEvent::Turned(Encoder::X, detents) => Point::new(detents, 0),
+            InputEvent::Turned(Encoder::Y, detents) => Point::new(0, detents),
+            _ => return,
+        };
+
+        self.canvas.pan(offset);
+    }
+}
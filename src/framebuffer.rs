@@ -0,0 +1,214 @@
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+use std::convert::Infallible;
+
+/// Number of pixel columns on the matrix.
+pub const WIDTH: usize = 8;
+
+/// Number of pixel rows on the matrix.
+pub const HEIGHT: usize = 8;
+
+/// Number of passes used to produce intermediate brightness levels.
+///
+/// Every flush of the matrix is split into this many passes, and a pixel is
+/// only lit during the passes its brightness level covers, which is a simple
+/// form of software PWM.
+pub const PWM_STEPS: u8 = 8;
+
+/// In-memory copy of what should be shown on the matrix.
+///
+/// Every pixel stores a brightness level, from `0` (off) to `u8::MAX` (fully
+/// on). Drawing with [`BinaryColor`] turns pixels fully on or off, while
+/// [`FrameBuffer::set_level`] can be used for anything in between.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct FrameBuffer {
+    levels: [[u8; WIDTH]; HEIGHT],
+}
+
+impl FrameBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a frame out of the levels of its pixels, row by row, or
+    /// returns nothing if there aren't as many levels as pixels.
+    pub fn from_levels(levels: &[u8]) -> Option<Self> {
+        if levels.len() != WIDTH * HEIGHT {
+            return None;
+        }
+
+        let mut frame = Self::new();
+
+        for (row, levels) in frame.levels.iter_mut().zip(levels.chunks(WIDTH)) {
+            row.copy_from_slice(levels);
+        }
+
+        Some(frame)
+    }
+
+    /// Turns every pixel off, to start drawing a frame from scratch.
+    pub fn clear_screen(&mut self) {
+        self.levels = [[0; WIDTH]; HEIGHT];
+    }
+
+    /// Turns every pixel into its negative, so that lit pixels are off and
+    /// the other way around.
+    pub fn invert(&mut self) {
+        for level in self.levels.iter_mut().flatten() {
+            *level = u8::MAX - *level;
+        }
+    }
+
+    /// Returns the brightness level of the pixel at `p`, or `0` if `p` is
+    /// outside of the matrix.
+    pub fn level(&self, p: Point) -> u8 {
+        index(p).map_or(0, |(x, y)| self.levels[y][x])
+    }
+
+    /// Sets the brightness level of the pixel at `p`. Points outside of the
+    /// matrix are ignored.
+    pub fn set_level(&mut self, p: Point, level: u8) {
+        if let Some((x, y)) = index(p) {
+            self.levels[y][x] = level;
+        }
+    }
+
+    /// Draws `other` on top of this frame, keeping the brighter of the two
+    /// levels for every pixel.
+    pub fn overlay(&mut self, other: &FrameBuffer) {
+        for (row, other_row) in self.levels.iter_mut().zip(&other.levels) {
+            for (level, &other_level) in row.iter_mut().zip(other_row) {
+                *level = (*level).max(other_level);
+            }
+        }
+    }
+
+    /// Mixes `other` into this frame, where an `alpha` of `u8::MAX` gives
+    /// `other` and `0` keeps this frame as is.
+    pub fn blend(&mut self, other: &FrameBuffer, alpha: u8) {
+        let alpha = alpha as u16;
+
+        for (row, other_row) in self.levels.iter_mut().zip(&other.levels) {
+            for (level, &other_level) in row.iter_mut().zip(other_row) {
+                let mixed = *level as u16 * (u8::MAX as u16 - alpha) + other_level as u16 * alpha;
+
+                *level = (mixed / u8::MAX as u16) as u8;
+            }
+        }
+    }
+
+    /// Scales the level of every pixel by `brightness`, where `u8::MAX` keeps
+    /// the frame as is.
+    pub fn dim(&mut self, brightness: u8) {
+        for level in self.levels.iter_mut().flatten() {
+            *level = (*level as u16 * brightness as u16 / u8::MAX as u16) as u8;
+        }
+    }
+
+    /// Returns the brightness levels of row `y`.
+    pub fn row(&self, y: usize) -> &[u8; WIDTH] {
+        &self.levels[y]
+    }
+
+    /// Returns which pixels of row `y` should be lit during the given PWM
+    /// pass, as a mask where bit `x` is set for column `x`.
+    pub fn lit_columns(&self, step: u8, y: usize) -> u8 {
+        // A pixel at full brightness is lit during every pass, one at half
+        // brightness during half of them, and so on
+        let threshold = step as u16 * 256 / PWM_STEPS as u16;
+
+        self.levels[y]
+            .iter()
+            .enumerate()
+            .filter(|(_, &level)| level as u16 > threshold)
+            .fold(0, |mask, (x, _)| mask | 1 << x)
+    }
+}
+
+/// Converts a point into `(x, y)` array indices, if it is inside the matrix.
+fn index(p: Point) -> Option<(usize, usize)> {
+    let x = usize::try_from(p.x).ok().filter(|&x| x < WIDTH)?;
+    let y = usize::try_from(p.y).ok().filter(|&y| y < HEIGHT)?;
+
+    Some((x, y))
+}
+
+impl OriginDimensions for FrameBuffer {
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl DrawTarget for FrameBuffer {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, c) in pixels {
+            self.set_level(p, color_level(c));
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let width = area.size.width as usize;
+        let mut colors = colors.into_iter();
+
+        // The colors cover the whole area, a row after the other, including
+        // the parts which are outside of the matrix and skipped
+        for y in area.rows() {
+            let row_colors = colors.by_ref().take(width);
+
+            let Some(row) = usize::try_from(y).ok().and_then(|y| self.levels.get_mut(y)) else {
+                row_colors.for_each(drop);
+                continue;
+            };
+
+            for (x, color) in area.columns().zip(row_colors) {
+                if let Some(level) = usize::try_from(x).ok().and_then(|x| row.get_mut(x)) {
+                    *level = color_level(color);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+
+        // Nothing to fill if the area is empty or entirely outside
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let xs = area.top_left.x as usize..=bottom_right.x as usize;
+        let ys = area.top_left.y as usize..=bottom_right.y as usize;
+
+        for row in &mut self.levels[ys] {
+            row[xs.clone()].fill(color_level(color));
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.levels = [[color_level(color); WIDTH]; HEIGHT];
+
+        Ok(())
+    }
+}
+
+/// Returns the brightness level pixels drawn with `color` get.
+pub fn color_level(color: BinaryColor) -> u8 {
+    match color {
+        BinaryColor::On => u8::MAX,
+        BinaryColor::Off => 0,
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    animation::Animation,
+    config::GithubConfig,
+    framebuffer::FrameBuffer,
+    http,
+    icons::{self, BELL},
+    json::Json,
+    log,
+    widget::show_number,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// GitHub's notifications API, which only lists unread notifications by
+/// default.
+const API_URL: &str = "https://api.github.com/notifications?per_page=50";
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Number of frames the bell and the count are each shown for before
+/// switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// Number of frames the display flashes for when new notifications come in.
+const FLASH_FRAMES: u32 = 10;
+
+/// Fetches the number of unread notifications, up to the 50 which fit in a
+/// single page.
+fn fetch(config: &GithubConfig) -> Result<usize> {
+    let authorization = format!("Bearer {}", config.token);
+    let response = Json::parse(&http::get_with_headers(
+        API_URL,
+        &[
+            ("Authorization", &authorization),
+            ("Accept", "application/vnd.github+json"),
+        ],
+    )?)?;
+
+    match response {
+        Json::Array(notifications) => Ok(notifications.len()),
+        _ => Err(anyhow!("unexpected answer from GitHub")),
+    }
+}
+
+/// Shows how many unread GitHub notifications there are.
+///
+/// It alternates between a bell and the count, and flashes when the count
+/// goes up.
+pub struct GithubNotifications {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<usize>>,
+    count: Option<usize>,
+    frame_count: u32,
+    flash_frames: u32,
+}
+
+impl GithubNotifications {
+    pub fn new(config: GithubConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Fetching takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch(&config) {
+                Ok(count) => (Some(count), Duration::from_secs(config.interval_secs)),
+                Err(err) => {
+                    log::warn!("GitHub: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            count: None,
+            frame_count: 0,
+            flash_frames: 0,
+        }
+    }
+}
+
+impl Animation for GithubNotifications {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+        self.flash_frames = self.flash_frames.saturating_sub(1);
+
+        if let Some(count) = self.updates.try_iter().flatten().last() {
+            if self.count.is_some_and(|previous| count > previous) {
+                self.flash_frames = FLASH_FRAMES;
+            }
+
+            self.count = Some(count);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(count) = self.count else {
+            return;
+        };
+
+        if self.flash_frames > 0 {
+            if self.flash_frames % 2 == 1 {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            } else {
+                icons::draw_icon(&BELL, Point::zero(), frame);
+            }
+
+            return;
+        }
+
+        if (self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            icons::draw_icon(&BELL, Point::zero(), frame);
+        } else {
+            let Ok(()) = show_number(count as i64)
+                .at_frame(self.frame_count % VIEW_FRAMES)
+                .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use std::{fs::OpenOptions, io, os::fd::AsRawFd, ptr};
+
+/// Size of the GPIO register block mapped by `/dev/gpiomem`.
+const BLOCK_SIZE: usize = 4096;
+
+// Offsets of the registers which set and clear GPIOs 0 to 31, in words
+const GPSET0: usize = 0x1c / 4;
+const GPCLR0: usize = 0x28 / 4;
+
+/// The GPIO registers of the BCM283x/BCM2711, mapped into memory through
+/// `/dev/gpiomem`.
+///
+/// Writing the registers directly sets or clears any number of pins at once
+/// in well under a microsecond, much faster than going through a pin at a
+/// time. The pins still have to be made outputs some other way.
+pub struct GpioMem {
+    registers: *mut u32,
+}
+
+// SAFETY: the mapping is only ever accessed through `&mut self`, and the
+// registers may be written from any thread
+unsafe impl Send for GpioMem {}
+
+impl GpioMem {
+    pub fn open() -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/gpiomem")
+            .context("failed to open /dev/gpiomem")?;
+
+        // SAFETY: a new shared mapping of a file which stays valid after the
+        // file is closed, whose result is checked
+        let registers = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                BLOCK_SIZE,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if registers == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error()).context("failed to map /dev/gpiomem");
+        }
+
+        Ok(Self {
+            registers: registers.cast(),
+        })
+    }
+
+    /// Drives the GPIOs whose bits are set in `mask` high.
+    pub fn set(&mut self, mask: u32) {
+        // SAFETY: the register is inside the mapped block
+        unsafe { ptr::write_volatile(self.registers.add(GPSET0), mask) }
+    }
+
+    /// Drives the GPIOs whose bits are set in `mask` low.
+    pub fn clear(&mut self, mask: u32) {
+        // SAFETY: the register is inside the mapped block
+        unsafe { ptr::write_volatile(self.registers.add(GPCLR0), mask) }
+    }
+}
+
+impl Drop for GpioMem {
+    fn drop(&mut self) {
+        // SAFETY: the block was mapped with this size and isn't used anymore
+        unsafe { libc::munmap(self.registers.cast(), BLOCK_SIZE) };
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Longest a request can take, in seconds, before it is given up on.
+const TIMEOUT_SECS: &str = "15";
+
+/// Fetches `url` and returns the body of the response.
+///
+/// This uses the system `curl` command, which comes with TLS support on
+/// Raspberry Pi OS, rather than an HTTP client of our own.
+pub fn get(url: &str) -> Result<String> {
+    get_with_headers(url, &[])
+}
+
+/// Fetches `url`, sending the extra `headers` as `(name, value)` pairs, and
+/// returns the body of the response.
+pub fn get_with_headers(url: &str, headers: &[(&str, &str)]) -> Result<String> {
+    let headers: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+
+    let options: Vec<(&str, &str)> = [("url", url)]
+        .into_iter()
+        .chain(headers.iter().map(|header| ("header", header.as_str())))
+        .collect();
+
+    curl(&options)
+}
+
+/// Runs `curl` with `options` as `(name, value)` pairs, like `("url", ...)`,
+/// and returns what it fetched. This also works for the other protocols
+/// `curl` knows, like IMAP.
+///
+/// The options are given to `curl` through its standard input rather than
+/// its arguments, so that the tokens and passwords they often carry don't
+/// show up in the process list.
+pub fn curl(options: &[(&str, &str)]) -> Result<String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--max-time", TIMEOUT_SECS])
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+
+    // Dropping stdin once the request is written closes it, which lets curl
+    // go ahead with it
+    if let Some(mut stdin) = child.stdin.take() {
+        for (name, value) in options {
+            writeln!(stdin, "{} = \"{}\"", name, escape(value))?;
+        }
+    }
+
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        // The URL is left out, since it can contain a token
+        return Err(anyhow!(
+            "request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Percent-encodes `component` to be part of a URL, like a query parameter
+/// or a path segment with slashes.
+pub fn encode(component: &str) -> String {
+    component
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Escapes `value` to be quoted in a `curl` config file.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,336 @@
+use crate::framebuffer::FrameBuffer;
+use embedded_graphics::{
+    image::{Image, ImageRaw},
+    pixelcolor::BinaryColor,
+    prelude::*,
+};
+
+/// A monochrome image, stored as one byte per row of 8 pixels with the most
+/// significant bit being the leftmost pixel.
+pub type Icon = ImageRaw<'static, BinaryColor>;
+
+/// Faces of a die, from one to six pips.
+#[rustfmt::skip]
+pub const DICE: [Icon; 6] = [
+    Icon::new_binary(&[
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00011000,
+        0b00011000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00000000,
+        0b01100000,
+        0b01100000,
+        0b00000000,
+        0b00000000,
+        0b00000110,
+        0b00000110,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00000000,
+        0b01100000,
+        0b01100000,
+        0b00011000,
+        0b00011000,
+        0b00000110,
+        0b00000110,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00000000,
+        0b01100110,
+        0b01100110,
+        0b00000000,
+        0b00000000,
+        0b01100110,
+        0b01100110,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00000000,
+        0b01100110,
+        0b01100110,
+        0b00011000,
+        0b00011000,
+        0b01100110,
+        0b01100110,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b01100110,
+        0b01100110,
+        0b00000000,
+        0b01100110,
+        0b01100110,
+        0b00000000,
+        0b01100110,
+        0b01100110,
+    ], 8),
+];
+
+/// Draws `icon` with its top left corner at `position`.
+pub fn draw_icon(icon: &Icon, position: Point, frame: &mut FrameBuffer) {
+    let Ok(()) = Image::new(icon, position).draw(frame);
+}
+
+/// Warning sign, a triangle with an exclamation mark.
+#[rustfmt::skip]
+pub const WARNING: Icon = Icon::new_binary(&[
+    0b00011000,
+    0b00100100,
+    0b00100100,
+    0b01011010,
+    0b01011010,
+    0b10000001,
+    0b10011001,
+    0b11111111,
+], 8);
+
+/// Arrow pointing up, for a rising value.
+#[rustfmt::skip]
+pub const ARROW_UP: Icon = Icon::new_binary(&[
+    0b00011000,
+    0b00111100,
+    0b01111110,
+    0b11011011,
+    0b00011000,
+    0b00011000,
+    0b00011000,
+    0b00011000,
+], 8);
+
+/// Arrow pointing down, for a falling value.
+#[rustfmt::skip]
+pub const ARROW_DOWN: Icon = Icon::new_binary(&[
+    0b00011000,
+    0b00011000,
+    0b00011000,
+    0b00011000,
+    0b11011011,
+    0b01111110,
+    0b00111100,
+    0b00011000,
+], 8);
+
+/// Arrow pointing right, for a value which holds steady.
+#[rustfmt::skip]
+pub const ARROW_RIGHT: Icon = Icon::new_binary(&[
+    0b00010000,
+    0b00011000,
+    0b00001100,
+    0b11111110,
+    0b11111110,
+    0b00001100,
+    0b00011000,
+    0b00010000,
+], 8);
+
+/// Thermometer, for temperatures.
+#[rustfmt::skip]
+pub const THERMOMETER: Icon = Icon::new_binary(&[
+    0b00011000,
+    0b00100100,
+    0b00101100,
+    0b00100100,
+    0b00101100,
+    0b01011110,
+    0b01011110,
+    0b00111100,
+], 8);
+
+/// Water drop, for humidity.
+#[rustfmt::skip]
+pub const DROP: Icon = Icon::new_binary(&[
+    0b00010000,
+    0b00011000,
+    0b00111100,
+    0b00111100,
+    0b01111110,
+    0b01111010,
+    0b01110110,
+    0b00111100,
+], 8);
+
+/// Frames of a shining sun, for clear weather.
+#[rustfmt::skip]
+pub const SUN: [Icon; 2] = [
+    Icon::new_binary(&[
+        0b00011000,
+        0b00000000,
+        0b00111100,
+        0b10111101,
+        0b10111101,
+        0b00111100,
+        0b00000000,
+        0b00011000,
+    ], 8),
+    Icon::new_binary(&[
+        0b10000001,
+        0b01000010,
+        0b00111100,
+        0b00111100,
+        0b00111100,
+        0b00111100,
+        0b01000010,
+        0b10000001,
+    ], 8),
+];
+
+/// Frames of a drifting cloud, for cloudy weather.
+#[rustfmt::skip]
+pub const CLOUD: [Icon; 2] = [
+    Icon::new_binary(&[
+        0b00000000,
+        0b00110000,
+        0b01111000,
+        0b01111110,
+        0b11111111,
+        0b11111111,
+        0b01111110,
+        0b00000000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00000000,
+        0b00011000,
+        0b00111100,
+        0b00111111,
+        0b01111111,
+        0b01111111,
+        0b00111111,
+        0b00000000,
+    ], 8),
+];
+
+/// Frames of a cloud with falling rain.
+#[rustfmt::skip]
+pub const RAIN: [Icon; 2] = [
+    Icon::new_binary(&[
+        0b00111000,
+        0b01111110,
+        0b11111111,
+        0b01111110,
+        0b01000100,
+        0b00010001,
+        0b01000100,
+        0b00010001,
+    ], 8),
+    Icon::new_binary(&[
+        0b00111000,
+        0b01111110,
+        0b11111111,
+        0b01111110,
+        0b00010001,
+        0b01000100,
+        0b00010001,
+        0b01000100,
+    ], 8),
+];
+
+/// Frames of a cloud with falling snow.
+#[rustfmt::skip]
+pub const SNOW: [Icon; 2] = [
+    Icon::new_binary(&[
+        0b00111000,
+        0b01111110,
+        0b11111111,
+        0b01111110,
+        0b01000000,
+        0b00001000,
+        0b00000010,
+        0b00100000,
+    ], 8),
+    Icon::new_binary(&[
+        0b00111000,
+        0b01111110,
+        0b11111111,
+        0b01111110,
+        0b00000010,
+        0b01000000,
+        0b00001000,
+        0b00000001,
+    ], 8),
+];
+
+/// Triangle pointing up, sized to sit in a line of 5x8 text.
+#[rustfmt::skip]
+pub const TRIANGLE_UP: Icon = Icon::new_binary(&[
+    0b00000000,
+    0b00000000,
+    0b00100000,
+    0b01110000,
+    0b11111000,
+    0b00000000,
+    0b00000000,
+    0b00000000,
+], 5);
+
+/// Triangle pointing down, sized to sit in a line of 5x8 text.
+#[rustfmt::skip]
+pub const TRIANGLE_DOWN: Icon = Icon::new_binary(&[
+    0b00000000,
+    0b00000000,
+    0b11111000,
+    0b01110000,
+    0b00100000,
+    0b00000000,
+    0b00000000,
+    0b00000000,
+], 5);
+
+/// Bell, for notifications.
+#[rustfmt::skip]
+pub const BELL: Icon = Icon::new_binary(&[
+    0b00011000,
+    0b00111100,
+    0b01111110,
+    0b01111110,
+    0b01111110,
+    0b11111111,
+    0b00000000,
+    0b00011000,
+], 8);
+
+/// Envelope, for email.
+#[rustfmt::skip]
+pub const ENVELOPE: Icon = Icon::new_binary(&[
+    0b00000000,
+    0b11111111,
+    0b11000011,
+    0b10100101,
+    0b10011001,
+    0b10000001,
+    0b11111111,
+    0b00000000,
+], 8);
+
+/// Check mark, for things which succeeded.
+#[rustfmt::skip]
+pub const CHECK: Icon = Icon::new_binary(&[
+    0b00000000,
+    0b00000001,
+    0b00000011,
+    0b00000110,
+    0b10001100,
+    0b11011000,
+    0b01110000,
+    0b00100000,
+], 8);
+
+/// Cross, for things which failed.
+#[rustfmt::skip]
+pub const CROSS: Icon = Icon::new_binary(&[
+    0b10000001,
+    0b11000011,
+    0b01100110,
+    0b00111100,
+    0b00111100,
+    0b01100110,
+    0b11000011,
+    0b10000001,
+], 8);
@@ -0,0 +1,257 @@
+use anyhow::Result;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use rppal::gpio::{Gpio, InputPin};
+use std::{
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A push button the user can press.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// The push button of the brightness encoder.
+    Select,
+}
+
+impl Button {
+    /// Every direction button, going clockwise from the top left quadrant.
+    pub const ALL: [Button; 4] = [Button::Up, Button::Right, Button::Down, Button::Left];
+
+    /// Returns the quadrant of the matrix which games map to this button,
+    /// going clockwise from the top left: up, right, down and left. Only the
+    /// direction buttons have one.
+    pub fn quadrant(self) -> Option<Rectangle> {
+        let top_left = match self {
+            Button::Up => Point::new(0, 0),
+            Button::Right => Point::new(4, 0),
+            Button::Down => Point::new(4, 4),
+            Button::Left => Point::new(0, 4),
+            Button::Select => return None,
+        };
+
+        Some(Rectangle::new(top_left, Size::new(4, 4)))
+    }
+}
+
+/// How long a button has to stay in a new state before the change is
+/// reported, to filter out the contacts bouncing.
+const DEBOUNCE_TIME: Duration = Duration::from_millis(20);
+
+/// How long a button has to be held down to count as a long press.
+const LONG_PRESS_TIME: Duration = Duration::from_millis(600);
+
+/// Longest time between two presses of a button for them to count as a
+/// double press.
+const DOUBLE_PRESS_TIME: Duration = Duration::from_millis(300);
+
+/// How often the input devices are polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Something the user did with one of the input devices.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputEvent {
+    Pressed(Button),
+    Released(Button),
+    /// A button was held down for a while. It is sent while the button is
+    /// still held, and followed by the usual release event.
+    LongPressed(Button),
+    /// A button was pressed twice in quick succession. It is sent after the
+    /// second press event.
+    DoublePressed(Button),
+    /// An encoder was turned by this many detents, positive when turned
+    /// clockwise.
+    Turned(Encoder, i32),
+}
+
+/// Push buttons wired between a GPIO pin and ground.
+///
+/// The internal pull-up resistors are enabled, so a pin reads low while its
+/// button is held down.
+pub struct Buttons {
+    buttons: Vec<DebouncedButton>,
+}
+
+struct DebouncedButton {
+    button: Button,
+    pin: InputPin,
+    // Debounced state of the button
+    down: bool,
+    // When the pin started reading differently from `down`, if it does
+    changed_at: Option<Instant>,
+    // When the current press started, until it is reported as a long press
+    pressed_at: Option<Instant>,
+    // When the button was last pressed, unless that already counted towards
+    // a double press
+    last_pressed_at: Option<Instant>,
+}
+
+impl Buttons {
+    pub fn new(gpio: &Gpio, pins: &[(Button, u8)]) -> Result<Self> {
+        let buttons = pins
+            .iter()
+            .map(|&(button, pin_number)| {
+                let pin = gpio.get(pin_number)?.into_input_pullup();
+
+                Ok(DebouncedButton {
+                    button,
+                    pin,
+                    down: false,
+                    changed_at: None,
+                    pressed_at: None,
+                    last_pressed_at: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { buttons })
+    }
+
+    /// Returns the buttons which were pressed or released since the last
+    /// call.
+    pub fn poll(&mut self) -> Vec<InputEvent> {
+        let now = Instant::now();
+        let mut events = Vec::new();
+
+        for button in &mut self.buttons {
+            if button.pin.is_low() == button.down {
+                button.changed_at = None;
+            } else {
+                let changed_at = *button.changed_at.get_or_insert(now);
+
+                if now - changed_at >= DEBOUNCE_TIME {
+                    button.down = !button.down;
+                    button.changed_at = None;
+
+                    if button.down {
+                        events.push(InputEvent::Pressed(button.button));
+
+                        let double = button
+                            .last_pressed_at
+                            .is_some_and(|at| now - at <= DOUBLE_PRESS_TIME);
+
+                        if double {
+                            events.push(InputEvent::DoublePressed(button.button));
+                            button.last_pressed_at = None;
+                        } else {
+                            button.last_pressed_at = Some(now);
+                        }
+
+                        button.pressed_at = Some(now);
+                    } else {
+                        events.push(InputEvent::Released(button.button));
+                        button.pressed_at = None;
+                    }
+                }
+            }
+
+            // Long presses are reported as soon as the button was held down
+            // long enough, rather than when it is released
+            if let Some(pressed_at) = button.pressed_at {
+                if now - pressed_at >= LONG_PRESS_TIME {
+                    events.push(InputEvent::LongPressed(button.button));
+                    button.pressed_at = None;
+                }
+            }
+        }
+
+        events
+    }
+}
+
+/// A rotary encoder the user can turn.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Encoder {
+    X,
+    Y,
+    Brightness,
+}
+
+/// Change in direction for every transition of the two encoder signals,
+/// indexed by the previous and the current state of the pins.
+const QUADRATURE_STEPS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// Number of signal transitions between two detents of the encoder.
+const TRANSITIONS_PER_DETENT: i8 = 4;
+
+/// A quadrature rotary encoder with its A and B pins wired to ground through
+/// the encoder contacts.
+pub struct RotaryEncoder {
+    a: InputPin,
+    b: InputPin,
+    state: u8,
+    transitions: i8,
+}
+
+impl RotaryEncoder {
+    pub fn new(gpio: &Gpio, a_pin_number: u8, b_pin_number: u8) -> Result<Self> {
+        let a = gpio.get(a_pin_number)?.into_input_pullup();
+        let b = gpio.get(b_pin_number)?.into_input_pullup();
+
+        let mut encoder = Self {
+            a,
+            b,
+            state: 0,
+            transitions: 0,
+        };
+
+        encoder.state = encoder.read();
+
+        Ok(encoder)
+    }
+
+    fn read(&self) -> u8 {
+        ((self.a.is_high() as u8) << 1) | self.b.is_high() as u8
+    }
+
+    /// Returns how many detents the encoder was turned since the last call,
+    /// positive when turned clockwise.
+    ///
+    /// This needs to be called often enough not to miss any transition.
+    pub fn poll(&mut self) -> i32 {
+        let state = self.read();
+
+        self.transitions += QUADRATURE_STEPS[((self.state << 2) | state) as usize];
+        self.state = state;
+
+        let detents = self.transitions / TRANSITIONS_PER_DETENT;
+
+        self.transitions %= TRANSITIONS_PER_DETENT;
+
+        detents as i32
+    }
+}
+
+/// Starts polling the input devices on a new thread, sending everything the
+/// user does to `events`.
+///
+/// The thread stops once the receiving end of `events` is dropped.
+pub fn spawn(
+    mut buttons: Buttons,
+    mut encoders: Vec<(Encoder, RotaryEncoder)>,
+    events: Sender<InputEvent>,
+) {
+    thread::spawn(move || loop {
+        let mut new_events = buttons.poll();
+
+        for (encoder, rotary_encoder) in &mut encoders {
+            let detents = rotary_encoder.poll();
+
+            if detents != 0 {
+                new_events.push(InputEvent::Turned(*encoder, detents));
+            }
+        }
+
+        for event in new_events {
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    });
+}
@@ -0,0 +1,25 @@
+use std::time::{Duration, Instant};
+
+/// Keeps track of when something which happens periodically is due next.
+pub struct Interval {
+    period: Duration,
+    last: Option<Instant>,
+}
+
+impl Interval {
+    pub fn new(period: Duration) -> Self {
+        Self { period, last: None }
+    }
+
+    /// Returns `true` the first time this is called, and then once every
+    /// period.
+    pub fn is_due(&mut self) -> bool {
+        let due = self.last.is_none_or(|last| last.elapsed() >= self.period);
+
+        if due {
+            self.last = Some(Instant::now());
+        }
+
+        due
+    }
+}
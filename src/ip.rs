@@ -0,0 +1,101 @@
+use crate::{animation::Animation, framebuffer::FrameBuffer, text::ScrollingText};
+use anyhow::Result;
+use std::{io, net::Ipv4Addr, ptr};
+
+/// Scrolls the IPv4 addresses of the Pi, which is handy to find it on the
+/// network when running headless.
+pub struct IpAddress {
+    text: ScrollingText,
+    // Frames left before the addresses stop being shown, if they are only
+    // shown once
+    frames_left: Option<u32>,
+}
+
+impl IpAddress {
+    /// Scrolls the addresses over and over again.
+    pub fn new() -> Self {
+        Self {
+            text: ScrollingText::new(message()),
+            frames_left: None,
+        }
+    }
+
+    /// Scrolls the addresses once, hiding everything below while doing so,
+    /// and then stays out of the way.
+    pub fn once() -> Self {
+        let text = ScrollingText::new(message());
+
+        Self {
+            frames_left: Some(text.width()),
+            text,
+        }
+    }
+}
+
+fn message() -> String {
+    match ipv4_addresses() {
+        Ok(addresses) if !addresses.is_empty() => {
+            let addresses: Vec<_> = addresses.iter().map(Ipv4Addr::to_string).collect();
+
+            format!("IP {}", addresses.join(" "))
+        }
+        _ => "No IP".to_string(),
+    }
+}
+
+/// Returns the IPv4 addresses of every network interface, except loopback.
+pub fn ipv4_addresses() -> Result<Vec<Ipv4Addr>> {
+    let mut interfaces = ptr::null_mut();
+
+    // SAFETY: on success, `interfaces` points to a linked list which stays
+    // valid until it is passed to `freeifaddrs`
+    unsafe {
+        if libc::getifaddrs(&mut interfaces) != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut addresses = Vec::new();
+        let mut interface = interfaces;
+
+        while let Some(current) = interface.as_ref() {
+            let address = current.ifa_addr;
+
+            if !address.is_null() && (*address).sa_family as i32 == libc::AF_INET {
+                let address = &*(address as *const libc::sockaddr_in);
+                let ip = Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr));
+
+                if !ip.is_loopback() {
+                    addresses.push(ip);
+                }
+            }
+
+            interface = current.ifa_next;
+        }
+
+        libc::freeifaddrs(interfaces);
+
+        Ok(addresses)
+    }
+}
+
+impl Animation for IpAddress {
+    fn tick(&mut self) {
+        match &mut self.frames_left {
+            Some(0) => return,
+            Some(frames) => *frames -= 1,
+            None => {}
+        }
+
+        self.text.tick();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.frames_left == Some(0) {
+            return;
+        }
+
+        frame.clear_screen();
+
+        self.text.draw(frame);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    action::Action,
+    command::{DisplayCommand, DisplayHandle},
+    config::IrConfig,
+    log,
+};
+use anyhow::Result;
+use std::{
+    io::{BufRead, BufReader},
+    os::unix::net::UnixStream,
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before connecting to `lircd` again after losing the
+/// connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Starts listening to the IR remote on a new thread, sending the action
+/// mapped to every key pressed to the display.
+///
+/// The remote is read through `lircd`, which takes care of decoding the
+/// signal of the GPIO IR receiver.
+pub fn spawn(config: IrConfig, display: DisplayHandle) {
+    thread::spawn(move || loop {
+        if let Err(err) = listen(&config, &display) {
+            log::warn!("IR remote: {:#}", err);
+        }
+
+        thread::sleep(RECONNECT_DELAY);
+    });
+}
+
+fn listen(config: &IrConfig, display: &DisplayHandle) -> Result<()> {
+    let socket = BufReader::new(UnixStream::connect(&config.socket)?);
+
+    for line in socket.lines() {
+        // Every key press is sent as `<code> <repeat count> <key name> <remote>`
+        let line = line?;
+        let mut fields = line.split_whitespace().skip(1);
+
+        let (Some(repeat), Some(key)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+
+        let Some(&action) = config.keys.get(key) else {
+            continue;
+        };
+
+        // Holding a key down keeps adjusting the brightness, but shouldn't
+        // keep toggling everything else
+        let repeated = u32::from_str_radix(repeat, 16).is_ok_and(|count| count > 0);
+
+        if repeated && !matches!(action, Action::AdjustBrightness(_)) {
+            continue;
+        }
+
+        log::debug!("IR remote: {} pressed, {:?}", key, action);
+
+        display.send(DisplayCommand::Action(action))?;
+    }
+
+    Ok(())
+}
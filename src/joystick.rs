@@ -0,0 +1,90 @@
+use crate::{
+    config::JoystickConfig,
+    input::{Button, InputEvent},
+};
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{self, File},
+    io::Read,
+    mem,
+    path::PathBuf,
+    sync::mpsc::Sender,
+    thread,
+};
+
+/// Name the Sense HAT gives to its joystick input device.
+const SENSE_HAT_JOYSTICK: &str = "Raspberry Pi Sense HAT Joystick";
+
+// Linux input event types and key codes, from `linux/input-event-codes.h`
+const EV_KEY: u16 = 0x01;
+const KEY_ENTER: u16 = 28;
+const KEY_UP: u16 = 103;
+const KEY_LEFT: u16 = 105;
+const KEY_RIGHT: u16 = 106;
+const KEY_DOWN: u16 = 108;
+
+/// Starts reading a 5-way joystick on a new thread, sending its movements to
+/// `events` as if they came from the direction buttons, and its center press
+/// as the select button.
+///
+/// The joystick is read through the Linux input subsystem, which is how the
+/// Sense HAT and most other joystick HATs expose it.
+pub fn spawn(config: JoystickConfig, events: Sender<InputEvent>) -> Result<()> {
+    let device = match config.device {
+        Some(device) => device,
+        None => find_device()?,
+    };
+
+    let mut file = File::open(&device)?;
+
+    thread::spawn(move || {
+        let mut buffer = vec![0; mem::size_of::<libc::input_event>()];
+
+        while file.read_exact(&mut buffer).is_ok() {
+            // The event type, code and value are always the last 8 bytes,
+            // after a timestamp whose size depends on the platform
+            let fields = &buffer[buffer.len() - 8..];
+            let kind = u16::from_ne_bytes([fields[0], fields[1]]);
+            let code = u16::from_ne_bytes([fields[2], fields[3]]);
+            let value = i32::from_ne_bytes([fields[4], fields[5], fields[6], fields[7]]);
+
+            let button = match code {
+                KEY_UP => Button::Up,
+                KEY_DOWN => Button::Down,
+                KEY_LEFT => Button::Left,
+                KEY_RIGHT => Button::Right,
+                KEY_ENTER => Button::Select,
+                _ => continue,
+            };
+
+            // Auto-repeat events of held down keys are ignored
+            let event = match (kind, value) {
+                (EV_KEY, 1) => InputEvent::Pressed(button),
+                (EV_KEY, 0) => InputEvent::Released(button),
+                _ => continue,
+            };
+
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Looks for the input device of the Sense HAT joystick.
+fn find_device() -> Result<PathBuf> {
+    for entry in fs::read_dir("/sys/class/input")? {
+        let entry = entry?;
+        let name = fs::read_to_string(entry.path().join("device/name")).unwrap_or_default();
+
+        let is_event_device = entry.file_name().to_string_lossy().starts_with("event");
+
+        if is_event_device && name.trim() == SENSE_HAT_JOYSTICK {
+            return Ok(PathBuf::from("/dev/input").join(entry.file_name()));
+        }
+    }
+
+    Err(anyhow!("could not find the {}", SENSE_HAT_JOYSTICK))
+}
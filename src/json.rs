@@ -0,0 +1,499 @@
+use anyhow::{anyhow, Result};
+use std::{fmt, iter::Peekable, str::Chars};
+
+/// Deepest nesting of arrays and objects which is parsed. Documents nested
+/// deeper are rejected rather than overflowing the stack, since they can come
+/// from anyone who can reach the HTTP server.
+const MAX_DEPTH: usize = 64;
+
+/// A JSON document, for reading the answers of web APIs, and for writing the
+/// answers of the HTTP server.
+///
+/// Only what is needed to pick values out of a document is supported. A
+/// document is built out of its variants, and serialized by formatting it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Members of an object, in the order they appear in the document.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut chars = text.chars().peekable();
+        let value = parse_value(&mut chars, 0)?;
+
+        skip_whitespace(&mut chars);
+
+        match chars.next() {
+            None => Ok(value),
+            Some(c) => Err(anyhow!("unexpected {:?} after the JSON document", c)),
+        }
+    }
+
+    /// Returns the member `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the element `index` of an array.
+    pub fn index(&self, index: usize) -> Option<&Json> {
+        match self {
+            Json::Array(elements) => elements.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+/// A JSONPath expression picking a single value out of a document, like
+/// `$.sensor.values[0]` or `$['temperature']`.
+///
+/// Only member names and array indices are supported, not wildcards, slices
+/// or filters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    Member(String),
+    Index(usize),
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut chars = path.trim().chars().peekable();
+        let mut steps = Vec::new();
+
+        expect(&mut chars, '$')?;
+
+        while let Some(c) = chars.next() {
+            let step = match c {
+                '.' => {
+                    let mut name = String::new();
+
+                    while let Some(c) = chars.next_if(|c| *c != '.' && *c != '[') {
+                        name.push(c);
+                    }
+
+                    if name.is_empty() {
+                        return Err(anyhow!("missing member name in JSONPath {:?}", path));
+                    }
+
+                    Step::Member(name)
+                }
+                '[' => {
+                    let step = match chars.next_if(|c| *c == '\'' || *c == '"') {
+                        Some(quote) => Step::Member(parse_quoted(&mut chars, quote)?),
+                        None => {
+                            let mut digits = String::new();
+
+                            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                                digits.push(c);
+                            }
+
+                            Step::Index(digits.parse().map_err(|_| {
+                                anyhow!("invalid array index in JSONPath {:?}", path)
+                            })?)
+                        }
+                    };
+
+                    expect(&mut chars, ']')?;
+                    step
+                }
+                c => return Err(anyhow!("unexpected {:?} in JSONPath {:?}", c, path)),
+            };
+
+            steps.push(step);
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// Returns the value the path leads to in `document`, if there is one.
+    pub fn select<'a>(&self, document: &'a Json) -> Option<&'a Json> {
+        self.steps
+            .iter()
+            .try_fold(document, |value, step| match step {
+                Step::Member(name) => value.get(name),
+                Step::Index(index) => value.index(*index),
+            })
+    }
+}
+
+/// Parses a member name quoted with `quote` in a JSONPath, after its opening
+/// quote.
+fn parse_quoted(chars: &mut Input, quote: char) -> Result<String> {
+    let mut name = String::new();
+
+    loop {
+        match chars.next() {
+            Some('\\') => name.extend(chars.next()),
+            Some(c) if c == quote => return Ok(name),
+            Some(c) => name.push(c),
+            None => return Err(anyhow!("unterminated member name in JSONPath")),
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            // JSON has no infinities or NaN
+            Json::Number(number) if !number.is_finite() => write!(f, "null"),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(string) => write_string(f, string),
+            Json::Array(elements) => {
+                write!(f, "[")?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{}", element)?;
+                }
+
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+type Input<'a> = Peekable<Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Input) {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Input, expected: char) -> Result<()> {
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        Some(c) => Err(anyhow!("expected {:?} but found {:?}", expected, c)),
+        None => Err(anyhow!("expected {:?} but the JSON ended", expected)),
+    }
+}
+
+/// Parses the value at `depth` arrays and objects deep.
+fn parse_value(chars: &mut Input, depth: usize) -> Result<Json> {
+    skip_whitespace(chars);
+
+    if depth >= MAX_DEPTH && matches!(chars.peek(), Some('{' | '[')) {
+        return Err(anyhow!("the JSON is nested more than {} deep", MAX_DEPTH));
+    }
+
+    match chars.peek() {
+        Some('{') => parse_object(chars, depth + 1),
+        Some('[') => parse_array(chars, depth + 1),
+        Some('"') => Ok(Json::String(parse_string(chars)?)),
+        Some('t') => parse_literal(chars, "true", Json::Bool(true)),
+        Some('f') => parse_literal(chars, "false", Json::Bool(false)),
+        Some('n') => parse_literal(chars, "null", Json::Null),
+        Some(c) if *c == '-' || c.is_ascii_digit() => parse_number(chars),
+        Some(c) => Err(anyhow!("unexpected {:?} in JSON", c)),
+        None => Err(anyhow!("the JSON ended too early")),
+    }
+}
+
+fn parse_literal(chars: &mut Input, literal: &str, value: Json) -> Result<Json> {
+    for expected in literal.chars() {
+        expect(chars, expected)?;
+    }
+
+    Ok(value)
+}
+
+fn parse_number(chars: &mut Input) -> Result<Json> {
+    let mut number = String::new();
+
+    while let Some(c) = chars.next_if(|c| matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')) {
+        number.push(c);
+    }
+
+    number
+        .parse()
+        .map(Json::Number)
+        .map_err(|_| anyhow!("invalid number {:?} in JSON", number))
+}
+
+fn parse_string(chars: &mut Input) -> Result<String> {
+    expect(chars, '"')?;
+
+    let mut string = String::new();
+
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some('r') => string.push('\r'),
+                Some('b') => string.push('\u{8}'),
+                Some('f') => string.push('\u{c}'),
+                Some('u') => {
+                    let unit = parse_hex4(chars)?;
+
+                    // Characters outside of the BMP are escaped as a pair of
+                    // surrogates
+                    let c = if (0xd800..0xdc00).contains(&unit) {
+                        expect(chars, '\\')?;
+                        expect(chars, 'u')?;
+
+                        let low = parse_hex4(chars)?;
+
+                        char::from_u32(
+                            0x10000 + ((unit - 0xd800) << 10) + (low.wrapping_sub(0xdc00)),
+                        )
+                    } else {
+                        char::from_u32(unit)
+                    };
+
+                    string.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                Some(c) => string.push(c),
+                None => return Err(anyhow!("the JSON ended inside of a string")),
+            },
+            Some(c) => string.push(c),
+            None => return Err(anyhow!("the JSON ended inside of a string")),
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Input) -> Result<u32> {
+    let digits: String = chars.take(4).collect();
+
+    u32::from_str_radix(&digits, 16).map_err(|_| anyhow!("invalid escape \\u{} in JSON", digits))
+}
+
+fn parse_array(chars: &mut Input, depth: usize) -> Result<Json> {
+    expect(chars, '[')?;
+    skip_whitespace(chars);
+
+    let mut elements = Vec::new();
+
+    if chars.next_if_eq(&']').is_some() {
+        return Ok(Json::Array(elements));
+    }
+
+    loop {
+        elements.push(parse_value(chars, depth)?);
+        skip_whitespace(chars);
+
+        match chars.next() {
+            Some(',') => {}
+            Some(']') => return Ok(Json::Array(elements)),
+            _ => return Err(anyhow!("expected ',' or ']' in JSON array")),
+        }
+    }
+}
+
+fn parse_object(chars: &mut Input, depth: usize) -> Result<Json> {
+    expect(chars, '{')?;
+    skip_whitespace(chars);
+
+    let mut members = Vec::new();
+
+    if chars.next_if_eq(&'}').is_some() {
+        return Ok(Json::Object(members));
+    }
+
+    loop {
+        skip_whitespace(chars);
+        let key = parse_string(chars)?;
+        skip_whitespace(chars);
+        expect(chars, ':')?;
+
+        members.push((key, parse_value(chars, depth)?));
+        skip_whitespace(chars);
+
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => return Ok(Json::Object(members)),
+            _ => return Err(anyhow!("expected ',' or '}}' in JSON object")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_documents() {
+        let document =
+            Json::parse(r#" {"a": [1, -2.5e1, true, null], "b": {"c": "d\u00e9\n"}} "#).unwrap();
+
+        assert_eq!(
+            document,
+            Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-25.0),
+                        Json::Bool(true),
+                        Json::Null,
+                    ])
+                ),
+                (
+                    "b".to_string(),
+                    Json::Object(vec![(
+                        "c".to_string(),
+                        Json::String("d\u{e9}\n".to_string())
+                    )])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let text = r#"{"a":[1,2.5,"x\"y"],"b":{},"c":[]}"#;
+
+        assert_eq!(Json::parse(text).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        for text in ["", "[1,", "{\"a\" 1}", "[1] 2", "tru", "\"abc", "{1: 2}"] {
+            assert!(Json::parse(text).is_err(), "{:?} was parsed", text);
+        }
+    }
+
+    #[test]
+    fn parses_nesting_up_to_the_limit() {
+        let text = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+
+        assert!(Json::parse(&text).is_ok());
+    }
+
+    #[test]
+    fn rejects_deeper_nesting() {
+        let text = format!("{}{}", "[".repeat(MAX_DEPTH + 1), "]".repeat(MAX_DEPTH + 1));
+
+        assert!(Json::parse(&text).is_err());
+        assert!(Json::parse(&"{\"a\":".repeat(MAX_DEPTH + 1)).is_err());
+    }
+
+    #[test]
+    fn rejects_deep_nesting_without_overflowing_the_stack() {
+        // Well under the largest request body the server takes
+        assert!(Json::parse(&"[".repeat(60_000)).is_err());
+    }
+
+    fn select(path: &str, document: &str) -> Option<Json> {
+        JsonPath::parse(path)
+            .unwrap()
+            .select(&Json::parse(document).unwrap())
+            .cloned()
+    }
+
+    #[test]
+    fn selects_values_by_path() {
+        let document = r#"{"a": {"b": [10, {"c": "x"}]}, "d.e": 1, "f'g": 2}"#;
+
+        assert_eq!(select("$", "5"), Some(Json::Number(5.0)));
+        assert_eq!(select("$.a.b[0]", document), Some(Json::Number(10.0)));
+        assert_eq!(
+            select("$.a.b[1].c", document),
+            Some(Json::String("x".to_string()))
+        );
+        assert_eq!(select("$['d.e']", document), Some(Json::Number(1.0)));
+        assert_eq!(select(r#"$["f'g"]"#, document), Some(Json::Number(2.0)));
+        assert_eq!(select(r"$['f\'g']", document), Some(Json::Number(2.0)));
+        assert_eq!(select(" $.a.b[0] ", document), Some(Json::Number(10.0)));
+    }
+
+    #[test]
+    fn selects_nothing_where_there_is_no_value() {
+        let document = r#"{"a": [1], "b": "text"}"#;
+
+        assert_eq!(select("$.missing", document), None);
+        assert_eq!(select("$.a[1]", document), None);
+        assert_eq!(select("$.a.b", document), None);
+        assert_eq!(select("$.b[0]", document), None);
+        assert_eq!(select("$[0]", document), None);
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        for path in [
+            "", "a", "$.", "$..a", "$[", "$[a]", "$[-1]", "$[0", "$['a]", "$['a'", "$x",
+        ] {
+            assert!(JsonPath::parse(path).is_err(), "{:?}", path);
+        }
+    }
+}
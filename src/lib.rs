@@ -0,0 +1,1322 @@
+//! Shows text and animations on an 8x8 LED matrix driven by a Raspberry Pi.
+//!
+//! Other crates can add modes of their own, by implementing
+//! [`Animation`](animation::Animation) and registering it with the built-in
+//! ones:
+//!
+//! ```no_run
+//! use led_matrix::{animation::Animation, framebuffer::FrameBuffer};
+//!
+//! struct Blank;
+//!
+//! impl Animation for Blank {
+//!     fn tick(&mut self) {}
+//!
+//!     fn draw(&self, _frame: &mut FrameBuffer) {}
+//! }
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     let scenes = led_matrix::scenes().register("blank", "Nothing", |_| Ok(Box::new(Blank)));
+//!
+//!     led_matrix::run(scenes)
+//! }
+//! ```
+
+mod accelerometer;
+pub mod action;
+pub mod animation;
+mod audio;
+mod ball;
+mod beat;
+mod bits;
+mod calendar;
+mod canvas;
+mod ci;
+mod co2;
+mod command;
+mod compositor;
+mod config;
+mod cpu;
+mod crypto;
+mod data;
+mod dht22;
+mod dice;
+mod dimmer;
+mod disk;
+mod distance;
+mod dither;
+mod dmx;
+mod docker;
+mod ds18b20;
+mod explore;
+pub mod framebuffer;
+mod github;
+mod gpiomem;
+mod http;
+mod icons;
+pub mod input;
+mod interval;
+mod ip;
+mod ir;
+mod joystick;
+mod json;
+mod log;
+mod mail;
+mod matrix;
+mod maze;
+mod mdns;
+mod memory;
+mod metrics;
+pub mod mode;
+mod moon;
+mod morse;
+mod mqtt;
+mod network;
+mod notifications;
+mod opc;
+mod pigpio;
+mod ping;
+mod pong;
+mod reaction;
+mod realtime;
+mod regex;
+mod rss;
+mod sand;
+pub mod scene;
+mod script;
+mod selftest;
+mod server;
+mod simon;
+mod sketch;
+mod snake;
+mod source;
+mod sparkle;
+mod spectrum;
+mod state;
+mod stdin;
+mod stocks;
+mod tail;
+mod tally;
+mod telegram;
+mod temperature;
+mod template;
+mod text;
+mod throttle;
+mod tick;
+mod ticker;
+mod timing;
+mod uart;
+mod vu;
+mod watchdog;
+mod weather;
+mod webhook;
+mod websocket;
+mod whack;
+mod widget;
+
+use accelerometer::Accelerometer;
+use action::Action;
+use animation::Animation;
+use anyhow::{anyhow, Result};
+use ball::Ball;
+use beat::BeatPulse;
+use bits::Bits;
+use calendar::NextEvent;
+use ci::CiStatus;
+use clap::{parser::ValueSource, CommandFactory, FromArgMatches, Parser};
+use co2::Co2;
+use command::DisplayCommand;
+use config::{Config, LayerConfig};
+use cpu::CpuLoad;
+use data::DataDisplay;
+use dht22::Humidity;
+use dice::Dice;
+use dimmer::Dimmer;
+use disk::Disk;
+use distance::Distance;
+use docker::ContainerHealth;
+use ds18b20::ProbeTemperature;
+use explore::Explore;
+use framebuffer::FrameBuffer;
+use github::GithubNotifications;
+use input::{Button, Buttons, Encoder, InputEvent, RotaryEncoder};
+use ip::IpAddress;
+use log::{Level, Span};
+use mail::UnreadMail;
+use matrix::LedMatrix;
+use maze::Maze;
+use memory::Memory;
+use metrics::{Metrics, RateMeter};
+use mode::Mode;
+use moon::Moon;
+use morse::Morse;
+use network::Network;
+use ping::Ping;
+use pong::Pong;
+use reaction::Reaction;
+use rppal::gpio::Gpio;
+use rss::Headlines;
+use sand::Sand;
+use scene::{Registry, SceneContext, SceneManager};
+use script::Script;
+use selftest::SelfTest;
+use simon::Simon;
+use sketch::Sketch;
+use snake::Snake;
+use sparkle::Sparkle;
+use spectrum::Spectrum;
+use state::ScreenState;
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::Ordering,
+        mpsc::{channel, Receiver},
+        Arc, Mutex, MutexGuard, PoisonError, TryLockError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tail::Tail;
+use tally::Tally;
+use temperature::Temperature;
+use text::{Announcements, Messages};
+use throttle::ThrottleWarning;
+use tick::TickReceiver;
+use timing::Timer;
+use vu::VuMeter;
+use watchdog::{Failure, Heartbeat, Watchdog};
+use weather::WeatherReport;
+use webhook::Webhook;
+use whack::Whack;
+
+// Row pin numbers
+const ROW_1: u8 = 8;
+const ROW_2: u8 = 13;
+const ROW_3: u8 = 7;
+const ROW_4: u8 = 11;
+const ROW_5: u8 = 0;
+const ROW_6: u8 = 6;
+const ROW_7: u8 = 1;
+const ROW_8: u8 = 4;
+
+// Column pin numbers
+const COL_1: u8 = 16;
+const COL_2: u8 = 2;
+const COL_3: u8 = 3;
+const COL_4: u8 = 9;
+const COL_5: u8 = 5;
+const COL_6: u8 = 10;
+const COL_7: u8 = 14;
+const COL_8: u8 = 15;
+
+// Button pin numbers
+const BUTTON_UP: u8 = 17;
+const BUTTON_DOWN: u8 = 27;
+const BUTTON_LEFT: u8 = 22;
+const BUTTON_RIGHT: u8 = 23;
+
+// Rotary encoder pin numbers
+const ENCODER_X_A: u8 = 24;
+const ENCODER_X_B: u8 = 25;
+const ENCODER_Y_A: u8 = 20;
+const ENCODER_Y_B: u8 = 21;
+const ENCODER_BRIGHTNESS_A: u8 = 26;
+const ENCODER_BRIGHTNESS_B: u8 = 19;
+const ENCODER_BRIGHTNESS_BUTTON: u8 = 12;
+
+// Button gestures which trigger an action no matter which mode is running,
+// except for the modes played with the buttons, where only holding a button
+// down does
+const GESTURES: [(InputEvent, Action); 4] = [
+    (InputEvent::Pressed(Button::Select), Action::NextMode),
+    (
+        InputEvent::DoublePressed(Button::Right),
+        Action::NextMessage,
+    ),
+    (InputEvent::LongPressed(Button::Left), Action::TogglePower),
+    (
+        InputEvent::LongPressed(Button::Right),
+        Action::CycleBrightness,
+    ),
+];
+
+// Animation frames drawn every second
+const FRAMES_PER_SECOND: u64 = 5;
+
+// Refresh rate below which the display may visibly flicker
+const FLICKER_THRESHOLD_HZ: u64 = 100;
+
+// How long the drawing thread can go without making progress before it is
+// considered stuck and restarted
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Brightness levels the display can be cycled through
+const BRIGHTNESS_LEVELS: [u8; 4] = [u8::MAX, u8::MAX / 2, u8::MAX / 4, u8::MAX / 8];
+
+// Brightness change for every detent of the brightness encoder
+const BRIGHTNESS_STEP: u8 = 16;
+
+#[derive(Parser, Clone)]
+struct Args {
+    /// Configuration file to read
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Message shown by the text mode. Can be given several times, in which
+    /// case the messages are cycled through with a double press of the right
+    /// button. Without it, the messages of the startup section of the
+    /// configuration are shown if there are any
+    #[arg(long = "message", default_value = "I bet you can't do this!")]
+    messages: Vec<String>,
+
+    /// What to show on the display. Without it, the first mode of the
+    /// playlist or the one of the startup section of the configuration is
+    /// shown if there is one
+    #[arg(long, value_parser = Mode::parser(), default_value = "text")]
+    mode: Mode,
+
+    /// File followed by the tail mode, instead of the one of the
+    /// configuration
+    #[arg(long)]
+    file: Option<PathBuf>,
+
+    /// Replace the messages with every line read from standard input
+    #[arg(long)]
+    stdin: bool,
+
+    /// Overlay randomly twinkling pixels on top of the selected mode
+    #[arg(long)]
+    sparkle: bool,
+
+    /// Pulse whatever is shown in time with the music heard by the
+    /// microphone
+    #[arg(long)]
+    beat_pulse: bool,
+
+    /// Show the negative of everything, for panels whose LEDs light up the
+    /// other way around
+    #[arg(long)]
+    invert: bool,
+
+    /// Don't scroll the IP addresses of the Pi once on startup
+    #[arg(long)]
+    no_startup_ip: bool,
+
+    /// Least important kind of messages which are logged
+    #[arg(long, value_enum, default_value_t = Level::Info)]
+    log_level: Level,
+
+    /// Accelerometer used by the modes which react to tilting the board
+    #[arg(long, value_enum, default_value_t = accelerometer::Model::Mpu6050)]
+    accelerometer: accelerometer::Model,
+}
+
+/// Registers every mode the display can show, in the order they are cycled
+/// through. New modes only have to be added here to be selectable by name.
+pub fn scenes() -> Registry {
+    Registry::new()
+        .register("text", "Scrolling text", |context| {
+            Ok(Box::new(Messages::new(context.messages.clone())))
+        })
+        .register("morse", "The messages flashed in Morse code", |context| {
+            Ok(Box::new(Morse::new(
+                context.config.morse.clone(),
+                context.messages.clone(),
+            )))
+        })
+        .register(
+            "tail",
+            "New lines of a file, like a log, as they are written",
+            |context| {
+                let mut config = context.config.tail.clone();
+
+                if let Some(file) = &context.file {
+                    config.file = file.clone();
+                }
+
+                Ok(Box::new(Tail::new(config)?))
+            },
+        )
+        .register(
+            "bits",
+            "The message as the bits of a 64-bit value",
+            |context| Ok(Box::new(Bits::new(context.messages.clone()))),
+        )
+        .register(
+            "test",
+            "Test patterns for finding dead LEDs and wiring faults",
+            |_| Ok(Box::new(SelfTest::new())),
+        )
+        .register("sparkle", "Randomly twinkling pixels", |_| {
+            Ok(Box::new(Sparkle::new()))
+        })
+        .register("ball", "A ball bouncing around under gravity", |_| {
+            Ok(Box::new(Ball::new()))
+        })
+        .register(
+            "snake",
+            "The Snake game, played with the direction buttons",
+            |_| Ok(Box::new(Snake::new())),
+        )
+        .register(
+            "pong",
+            "Pong against the computer or a second player",
+            |_| Ok(Box::new(Pong::new())),
+        )
+        .register("simon", "Simon-style memory game", |_| {
+            Ok(Box::new(Simon::new()))
+        })
+        .register("reaction", "Reaction timer game", |_| {
+            Ok(Box::new(Reaction::new()))
+        })
+        .register("dice", "A die rolled with any button", |_| {
+            Ok(Box::new(Dice::new()))
+        })
+        .register("whack", "Whack-a-mole game", |_| Ok(Box::new(Whack::new())))
+        .register(
+            "tally",
+            "A count kept across restarts, changed with the buttons",
+            |context| Ok(Box::new(Tally::new(context.config.tally.clone())?)),
+        )
+        .register(
+            "sketch",
+            "Etch-a-sketch drawing with the rotary encoders",
+            |_| Ok(Box::new(Sketch::new())),
+        )
+        .register(
+            "sand",
+            "Falling sand which follows the tilt of the board",
+            |context| {
+                Ok(Box::new(Sand::new(Accelerometer::new(
+                    context.accelerometer,
+                )?)))
+            },
+        )
+        .register("maze", "A maze solved by tilting the board", |context| {
+            Ok(Box::new(Maze::new(Accelerometer::new(
+                context.accelerometer,
+            )?)))
+        })
+        .register(
+            "explore",
+            "A large cave to look around, panned with the buttons or encoders",
+            |_| Ok(Box::new(Explore::new())),
+        )
+        .register("cpu", "Bar graph of the recent CPU load", |_| {
+            Ok(Box::new(CpuLoad::new()))
+        })
+        .register("memory", "Gauge of the RAM in use", |_| {
+            Ok(Box::new(Memory::new()))
+        })
+        .register("disk", "Fill level of a disk", |context| {
+            Ok(Box::new(Disk::new(context.config.disk.clone())))
+        })
+        .register(
+            "network",
+            "Bar graphs of the traffic of a network interface",
+            |context| Ok(Box::new(Network::new(context.config.network.clone()))),
+        )
+        .register("ping", "Whether a list of hosts answer pings", |context| {
+            Ok(Box::new(Ping::new(context.config.ping.clone())))
+        })
+        .register("ip", "The IPv4 addresses of the Pi", |_| {
+            Ok(Box::new(IpAddress::new()))
+        })
+        .register("temperature", "Temperature of the Pi's SoC", |context| {
+            Ok(Box::new(Temperature::new(
+                context.config.temperature.clone(),
+            )))
+        })
+        .register(
+            "ds18b20",
+            "Temperature measured by a DS18B20 sensor, and its trend",
+            |context| {
+                Ok(Box::new(ProbeTemperature::new(
+                    context.config.ds18b20.clone(),
+                )?))
+            },
+        )
+        .register(
+            "dht22",
+            "Temperature and humidity measured by a DHT22 sensor",
+            |context| Ok(Box::new(Humidity::new(context.config.dht22.clone())?)),
+        )
+        .register(
+            "distance",
+            "Distance measured by an HC-SR04 sensor, like a parking sensor",
+            |context| {
+                let config = context
+                    .config
+                    .distance
+                    .clone()
+                    .ok_or_else(|| anyhow!("the distance sensor isn't configured"))?;
+
+                Ok(Box::new(Distance::new(config)?))
+            },
+        )
+        .register(
+            "co2",
+            "CO2 concentration measured by an air quality sensor",
+            |context| Ok(Box::new(Co2::new(context.config.co2.clone())?)),
+        )
+        .register(
+            "vu",
+            "Sound level heard by a microphone, with peak hold",
+            |context| Ok(Box::new(VuMeter::new(&context.config.audio)?)),
+        )
+        .register(
+            "spectrum",
+            "Loudness of the frequency bands heard by a microphone",
+            |context| Ok(Box::new(Spectrum::new(&context.config.audio)?)),
+        )
+        .register(
+            "weather",
+            "Current weather, fetched from the internet",
+            |context| {
+                let config = context
+                    .config
+                    .weather
+                    .clone()
+                    .ok_or_else(|| anyhow!("the weather location isn't configured"))?;
+
+                Ok(Box::new(WeatherReport::new(config)))
+            },
+        )
+        .register("moon", "Current phase of the moon", |_| {
+            Ok(Box::new(Moon::new()))
+        })
+        .register(
+            "stocks",
+            "Stock prices, fetched from the internet",
+            |context| Ok(Box::new(stocks::ticker(context.config.stocks.clone()))),
+        )
+        .register(
+            "crypto",
+            "Cryptocurrency prices, fetched from the internet",
+            |context| Ok(Box::new(crypto::ticker(context.config.crypto.clone()))),
+        )
+        .register(
+            "rss",
+            "Latest headlines of an RSS or Atom feed",
+            |context| {
+                let config = context
+                    .config
+                    .rss
+                    .clone()
+                    .ok_or_else(|| anyhow!("the RSS feed isn't configured"))?;
+
+                Ok(Box::new(Headlines::new(config)))
+            },
+        )
+        .register(
+            "github",
+            "Number of unread GitHub notifications",
+            |context| {
+                let config = context
+                    .config
+                    .github
+                    .clone()
+                    .ok_or_else(|| anyhow!("the GitHub token isn't configured"))?;
+
+                Ok(Box::new(GithubNotifications::new(config)))
+            },
+        )
+        .register(
+            "mqtt",
+            "A value of the messages of an MQTT topic",
+            |context| {
+                let config = context
+                    .config
+                    .mqtt
+                    .clone()
+                    .ok_or_else(|| anyhow!("the MQTT topic isn't configured"))?;
+
+                Ok(Box::new(mqtt::ticker(config)?))
+            },
+        )
+        .register(
+            "data",
+            "Values fetched from URLs or commands, shown through a template",
+            |context| {
+                let config = context
+                    .config
+                    .data
+                    .clone()
+                    .ok_or_else(|| anyhow!("the data mode isn't configured"))?;
+
+                Ok(Box::new(DataDisplay::new(config, context.sources.clone())))
+            },
+        )
+        .register(
+            "docker",
+            "Health of the Docker containers, a pixel each",
+            |context| {
+                Ok(Box::new(ContainerHealth::new(
+                    context.config.docker.clone(),
+                )))
+            },
+        )
+        .register(
+            "ci",
+            "Whether the latest CI builds of some repositories passed",
+            |context| {
+                let config = context
+                    .config
+                    .ci
+                    .clone()
+                    .ok_or_else(|| anyhow!("the CI repositories aren't configured"))?;
+
+                Ok(Box::new(CiStatus::new(config)))
+            },
+        )
+        .register(
+            "mail",
+            "Number of unread messages in an IMAP mailbox",
+            |context| {
+                let config = context
+                    .config
+                    .imap
+                    .clone()
+                    .ok_or_else(|| anyhow!("the IMAP mailbox isn't configured"))?;
+
+                Ok(Box::new(UnreadMail::new(config)))
+            },
+        )
+        .register("calendar", "Next event of a calendar", |context| {
+            let config = context
+                .config
+                .calendar
+                .clone()
+                .ok_or_else(|| anyhow!("the calendar isn't configured"))?;
+
+            Ok(Box::new(NextEvent::new(config)))
+        })
+        .register(
+            "script",
+            "An animation written as an expression in the configuration",
+            |context| {
+                let config = context
+                    .config
+                    .script
+                    .clone()
+                    .ok_or_else(|| anyhow!("the script isn't configured"))?;
+
+                Ok(Box::new(Script::new(config)?))
+            },
+        )
+}
+
+/// Runs the display with the modes of `scenes`, which are usually the ones of
+/// [`scenes`] with others added by the caller, until it fails.
+pub fn run(scenes: Registry) -> Result<()> {
+    // The modes have to be known before they are selected on the command line
+    scenes.install();
+
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+    log::set_max_level(args.log_level);
+
+    let config = Config::load(args.config.as_deref())?;
+
+    // What was saved from the dashboard takes precedence over the defaults of
+    // the command line, but not over what is given on it
+    if let Some(mode) = config.startup.mode.filter(|_| !given("mode")) {
+        args.mode = mode;
+    }
+
+    if !config.startup.messages.is_empty() && !given("messages") {
+        args.messages = config.startup.messages.clone();
+    }
+
+    // The playlist starts with its first mode, unless one is selected on the
+    // command line
+    if let Some(entry) = config.playlist.first().filter(|_| !given("mode")) {
+        args.mode = entry.mode;
+    }
+
+    log::info!("Starting in {} mode", args.mode.name());
+
+    // Handle everything controlling the display sends its commands to the
+    // drawing thread through
+    let (display_handle, commands_rx) = command::channel();
+
+    // Channel used to send time tick messages to the thread where the drawing
+    // will take place. Ticks which come while the thread is still busy are
+    // dropped rather than queued
+    let (tx, rx) = tick::channel();
+
+    // State of the screen the last time the drawing thread ran, if it ever
+    // did, and the last frame it drew
+    let last_state = Arc::new(Mutex::new(None));
+    let last_frame = Arc::new(Mutex::new(FrameBuffer::new()));
+
+    let metrics = Arc::new(Metrics::new());
+    metrics
+        .target_fps
+        .store(FRAMES_PER_SECOND, Ordering::Relaxed);
+
+    let server_address = match config.server.clone() {
+        Some(server) => {
+            let webhook = match &server.webhook {
+                Some(webhook) => Some(Arc::new(Webhook::new(webhook)?)),
+                None => None,
+            };
+            let token = server.token.clone();
+
+            // `?token=` would give an empty token
+            if token
+                .as_deref()
+                .is_some_and(|token| token.trim().is_empty())
+            {
+                return Err(anyhow!("the token of the server can't be empty"));
+            }
+
+            Some(server::spawn(
+                server,
+                server::State {
+                    metrics: metrics.clone(),
+                    display: display_handle.clone(),
+                    screen: last_state.clone(),
+                    frame: last_frame.clone(),
+                    config_path: args.config.clone(),
+                    started: Instant::now(),
+                    token,
+                    webhook,
+                },
+            )?)
+        }
+        None => None,
+    };
+
+    if let Some(mdns) = config.mdns.clone() {
+        match server_address {
+            Some(address) => mdns::spawn(mdns, address.port())?,
+            None => log::warn!("There is no HTTP server to advertise over mDNS"),
+        }
+    }
+
+    let gpio = Gpio::new()?;
+
+    // The display outlives the drawing thread, so that it can be blanked and
+    // handed to a new one if the thread dies
+    let display = Arc::new(Mutex::new(LedMatrix::new(
+        &gpio,
+        &config.refresh,
+        ROW_1,
+        ROW_2,
+        ROW_3,
+        ROW_4,
+        ROW_5,
+        ROW_6,
+        ROW_7,
+        ROW_8,
+        COL_1,
+        COL_2,
+        COL_3,
+        COL_4,
+        COL_5,
+        COL_6,
+        COL_7,
+        COL_8,
+    )?));
+
+    let buttons = Buttons::new(
+        &gpio,
+        &[
+            (Button::Up, BUTTON_UP),
+            (Button::Down, BUTTON_DOWN),
+            (Button::Left, BUTTON_LEFT),
+            (Button::Right, BUTTON_RIGHT),
+            (Button::Select, ENCODER_BRIGHTNESS_BUTTON),
+        ],
+    )?;
+
+    let encoders = vec![
+        (
+            Encoder::X,
+            RotaryEncoder::new(&gpio, ENCODER_X_A, ENCODER_X_B)?,
+        ),
+        (
+            Encoder::Y,
+            RotaryEncoder::new(&gpio, ENCODER_Y_A, ENCODER_Y_B)?,
+        ),
+        (
+            Encoder::Brightness,
+            RotaryEncoder::new(&gpio, ENCODER_BRIGHTNESS_A, ENCODER_BRIGHTNESS_B)?,
+        ),
+    ];
+
+    // The input devices are polled on their own thread, so that presses
+    // aren't missed while the display is being refreshed
+    let (input_tx, input_rx) = channel();
+
+    input::spawn(buttons, encoders, input_tx.clone());
+
+    if let Some(joystick) = config.joystick.clone() {
+        joystick::spawn(joystick, input_tx)?;
+    }
+
+    // Other sources of actions, like the IR remote
+    if let Some(ir) = config.ir.clone() {
+        ir::spawn(ir, display_handle.clone());
+    }
+
+    if let Some(dmx) = config.dmx.clone() {
+        dmx::spawn(dmx, display_handle.clone())?;
+    }
+
+    if let Some(opc) = config.opc.clone() {
+        opc::spawn(opc, display_handle.clone())?;
+    }
+
+    if let Some(uart) = config.uart.clone() {
+        uart::spawn(uart, display_handle.clone())?;
+    }
+
+    if let Some(notifications) = config.notifications.clone() {
+        notifications::spawn(notifications, display_handle.clone());
+    }
+
+    if args.stdin {
+        stdin::spawn(display_handle.clone());
+    }
+
+    if let Some(telegram) = config.telegram.clone() {
+        telegram::spawn(telegram, display_handle);
+    }
+
+    let dimmer = config
+        .refresh
+        .pwm_dimming
+        .as_ref()
+        .map(Dimmer::new)
+        .transpose()?;
+
+    let shared = Shared {
+        display: display.clone(),
+        dimmer,
+        channels: Arc::new(Mutex::new(Channels {
+            ticks: rx,
+            input: input_rx,
+            commands: commands_rx,
+        })),
+        metrics: metrics.clone(),
+        last_state,
+        last_frame,
+    };
+
+    // The data sources are shared by every drawing thread, so that restarting
+    // one doesn't leave their threads behind
+    let sources = source::spawn(&config.sources)?;
+
+    // The first drawing thread tells whether it could create the screen, since
+    // the animations can't be sent to it from here
+    let (started_tx, started_rx) = channel();
+    let started = Mutex::new(Some(started_tx));
+
+    // We are using a new thread because we need to sleep on the main thread in
+    // order to animate the text scrolling. It is started again with the last
+    // state it had if it panics or gets stuck
+    let mut drawing = Watchdog::spawn("drawing", STALL_TIMEOUT, move |heartbeat| {
+        // Without the permission to, the display is refreshed anyway, just
+        // with the odd flicker when another process gets in the way
+        if let Some(priority) = config.refresh.realtime_priority {
+            match realtime::set_fifo_priority(priority) {
+                Ok(()) => log::info!("Refreshing with real-time priority {}", priority),
+                Err(err) => log::warn!("Failed to set real-time priority: {:#}", err),
+            }
+        }
+
+        if let Some(cpu) = config.refresh.cpu {
+            match realtime::pin_to_cpu(cpu) {
+                Ok(()) => log::info!("Refreshing on CPU core {}", cpu),
+                Err(err) => log::warn!("Failed to pin to CPU core {}: {:#}", cpu, err),
+            }
+        }
+
+        let screen = match lock(&started).take() {
+            Some(started) => match Screen::new(args.clone(), config.clone(), sources.clone()) {
+                Ok(screen) => {
+                    let _ = started.send(Ok(()));
+                    screen
+                }
+                Err(err) => {
+                    let _ = started.send(Err(err));
+                    return;
+                }
+            },
+            None => {
+                let last_state = lock(&shared.last_state).clone();
+
+                match Screen::recreate(&args, &config, &sources, last_state) {
+                    Ok(screen) => screen,
+                    Err(err) => {
+                        log::error!("Failed to create the screen again: {:#}", err);
+                        return;
+                    }
+                }
+            }
+        };
+
+        run_display(screen, &shared, &heartbeat);
+    });
+
+    // Nothing is drawn if the screen can't be created, like when the mode
+    // needs hardware which isn't there
+    match started_rx.recv() {
+        Ok(result) => result?,
+        Err(_) => return Err(anyhow!("the drawing thread panicked before it started")),
+    }
+
+    let mut timer = Timer::new(Duration::from_secs(1) / FRAMES_PER_SECOND as u32)?;
+
+    loop {
+        // Wait until the next frame should be rendered
+        let ticks = timer.wait()?;
+
+        // Notify the drawing thread that the next frame transition should be
+        // rendered. Periods this thread missed count as ticks too, which the
+        // channel then drops
+        for _ in 0..ticks {
+            tx.send();
+        }
+
+        metrics.ticks_sent.fetch_add(ticks, Ordering::Relaxed);
+
+        if let Some(failure) = drawing.check() {
+            match failure {
+                Failure::Panicked => log::error!("The drawing thread panicked"),
+                Failure::Stalled => log::error!(
+                    "The drawing thread made no progress for {:?}",
+                    STALL_TIMEOUT
+                ),
+            }
+
+            // An LED could have been left on halfway through a refresh. A
+            // thread stuck while refreshing still holds the display, which
+            // can't be helped
+            match display.try_lock() {
+                Ok(mut display) => display.blank(),
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().blank(),
+                Err(TryLockError::WouldBlock) => {
+                    log::warn!("The display is still in use and can't be blanked")
+                }
+            }
+
+            drawing.restart();
+        }
+    }
+}
+
+/// Locks `mutex`, even if a thread panicked while holding it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Receiving ends of the channels the drawing thread is sent everything
+/// through.
+struct Channels {
+    ticks: TickReceiver,
+    input: Receiver<InputEvent>,
+    commands: Receiver<DisplayCommand>,
+}
+
+/// Everything which outlives a drawing thread, for the next one to pick up.
+struct Shared {
+    display: Arc<Mutex<LedMatrix>>,
+    dimmer: Option<Dimmer>,
+    channels: Arc<Mutex<Channels>>,
+    metrics: Arc<Metrics>,
+    // State of the screen the last time the thread ran, if it ever did
+    last_state: Arc<Mutex<Option<ScreenState>>>,
+    last_frame: Arc<Mutex<FrameBuffer>>,
+}
+
+/// Animates `screen` and refreshes the display with it until the thread is
+/// abandoned.
+fn run_display(mut screen: Screen, shared: &Shared, heartbeat: &Heartbeat) {
+    let metrics = &shared.metrics;
+    let mut frame = FrameBuffer::new();
+
+    let mut refresh_rate = RateMeter::new();
+    let mut fps = RateMeter::new();
+    // When the last refresh started, to measure how long refresh cycles take
+    let mut last_flush = None;
+    // Whether the refresh rate was below the flicker threshold, so that
+    // it's only warned about when it drops below it
+    let mut flickering = false;
+    // Brightness the hardware dimmer was last set to
+    let mut dimmed = None;
+
+    *lock(&shared.last_state) = Some(screen.state());
+
+    while !heartbeat.abandoned() {
+        heartbeat.beat();
+
+        if let Some(rate) = refresh_rate.rate() {
+            metrics.refresh_rate.store(rate, Ordering::Relaxed);
+
+            let was_flickering = flickering;
+            flickering = rate < FLICKER_THRESHOLD_HZ;
+
+            if flickering && !was_flickering {
+                log::warn!(
+                    "Refreshing at {} Hz, below {} Hz the display may flicker",
+                    rate,
+                    FLICKER_THRESHOLD_HZ
+                );
+            }
+        }
+
+        if let Some(rate) = fps.rate() {
+            metrics.fps.store(rate, Ordering::Relaxed);
+        }
+
+        // Everything is taken out of the channels at once, so that they
+        // aren't held by a thread which gets stuck handling it
+        let (tick, events, commands) = {
+            let channels = lock(&shared.channels);
+
+            (
+                channels.ticks.try_recv(),
+                channels.input.try_iter().collect::<Vec<_>>(),
+                channels.commands.try_iter().collect::<Vec<_>>(),
+            )
+        };
+
+        // The state only changes with what came through the channels
+        let changed = tick.is_some() || !events.is_empty() || !commands.is_empty();
+
+        // If we get a frame tick, then we advance every animation
+        if let Some(ticks) = tick {
+            metrics.frames.fetch_add(1, Ordering::Relaxed);
+            fps.record();
+
+            // More than one tick means the previous frame took so long that
+            // the next ones were missed
+            if ticks > 1 {
+                metrics
+                    .dropped_ticks
+                    .fetch_add(ticks - 1, Ordering::Relaxed);
+                log::debug!("Dropped {} frame ticks", ticks - 1);
+            }
+
+            screen.tick();
+        }
+
+        for event in events {
+            screen.handle_input(event);
+
+            let interactive = screen.scenes.is_interactive();
+
+            let action = match event {
+                InputEvent::Turned(Encoder::Brightness, detents) => {
+                    Some(Action::AdjustBrightness(detents))
+                }
+                // Pressing select plays the mode, so it is left by holding
+                // select down instead, and the other gestures are the mode's
+                // too
+                InputEvent::LongPressed(Button::Select) if interactive => Some(Action::NextMode),
+                _ if interactive => None,
+                _ => GESTURES
+                    .iter()
+                    .find(|(gesture, _)| *gesture == event)
+                    .map(|&(_, action)| action),
+            };
+
+            if let Some(action) = action {
+                screen.apply(action);
+            }
+        }
+
+        for command in commands {
+            screen.handle(command);
+        }
+
+        if changed {
+            *lock(&shared.last_state) = Some(screen.state());
+        }
+
+        if let Some(dimmer) = &shared.dimmer {
+            if dimmed != Some(screen.brightness) {
+                if let Err(err) = dimmer.set_brightness(screen.brightness) {
+                    log::warn!("Failed to dim the display: {:#}", err);
+                }
+
+                dimmed = Some(screen.brightness);
+            }
+        }
+
+        metrics
+            .brightness
+            .store(screen.brightness as u64, Ordering::Relaxed);
+
+        // While the display is off there is nothing to refresh
+        if !screen.powered {
+            // The LEDs lit by the last refresh, or by a pigpio waveform which
+            // keeps running on its own, are turned off once
+            if last_flush.is_some() {
+                lock(&shared.display).blank();
+            }
+
+            // Being off isn't flickering, so the refresh rate is measured
+            // again from when it's back on
+            metrics.refresh_rate.store(0, Ordering::Relaxed);
+            refresh_rate = RateMeter::new();
+            last_flush = None;
+
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+
+        let render_start = Instant::now();
+
+        {
+            let _span = Span::new(Level::Trace, "frame render");
+            screen.draw(&mut frame);
+        }
+
+        lock(&shared.last_frame).clone_from(&frame);
+
+        metrics.record_render(render_start.elapsed());
+
+        let flush_start = Instant::now();
+
+        if let Some(last_flush) = last_flush {
+            metrics.cycle_times.record(flush_start - last_flush);
+        }
+
+        last_flush = Some(flush_start);
+
+        {
+            let _span = Span::new(Level::Trace, "flush");
+            lock(&shared.display).flush(&frame);
+        }
+
+        metrics.record_flush(flush_start.elapsed());
+        refresh_rate.record();
+    }
+}
+
+/// Everything shown on the display, and how.
+struct Screen {
+    config: Config,
+    // The current mode, with everything drawn on top of it
+    scenes: SceneManager,
+    // Messages scrolled once on top of everything else
+    announcements: Announcements,
+    // Frame shown instead of the mode, until it is cleared
+    shown_frame: Option<FrameBuffer>,
+    powered: bool,
+    paused: bool,
+    inverted: bool,
+    brightness: u8,
+}
+
+impl Screen {
+    fn new(args: Args, config: Config, sources: source::Values) -> Result<Self> {
+        let context = SceneContext {
+            messages: args.messages.clone(),
+            accelerometer: args.accelerometer,
+            file: args.file.clone(),
+            sources,
+            config: config.clone(),
+        };
+        let factory = Box::new(move |mode: Mode| mode.create(&context));
+
+        let mut scenes = SceneManager::new(
+            args.mode,
+            factory,
+            args.messages.clone(),
+            config.layers.crossfade_frames,
+        )?;
+        scenes.set_playlist(config.playlist.clone());
+
+        let mut push = |animation: Box<dyn Animation>, layer: LayerConfig| {
+            scenes.push_layer(animation, layer.blend, layer.opacity);
+        };
+
+        if args.sparkle {
+            push(Box::new(Sparkle::new()), config.layers.sparkle);
+        }
+
+        if args.beat_pulse {
+            push(
+                Box::new(BeatPulse::new(&config.audio)?),
+                config.layers.beat_pulse,
+            );
+        }
+
+        push(
+            Box::new(ThrottleWarning::new()),
+            config.layers.throttle_warning,
+        );
+
+        if !args.no_startup_ip && args.mode.name() != "ip" {
+            push(Box::new(IpAddress::once()), config.layers.startup_ip);
+        }
+
+        Ok(Self {
+            inverted: args.invert,
+            brightness: config.startup.brightness,
+            config,
+            scenes,
+            announcements: Announcements::new(),
+            shown_frame: None,
+            powered: true,
+            paused: false,
+        })
+    }
+
+    /// Creates the screen of a drawing thread started again, as it was in
+    /// `state` if possible, or as it was on startup otherwise.
+    fn recreate(
+        args: &Args,
+        config: &Config,
+        sources: &source::Values,
+        state: Option<ScreenState>,
+    ) -> Result<Self> {
+        if let Some(state) = state {
+            match Self::restore(args.clone(), config.clone(), sources.clone(), state) {
+                Ok(screen) => return Ok(screen),
+                Err(err) => log::error!("Failed to restore the screen: {:#}", err),
+            }
+        }
+
+        Self::new(args.clone(), config.clone(), sources.clone())
+    }
+
+    /// Creates the screen again as it was in `state`, without what is only
+    /// shown on startup.
+    fn restore(
+        args: Args,
+        config: Config,
+        sources: source::Values,
+        state: ScreenState,
+    ) -> Result<Self> {
+        let args = Args {
+            mode: state.mode,
+            messages: state.messages.to_vec(),
+            no_startup_ip: true,
+            ..args
+        };
+
+        let mut screen = Self {
+            powered: state.powered,
+            paused: state.paused,
+            inverted: state.inverted,
+            brightness: state.brightness,
+            ..Self::new(args, config, sources)?
+        };
+
+        // The announcement which was being scrolled starts over
+        for message in state.announcement.into_iter().chain(state.queued) {
+            screen.announcements.push(message);
+        }
+
+        Ok(screen)
+    }
+
+    fn state(&self) -> ScreenState {
+        ScreenState {
+            mode: self.scenes.mode(),
+            messages: self.scenes.messages(),
+            announcement: self.announcements.current().map(str::to_string),
+            queued: self.announcements.queued().map(str::to_string).collect(),
+            powered: self.powered,
+            paused: self.paused,
+            inverted: self.inverted,
+            brightness: self.brightness,
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.paused {
+            return;
+        }
+
+        self.scenes.tick();
+        self.announcements.tick();
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        self.scenes.handle_input(event);
+    }
+
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::TogglePower => self.powered = !self.powered,
+            Action::PowerOn => self.powered = true,
+            Action::PowerOff => self.powered = false,
+            Action::TogglePause => self.paused = !self.paused,
+            Action::Pause => self.paused = true,
+            Action::Resume => self.paused = false,
+            Action::ToggleInvert => self.inverted = !self.inverted,
+            Action::CycleBrightness => {
+                // Go to the next dimmer level, or back to the brightest
+                self.brightness = BRIGHTNESS_LEVELS
+                    .into_iter()
+                    .find(|&level| level < self.brightness)
+                    .unwrap_or(u8::MAX);
+            }
+            Action::AdjustBrightness(steps) => {
+                let change = steps * BRIGHTNESS_STEP as i32;
+
+                self.brightness = (self.brightness as i32 + change).clamp(0, u8::MAX as i32) as u8;
+            }
+            Action::SetBrightness(level) => self.brightness = level,
+            _ => self.scenes.handle_action(action),
+        }
+    }
+
+    fn handle(&mut self, command: DisplayCommand) {
+        match command {
+            DisplayCommand::Action(action) => self.apply(action),
+            DisplayCommand::Announce(message) => self.announcements.push(message),
+            DisplayCommand::Alert(message, priority) => {
+                self.announcements.push_with_priority(message, priority)
+            }
+            DisplayCommand::SetText(text) => self.scenes.set_messages(&[text]),
+            DisplayCommand::SetMessages(messages) => {
+                if !messages.is_empty() {
+                    self.scenes.set_messages(&messages);
+                }
+            }
+            DisplayCommand::SetBrightness(level) => self.apply(Action::SetBrightness(level)),
+            DisplayCommand::ShowFrame(frame) => self.shown_frame = Some(frame),
+            DisplayCommand::Pause => self.apply(Action::Pause),
+            DisplayCommand::Resume => self.apply(Action::Resume),
+            DisplayCommand::PowerOn => self.apply(Action::PowerOn),
+            DisplayCommand::PowerOff => self.apply(Action::PowerOff),
+            DisplayCommand::Clear => {
+                self.shown_frame = None;
+                self.announcements = Announcements::new();
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        frame.clear_screen();
+
+        match &self.shown_frame {
+            Some(shown) => frame.overlay(shown),
+            None => self.scenes.draw(frame),
+        }
+        self.announcements.draw(frame);
+
+        if self.inverted {
+            frame.invert();
+        }
+
+        // Hardware dimming leaves the frame at full brightness
+        if self.config.refresh.pwm_dimming.is_none() {
+            frame.dim(self.brightness);
+        }
+
+        if let Some(dithering) = self.config.refresh.dithering {
+            dither::dither(frame, dithering);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use clap::ValueEnum;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// How important a log message is, from the most to the least.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+/// Least important level which is logged.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// When the program started, which log messages are timed from.
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Sets the least important level which is logged.
+pub fn set_max_level(level: Level) {
+    START.get_or_init(Instant::now);
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns whether messages of `level` are logged.
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Writes a message to stderr if its level is enabled. Use the macros of this
+/// module rather than calling this directly.
+pub fn log(level: Level, args: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+
+    let uptime = START.get_or_init(Instant::now).elapsed();
+
+    eprintln!(
+        "{:>10.3} {:<5} {}",
+        uptime.as_secs_f64(),
+        level.name(),
+        args
+    );
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Error, format_args!($($arg)*)) };
+}
+
+// Named differently from the built-in `warn` attribute, which a macro named
+// `warn` would be ambiguous with
+macro_rules! warning {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+
+pub(crate) use {debug, error, info, warning as warn};
+
+/// Times something from its creation until it is dropped, and then logs how
+/// long it took.
+///
+/// Nothing is measured if the level of the span isn't enabled, so spans are
+/// cheap enough to wrap code which runs on every refresh.
+pub struct Span {
+    name: &'static str,
+    level: Level,
+    start: Option<Instant>,
+}
+
+impl Span {
+    pub fn new(level: Level, name: &'static str) -> Self {
+        Self {
+            name,
+            level,
+            start: enabled(level).then(Instant::now),
+        }
+    }
+
+    /// Returns how long the span lasted so far, if it is being measured.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.start.map(|start| start.elapsed())
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(elapsed) = self.elapsed() {
+            log(self.level, format_args!("{} took {:?}", self.name, elapsed));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    animation::Animation,
+    config::ImapConfig,
+    framebuffer::FrameBuffer,
+    http,
+    icons::{self, ENVELOPE},
+    log,
+    widget::show_number,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before trying again after a failed check.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Number of frames the envelope and the count are each shown for before
+/// switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// Number of frames the display flashes for when new mail comes in.
+const FLASH_FRAMES: u32 = 10;
+
+/// Asks the IMAP server how many unread messages the mailbox has.
+fn fetch(config: &ImapConfig) -> Result<u32> {
+    let url = format!("imaps://{}/", config.server);
+    let user = format!("{}:{}", config.user, config.password);
+    let request = format!(
+        "STATUS \"{}\" (UNSEEN)",
+        config.mailbox.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+
+    // `* STATUS INBOX (UNSEEN 3)`
+    let response = http::curl(&[("url", &url), ("user", &user), ("request", &request)])?;
+
+    response
+        .split_once("UNSEEN ")
+        .and_then(|(_, rest)| {
+            rest.split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        })
+        .ok_or_else(|| anyhow!("unexpected answer from the IMAP server"))
+}
+
+/// Shows how many unread messages an IMAP mailbox has.
+///
+/// It alternates between an envelope and the count, and flashes when the
+/// count goes up.
+pub struct UnreadMail {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<u32>>,
+    count: Option<u32>,
+    frame_count: u32,
+    flash_frames: u32,
+}
+
+impl UnreadMail {
+    pub fn new(config: ImapConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Checking takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch(&config) {
+                Ok(count) => (Some(count), Duration::from_secs(config.interval_secs)),
+                Err(err) => {
+                    log::warn!("IMAP: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            count: None,
+            frame_count: 0,
+            flash_frames: 0,
+        }
+    }
+}
+
+impl Animation for UnreadMail {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+        self.flash_frames = self.flash_frames.saturating_sub(1);
+
+        if let Some(count) = self.updates.try_iter().flatten().last() {
+            if self.count.is_some_and(|previous| count > previous) {
+                self.flash_frames = FLASH_FRAMES;
+            }
+
+            self.count = Some(count);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(count) = self.count else {
+            return;
+        };
+
+        if self.flash_frames > 0 {
+            if self.flash_frames % 2 == 1 {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            } else {
+                icons::draw_icon(&ENVELOPE, Point::zero(), frame);
+            }
+
+            return;
+        }
+
+        if (self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            icons::draw_icon(&ENVELOPE, Point::zero(), frame);
+        } else {
+            let Ok(()) = show_number(count as i64)
+                .at_frame(self.frame_count % VIEW_FRAMES)
+                .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{
+    config::RefreshConfig,
+    framebuffer::{FrameBuffer, HEIGHT, PWM_STEPS, WIDTH},
+    gpiomem::GpioMem,
+    log,
+    pigpio::{Pigpio, Pulse},
+    timing,
+};
+use anyhow::Result;
+use rppal::gpio::{Gpio, OutputPin};
+use serde::Deserialize;
+use std::{thread, time::Duration};
+
+/// How the pins of the matrix are written.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    /// A pin at a time, through rppal.
+    #[default]
+    Rppal,
+    /// Every pin which changes at once, by writing the GPIO registers mapped
+    /// through `/dev/gpiomem`. This is much faster, which allows higher
+    /// refresh rates.
+    Gpiomem,
+    /// Waveforms played by the pigpio daemon, timed by DMA. The CPU is only
+    /// needed when the frame changes, and the refresh never flickers.
+    Pigpio,
+}
+
+/// How long every row is lit for.
+const ROW_ON_TIME: Duration = Duration::from_micros(5);
+
+/// How long all the LEDs are off after a row was lit, for a 50% duty cycle.
+const ROW_OFF_TIME: Duration = Duration::from_micros(5);
+
+/// How long flushing waits for when pigpio already shows the frame, since
+/// there is nothing to do then.
+const PIGPIO_IDLE_TIME: Duration = Duration::from_millis(1);
+
+/// How the GPIOs are actually written.
+enum Output {
+    Pins,
+    Registers(GpioMem),
+    Pigpio {
+        pigpio: Pigpio,
+        // Frame the waveform being played shows
+        shown: Option<FrameBuffer>,
+    },
+}
+
+pub struct LedMatrix {
+    // Driven high to light the LEDs of a row
+    rows: [OutputPin; 8],
+    // Driven low to light the LEDs of a column
+    cols: [OutputPin; 8],
+    // Columns currently driven low, as a mask where bit `x` is column `x`
+    lit_cols: u8,
+    output: Output,
+    // Whether every LED should be equally bright no matter how many others
+    // are lit
+    normalize_brightness: bool,
+    // Dead time after a row is turned off, with every column off too
+    blanking: Duration,
+    // Rows of the last flushed frame, and the columns lit during each PWM
+    // pass for them, which only need to be worked out again for the rows
+    // which change
+    rows_shown: [[u8; WIDTH]; HEIGHT],
+    lit_masks: [[u8; HEIGHT]; PWM_STEPS as usize],
+}
+
+impl LedMatrix {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        gpio: &Gpio,
+        config: &RefreshConfig,
+        row_1_pin_number: u8,
+        row_2_pin_number: u8,
+        row_3_pin_number: u8,
+        row_4_pin_number: u8,
+        row_5_pin_number: u8,
+        row_6_pin_number: u8,
+        row_7_pin_number: u8,
+        row_8_pin_number: u8,
+        col_1_pin_number: u8,
+        col_2_pin_number: u8,
+        col_3_pin_number: u8,
+        col_4_pin_number: u8,
+        col_5_pin_number: u8,
+        col_6_pin_number: u8,
+        col_7_pin_number: u8,
+        col_8_pin_number: u8,
+    ) -> Result<Self> {
+        let rows = [
+            gpio.get(row_1_pin_number)?.into_output_low(),
+            gpio.get(row_2_pin_number)?.into_output_low(),
+            gpio.get(row_3_pin_number)?.into_output_low(),
+            gpio.get(row_4_pin_number)?.into_output_low(),
+            gpio.get(row_5_pin_number)?.into_output_low(),
+            gpio.get(row_6_pin_number)?.into_output_low(),
+            gpio.get(row_7_pin_number)?.into_output_low(),
+            gpio.get(row_8_pin_number)?.into_output_low(),
+        ];
+
+        let cols = [
+            gpio.get(col_1_pin_number)?.into_output_high(),
+            gpio.get(col_2_pin_number)?.into_output_high(),
+            gpio.get(col_3_pin_number)?.into_output_high(),
+            gpio.get(col_4_pin_number)?.into_output_high(),
+            gpio.get(col_5_pin_number)?.into_output_high(),
+            gpio.get(col_6_pin_number)?.into_output_high(),
+            gpio.get(col_7_pin_number)?.into_output_high(),
+            gpio.get(col_8_pin_number)?.into_output_high(),
+        ];
+
+        // The pins are still taken through rppal with the other backends,
+        // which makes them outputs and resets them when the matrix is dropped
+        let output = match config.backend {
+            Backend::Rppal => Output::Pins,
+            Backend::Gpiomem => Output::Registers(GpioMem::open()?),
+            Backend::Pigpio => Output::Pigpio {
+                pigpio: Pigpio::connect()?,
+                shown: None,
+            },
+        };
+
+        Ok(Self {
+            rows,
+            cols,
+            lit_cols: 0,
+            output,
+            normalize_brightness: config.normalize_brightness,
+            blanking: Duration::from_micros(config.blanking_us),
+            rows_shown: [[0; WIDTH]; HEIGHT],
+            lit_masks: [[0; HEIGHT]; PWM_STEPS as usize],
+        })
+    }
+
+    /// Drives the GPIOs whose bits are set in `high` high, and the ones set in
+    /// `low` low.
+    fn write(&mut self, high: u32, low: u32) {
+        match &mut self.output {
+            Output::Pins => {
+                for pin in self.rows.iter_mut().chain(&mut self.cols) {
+                    let bit = 1 << pin.pin();
+
+                    if high & bit != 0 {
+                        pin.set_high();
+                    } else if low & bit != 0 {
+                        pin.set_low();
+                    }
+                }
+            }
+            Output::Registers(registers) => {
+                registers.set(high);
+                registers.clear(low);
+            }
+            Output::Pigpio { pigpio, .. } => {
+                if let Err(err) = pigpio.set(high).and_then(|()| pigpio.clear(low)) {
+                    log::warn!("pigpio: {:#}", err);
+                }
+            }
+        }
+    }
+
+    fn row_bit(&self, y: usize) -> u32 {
+        1 << self.rows[y].pin()
+    }
+
+    fn col_bit(&self, x: usize) -> u32 {
+        1 << self.cols[x].pin()
+    }
+
+    /// Turns every LED off, in case something stopped halfway through lighting
+    /// a row.
+    pub fn blank(&mut self) {
+        if let Output::Pigpio { pigpio, shown } = &mut self.output {
+            *shown = None;
+
+            if let Err(err) = pigpio.halt() {
+                log::warn!("pigpio: {:#}", err);
+            }
+        }
+
+        let rows = (0..HEIGHT).fold(0, |mask, y| mask | self.row_bit(y));
+        let cols = (0..self.cols.len()).fold(0, |mask, x| mask | self.col_bit(x));
+
+        self.write(cols, rows);
+        self.lit_cols = 0;
+    }
+
+    /// Returns how long a row whose lit columns are set in `mask` is lit for,
+    /// and then how long everything stays off, or nothing if the row is
+    /// skipped.
+    fn row_times(&self, mask: u8) -> Option<(Duration, Duration)> {
+        if !self.normalize_brightness {
+            return (mask != 0).then_some((ROW_ON_TIME, ROW_OFF_TIME));
+        }
+
+        // The LEDs of a row share the current of its pin, so the more are lit
+        // the longer it stays on. Empty rows still take their time, so that
+        // the other rows don't get a larger share of the scan
+        let on_time = ROW_ON_TIME * mask.count_ones() / WIDTH as u32;
+
+        Some((on_time, ROW_ON_TIME + ROW_OFF_TIME - on_time))
+    }
+
+    /// Works out the columns lit during each PWM pass again for the rows of
+    /// `frame` which changed since the last flush.
+    fn update_masks(&mut self, frame: &FrameBuffer) {
+        for y in 0..HEIGHT {
+            if frame.row(y) == &self.rows_shown[y] {
+                continue;
+            }
+
+            for step in 0..PWM_STEPS {
+                self.lit_masks[step as usize][y] = frame.lit_columns(step, y);
+            }
+
+            self.rows_shown[y] = *frame.row(y);
+        }
+    }
+
+    /// Drives the columns set in `mask` low and the others high, only
+    /// writing the pins which change.
+    fn set_columns(&mut self, mask: u8) {
+        let changed = mask ^ self.lit_cols;
+        let (mut high, mut low) = (0, 0);
+
+        for x in (0..self.cols.len()).filter(|x| changed & 1 << x != 0) {
+            if mask & 1 << x != 0 {
+                low |= self.col_bit(x);
+            } else {
+                high |= self.col_bit(x);
+            }
+        }
+
+        self.write(high, low);
+        self.lit_cols = mask;
+    }
+
+    /// Shows the contents of `frame` on the matrix.
+    ///
+    /// The LEDs are only lit while this is running, so it needs to be called
+    /// in a loop to keep the image on the display.
+    pub fn flush(&mut self, frame: &FrameBuffer) {
+        self.update_masks(frame);
+
+        if matches!(self.output, Output::Pigpio { .. }) {
+            self.flush_pigpio(frame);
+            return;
+        }
+
+        // The matrix is scanned a row at a time: the columns of a row are set
+        // up while every row is off, and then the row is lit on its own for
+        // a moment. This is a simple software PWM with a period of 10us and a
+        // duty cycle of 50%, so that the Pi doesn't have to drive the LEDs
+        // for too long
+        for step in 0..PWM_STEPS as usize {
+            for y in 0..HEIGHT {
+                let mask = self.lit_masks[step][y];
+
+                let Some((on_time, off_time)) = self.row_times(mask) else {
+                    continue;
+                };
+
+                if !on_time.is_zero() {
+                    self.set_columns(mask);
+
+                    self.write(self.row_bit(y), 0);
+                    timing::sleep(on_time);
+                    self.write(0, self.row_bit(y));
+
+                    // Switching the columns right away can make the next row
+                    // faintly show this one while the lines settle
+                    if !self.blanking.is_zero() {
+                        self.set_columns(0);
+                    }
+                }
+
+                timing::sleep(off_time + self.blanking);
+            }
+        }
+    }
+
+    /// Hands the waveform which scans `frame` to pigpio, unless it is already
+    /// being played.
+    ///
+    /// pigpio keeps refreshing the matrix on its own, so unlike with the other
+    /// backends the LEDs stay lit in between two flushes.
+    fn flush_pigpio(&mut self, frame: &FrameBuffer) {
+        if let Output::Pigpio {
+            shown: Some(shown), ..
+        } = &self.output
+        {
+            if shown == frame {
+                thread::sleep(PIGPIO_IDLE_TIME);
+                return;
+            }
+        }
+
+        let pulses = self.waveform();
+
+        let Output::Pigpio { pigpio, shown } = &mut self.output else {
+            return;
+        };
+
+        match pigpio.repeat(&pulses) {
+            Ok(()) => *shown = Some(frame.clone()),
+            Err(err) => log::warn!("pigpio: {:#}", err),
+        }
+    }
+
+    /// Returns the pulses which scan the last flushed frame once, the same way
+    /// [`LedMatrix::flush`] does.
+    fn waveform(&self) -> Vec<Pulse> {
+        let rows = (0..HEIGHT).fold(0, |mask, y| mask | self.row_bit(y));
+        let cols = (0..self.cols.len()).fold(0, |mask, x| mask | self.col_bit(x));
+
+        let mut pulses = Vec::new();
+
+        for masks in &self.lit_masks {
+            for (y, &mask) in masks.iter().enumerate() {
+                let Some((on_time, off_time)) = self.row_times(mask) else {
+                    continue;
+                };
+
+                if !on_time.is_zero() {
+                    let lit = (0..self.cols.len())
+                        .filter(|x| mask & 1 << x != 0)
+                        .fold(0, |lit, x| lit | self.col_bit(x));
+
+                    pulses.push(Pulse {
+                        high: self.row_bit(y) | (cols & !lit),
+                        low: lit,
+                        delay: on_time,
+                    });
+                }
+
+                pulses.push(Pulse {
+                    high: if self.blanking.is_zero() { 0 } else { cols },
+                    low: self.row_bit(y),
+                    delay: off_time + self.blanking,
+                });
+            }
+        }
+
+        // A waveform can't be empty, so a blank frame is a single pulse which
+        // keeps everything off
+        if pulses.is_empty() {
+            pulses.push(Pulse {
+                high: cols,
+                low: rows,
+                delay: PIGPIO_IDLE_TIME,
+            });
+        }
+
+        pulses
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    accelerometer::Accelerometer,
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+
+/// Brightness of the walls, dimmer than the ball so the two can be told
+/// apart.
+const WALL_LEVEL: u8 = u8::MAX / 4;
+
+/// Number of frames the win animation lasts for.
+const WIN_FRAMES: u32 = 12;
+
+struct Layout {
+    // One byte per row, the most significant bit being the leftmost wall
+    walls: [u8; HEIGHT],
+    start: Point,
+    exit: Point,
+}
+
+#[rustfmt::skip]
+const LAYOUTS: [Layout; 2] = [
+    Layout {
+        walls: [
+            0b11111111,
+            0b10001001,
+            0b11101011,
+            0b10000001,
+            0b10111101,
+            0b10100101,
+            0b10001101,
+            0b11111111,
+        ],
+        start: Point::new(1, 1),
+        exit: Point::new(6, 6),
+    },
+    Layout {
+        walls: [
+            0b11111111,
+            0b10000001,
+            0b10111101,
+            0b10100001,
+            0b10101111,
+            0b10100001,
+            0b10111111,
+            0b11111111,
+        ],
+        start: Point::new(1, 1),
+        exit: Point::new(6, 5),
+    },
+];
+
+/// Tilt maze game.
+///
+/// A ball rolls through a small maze towards whichever side of the board is
+/// tilted down, and has to be steered to the blinking exit. Every solved maze
+/// is followed by a short celebration and the next maze.
+pub struct Maze {
+    accelerometer: Accelerometer,
+    layout: usize,
+    ball: Point,
+    frame_count: u32,
+    // Frames left of the win animation, if it is playing
+    win_frames: Option<u32>,
+}
+
+impl Maze {
+    pub fn new(accelerometer: Accelerometer) -> Self {
+        Self {
+            accelerometer,
+            layout: 0,
+            ball: LAYOUTS[0].start,
+            frame_count: 0,
+            win_frames: None,
+        }
+    }
+
+    fn layout(&self) -> &'static Layout {
+        &LAYOUTS[self.layout]
+    }
+
+    fn is_wall(&self, p: Point) -> bool {
+        let inside = (0..WIDTH as i32).contains(&p.x) && (0..HEIGHT as i32).contains(&p.y);
+
+        !inside || self.layout().walls[p.y as usize] & (0x80 >> p.x) != 0
+    }
+
+    /// Rolls the ball one pixel in the `down` direction, one axis at a time so
+    /// it can slide along walls.
+    fn roll(&mut self, down: Point) {
+        for step in [Point::new(down.x, 0), Point::new(0, down.y)] {
+            if !self.is_wall(self.ball + step) {
+                self.ball += step;
+            }
+        }
+    }
+}
+
+impl Animation for Maze {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        match &mut self.win_frames {
+            Some(0) => {
+                self.layout = (self.layout + 1) % LAYOUTS.len();
+                self.ball = self.layout().start;
+                self.win_frames = None;
+            }
+            Some(frames) => *frames -= 1,
+            None => {
+                // Keep the ball still if the accelerometer can't be read
+                if let Ok(Some(down)) = self.accelerometer.gravity() {
+                    self.roll(down);
+                }
+
+                if self.ball == self.layout().exit {
+                    self.win_frames = Some(WIN_FRAMES);
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some(frames) = self.win_frames {
+            // Squares growing out of the middle of the matrix
+            let ring = (WIN_FRAMES - frames) % 4;
+            let offset = 3 - ring as i32;
+            let size = 2 + 2 * ring;
+
+            let Ok(()) = Rectangle::new(Point::new(offset, offset), Size::new(size, size))
+                .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                .draw(frame);
+
+            return;
+        }
+
+        for (y, row) in self.layout().walls.iter().enumerate() {
+            for x in 0..WIDTH {
+                if row & (0x80 >> x) != 0 {
+                    frame.set_level(Point::new(x as i32, y as i32), WALL_LEVEL);
+                }
+            }
+        }
+
+        if self.frame_count.is_multiple_of(2) {
+            let Ok(()) = Pixel(self.layout().exit, BinaryColor::On).draw(frame);
+        }
+
+        let Ok(()) = Pixel(self.ball, BinaryColor::On).draw(frame);
+    }
+}
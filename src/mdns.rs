@@ -0,0 +1,416 @@
+use crate::{
+    config::MdnsConfig,
+    ip,
+    log::{self, Level, Span},
+};
+use anyhow::{anyhow, Result};
+use std::{
+    ffi::CStr,
+    io, mem,
+    net::{Ipv4Addr, SocketAddrV4, UdpSocket},
+    os::fd::{AsRawFd, FromRawFd},
+    thread,
+    time::Duration,
+};
+
+/// Multicast group and port of mDNS.
+const GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const PORT: u16 = 5353;
+
+/// Type of the service the display is advertised as.
+const SERVICE: &str = "_ledmatrix._tcp.local";
+
+/// Name browsers ask for to find out which types of services there are.
+const SERVICE_TYPES: &str = "_services._dns-sd._udp.local";
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+
+const CLASS_IN: u16 = 1;
+
+/// Set on the class of records which only this display answers for, so that
+/// older records of the same name are replaced rather than added to.
+const CACHE_FLUSH: u16 = 0x8000;
+
+/// Seconds the records are cached for, as recommended by RFC 6762.
+const HOST_TTL: u32 = 120;
+const SERVICE_TTL: u32 = 4500;
+
+/// How many times the display announces itself when it starts, a second
+/// apart.
+const ANNOUNCEMENTS: u32 = 2;
+
+/// The service advertised on the network.
+struct Service {
+    /// Name of the display among the others.
+    instance: String,
+    /// Hostname of the Pi, without `.local`.
+    host: String,
+    /// Port the HTTP server listens on.
+    port: u16,
+}
+
+/// Starts advertising the HTTP server listening on `port` over mDNS on a new
+/// thread, as a `_ledmatrix._tcp` service, so that it can be found on the
+/// local network without knowing its address.
+///
+/// This answers the questions for the service itself, and runs alongside
+/// Avahi if it is there.
+pub fn spawn(config: MdnsConfig, port: u16) -> Result<()> {
+    let host = hostname()?;
+
+    let service = Service {
+        instance: config.name.unwrap_or_else(|| host.clone()),
+        host,
+        port,
+    };
+
+    let socket = bind()?;
+
+    log::info!(
+        "Advertising {}.{} over mDNS",
+        service.instance,
+        SERVICE.trim_end_matches(".local")
+    );
+
+    thread::spawn(move || {
+        for _ in 0..ANNOUNCEMENTS {
+            let announcement = response(&service, 0, false);
+
+            if let Err(err) = socket.send_to(&announcement, SocketAddrV4::new(GROUP, PORT)) {
+                log::warn!("mDNS: {:#}", err);
+            }
+
+            thread::sleep(Duration::from_secs(1));
+        }
+
+        let mut packet = [0; 9000];
+
+        loop {
+            if let Err(err) = answer(&socket, &service, &mut packet) {
+                log::warn!("mDNS: {:#}", err);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Returns the hostname of the Pi.
+fn hostname() -> Result<String> {
+    let mut name = [0u8; 256];
+
+    // SAFETY: the buffer is passed with its actual size, and one byte short
+    // so that it stays terminated even if the name is truncated
+    if unsafe { libc::gethostname(name.as_mut_ptr().cast(), name.len() - 1) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let name = CStr::from_bytes_until_nul(&name)?.to_str()?;
+
+    // Only the first label counts if the hostname is fully qualified
+    Ok(name.split('.').next().unwrap_or(name).to_string())
+}
+
+/// Creates a socket receiving the mDNS multicast group, sharing its port with
+/// any other responder.
+fn bind() -> Result<UdpSocket> {
+    // SAFETY: plain system call with no pointers
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+
+    if fd < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    // SAFETY: `fd` is a new socket which nothing else owns
+    let socket = unsafe { UdpSocket::from_raw_fd(fd) };
+
+    for option in [libc::SO_REUSEADDR, libc::SO_REUSEPORT] {
+        let enable: libc::c_int = 1;
+
+        // SAFETY: the option is passed as a pointer to an int, with its size
+        let result = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                option,
+                (&enable as *const libc::c_int).cast(),
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+
+        if result != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+    }
+
+    // SAFETY: `sockaddr_in` is plain data for which all zeros is the
+    // unspecified address
+    let mut address: libc::sockaddr_in = unsafe { mem::zeroed() };
+    address.sin_family = libc::AF_INET as libc::sa_family_t;
+    address.sin_port = PORT.to_be();
+
+    // SAFETY: `address` is a valid `sockaddr_in`, passed with its actual size
+    let result = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            (&address as *const libc::sockaddr_in).cast(),
+            mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+
+    if result != 0 {
+        return Err(anyhow!(
+            "failed to listen on port {}: {}",
+            PORT,
+            io::Error::last_os_error()
+        ));
+    }
+
+    socket.join_multicast_v4(&GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_ttl_v4(255)?;
+
+    Ok(socket)
+}
+
+/// Waits for a query, and answers it if it asks about the service.
+fn answer(socket: &UdpSocket, service: &Service, packet: &mut [u8]) -> Result<()> {
+    let (length, source) = socket.recv_from(packet)?;
+    let packet = &packet[..length];
+
+    let Some(query) = Query::parse(packet) else {
+        return Ok(());
+    };
+
+    let instance = format!("{}.{}", service.instance, SERVICE);
+    let host = format!("{}.local", service.host);
+
+    let mut asks_for_service = false;
+    let mut asks_for_types = false;
+
+    for (name, kind) in &query.questions {
+        let is = |expected: &str, kinds: &[u16]| {
+            name.eq_ignore_ascii_case(expected) && (*kind == TYPE_ANY || kinds.contains(kind))
+        };
+
+        asks_for_service |= is(SERVICE, &[TYPE_PTR])
+            || is(&instance, &[TYPE_SRV, TYPE_TXT])
+            || is(&host, &[TYPE_A]);
+        asks_for_types |= is(SERVICE_TYPES, &[TYPE_PTR]);
+    }
+
+    if !asks_for_service && !asks_for_types {
+        return Ok(());
+    }
+
+    let _span = Span::new(Level::Debug, "mDNS answer");
+
+    log::debug!("mDNS: answering {}", source);
+
+    // Queries which don't come from the mDNS port are from simple resolvers,
+    // which only listen for a direct answer
+    if source.port() == PORT {
+        let response = response(service, 0, asks_for_types);
+
+        socket.send_to(&response, SocketAddrV4::new(GROUP, PORT))?;
+    } else {
+        let response = response(service, query.id, asks_for_types);
+
+        socket.send_to(&response, source)?;
+    }
+
+    Ok(())
+}
+
+/// The questions of an mDNS query, by name and type.
+struct Query {
+    id: u16,
+    questions: Vec<(String, u16)>,
+}
+
+impl Query {
+    /// Parses `packet`, or returns nothing if it isn't a valid query.
+    fn parse(packet: &[u8]) -> Option<Self> {
+        let id = u16::from_be_bytes([*packet.first()?, *packet.get(1)?]);
+        let flags = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]);
+        let count = u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]);
+
+        // Responses of other responders come through the group too
+        if flags & 0x8000 != 0 {
+            return None;
+        }
+
+        let mut offset = 12;
+        let mut questions = Vec::new();
+
+        for _ in 0..count {
+            let (name, end) = read_name(packet, offset)?;
+            let kind = u16::from_be_bytes([*packet.get(end)?, *packet.get(end + 1)?]);
+
+            questions.push((name, kind));
+            offset = end + 4;
+        }
+
+        Some(Self { id, questions })
+    }
+}
+
+/// Reads the name at `offset` in `packet`, returning it with its labels
+/// separated by dots, and the offset right after it.
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    // Where the name ends, once it was followed to somewhere else
+    let mut end = None;
+
+    // Names can point to earlier ones, but a few hops are always enough
+    for _ in 0..32 {
+        let length = *packet.get(offset)? as usize;
+
+        match length {
+            0 => {
+                return Some((labels.join("."), end.unwrap_or(offset + 1)));
+            }
+            // The two top bits mark a pointer to the rest of the name
+            0xC0.. => {
+                let pointer = (length & 0x3F) << 8 | *packet.get(offset + 1)? as usize;
+
+                end.get_or_insert(offset + 2);
+                offset = pointer;
+            }
+            _ => {
+                let label = packet.get(offset + 1..offset + 1 + length)?;
+
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset += 1 + length;
+            }
+        }
+    }
+
+    None
+}
+
+/// Builds the response describing the service, with `id` as its id, and
+/// with its type if it is `listed` among the types of services.
+fn response(service: &Service, id: u16, listed: bool) -> Vec<u8> {
+    let addresses = ip::ipv4_addresses().unwrap_or_default();
+
+    let mut records = Vec::new();
+
+    let instance: Vec<&str> = [service.instance.as_str()]
+        .into_iter()
+        .chain(SERVICE.split('.'))
+        .collect();
+    let host = [service.host.as_str(), "local"];
+
+    if listed {
+        let mut data = Vec::new();
+        write_name(&mut data, SERVICE.split('.'));
+
+        records.push(record(
+            SERVICE_TYPES.split('.'),
+            TYPE_PTR,
+            false,
+            SERVICE_TTL,
+            &data,
+        ));
+    }
+
+    let mut data = Vec::new();
+    write_name(&mut data, instance.iter().copied());
+    records.push(record(
+        SERVICE.split('.'),
+        TYPE_PTR,
+        false,
+        SERVICE_TTL,
+        &data,
+    ));
+
+    // Priority and weight, which only matter with several servers
+    let mut data = vec![0, 0, 0, 0];
+    data.extend_from_slice(&service.port.to_be_bytes());
+    write_name(&mut data, host.iter().copied());
+    records.push(record(
+        instance.iter().copied(),
+        TYPE_SRV,
+        true,
+        HOST_TTL,
+        &data,
+    ));
+
+    // Everything is at the root of the HTTP server
+    let text = "path=/";
+    let mut data = vec![text.len() as u8];
+    data.extend_from_slice(text.as_bytes());
+    records.push(record(
+        instance.iter().copied(),
+        TYPE_TXT,
+        true,
+        SERVICE_TTL,
+        &data,
+    ));
+
+    for address in &addresses {
+        records.push(record(
+            host.iter().copied(),
+            TYPE_A,
+            true,
+            HOST_TTL,
+            &address.octets(),
+        ));
+    }
+
+    // Header of an authoritative response with only answers
+    let mut packet = Vec::new();
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&0x8400u16.to_be_bytes());
+    packet.extend_from_slice(&0u16.to_be_bytes());
+    packet.extend_from_slice(&(records.len() as u16).to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0, 0]);
+
+    for record in records {
+        packet.extend_from_slice(&record);
+    }
+
+    packet
+}
+
+/// Builds a resource record, whose data is `data`.
+fn record<'a>(
+    name: impl IntoIterator<Item = &'a str>,
+    kind: u16,
+    unique: bool,
+    ttl: u32,
+    data: &[u8],
+) -> Vec<u8> {
+    let class = if unique {
+        CLASS_IN | CACHE_FLUSH
+    } else {
+        CLASS_IN
+    };
+
+    let mut record = Vec::new();
+    write_name(&mut record, name);
+    record.extend_from_slice(&kind.to_be_bytes());
+    record.extend_from_slice(&class.to_be_bytes());
+    record.extend_from_slice(&ttl.to_be_bytes());
+    record.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    record.extend_from_slice(data);
+
+    record
+}
+
+/// Writes a name made of `labels`, which are cut to the longest length a
+/// label can have.
+fn write_name<'a>(packet: &mut Vec<u8>, labels: impl IntoIterator<Item = &'a str>) {
+    for label in labels {
+        let label = &label.as_bytes()[..label.len().min(63)];
+
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label);
+    }
+
+    packet.push(0);
+}
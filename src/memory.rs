@@ -0,0 +1,83 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    interval::Interval,
+    widget::{show_number, Gauge},
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use std::{fs, time::Duration};
+
+/// How often the memory usage is read.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of frames the gauge and the percentage are each shown for before
+/// switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// System monitor showing how much of the RAM is in use.
+///
+/// It alternates between a gauge, which fills the matrix from the bottom up
+/// one pixel per 1/64th of the memory in use, and the percentage as digits.
+pub struct Memory {
+    // Fraction of the memory in use, once it was read
+    usage: Option<f32>,
+    interval: Interval,
+    frame_count: u32,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self {
+            usage: None,
+            interval: Interval::new(SAMPLE_INTERVAL),
+            frame_count: 0,
+        }
+    }
+}
+
+/// Reads the fraction of the RAM which is in use.
+fn read_usage() -> Result<f32> {
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+
+    // Lines look like `MemTotal:        3884196 kB`
+    let field = |name: &str| -> Result<f32> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next()?.parse().ok())
+            .ok_or_else(|| anyhow!("missing {} in /proc/meminfo", name))
+    };
+
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+
+    Ok(1.0 - available / total.max(1.0))
+}
+
+impl Animation for Memory {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if self.interval.is_due() {
+            // Keep showing the previous reading if it can't be read
+            if let Ok(usage) = read_usage() {
+                self.usage = Some(usage);
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(usage) = self.usage else {
+            return;
+        };
+
+        if (self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            let Ok(()) = Gauge::new(usage).draw(frame);
+        } else {
+            let Ok(()) = show_number((usage * 100.0).round() as i64)
+                .at_frame(self.frame_count % VIEW_FRAMES)
+                .draw(frame);
+        }
+    }
+}
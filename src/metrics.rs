@@ -0,0 +1,302 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Number of recent durations the percentiles are computed from.
+const SAMPLES: usize = 1024;
+
+/// Percentiles reported for durations, from 0 to 1.
+const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Counters and gauges about how the display is doing, shared between the
+/// drawing thread which updates them and the server which exposes them.
+#[derive(Default)]
+pub struct Metrics {
+    /// Number of times the matrix was refreshed.
+    pub refreshes: AtomicU64,
+    /// Refreshes of the matrix during the last second.
+    pub refresh_rate: AtomicU64,
+    /// Animation frames drawn during the last second.
+    pub fps: AtomicU64,
+    /// Animation frames per second the display aims for.
+    pub target_fps: AtomicU64,
+    /// Number of frame ticks sent to the drawing thread.
+    pub ticks_sent: AtomicU64,
+    /// Number of frame ticks the drawing thread handled.
+    pub frames: AtomicU64,
+    /// Number of frame ticks which were dropped, because the drawing thread
+    /// was still busy with an earlier frame when they came.
+    pub dropped_ticks: AtomicU64,
+    /// Time it took to draw the last frame, in nanoseconds.
+    pub render_nanos: AtomicU64,
+    /// Time it took to draw every frame so far, in nanoseconds.
+    pub render_nanos_total: AtomicU64,
+    /// Recent times it took to draw a frame.
+    pub render_times: Samples,
+    /// Time it took to refresh the matrix the last time, in nanoseconds.
+    pub flush_nanos: AtomicU64,
+    /// Recent times between the starts of two refreshes of the matrix, which
+    /// is how long the rows are multiplexed for.
+    pub cycle_times: Samples,
+    /// Current brightness, from `0` to `255`.
+    pub brightness: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that drawing a frame took `duration`.
+    pub fn record_render(&self, duration: Duration) {
+        let nanos = duration.as_nanos() as u64;
+
+        self.render_nanos.store(nanos, Ordering::Relaxed);
+        self.render_nanos_total.fetch_add(nanos, Ordering::Relaxed);
+        self.render_times.record(duration);
+    }
+
+    /// Records that refreshing the matrix took `duration`.
+    pub fn record_flush(&self, duration: Duration) {
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+        self.flush_nanos
+            .store(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Formats the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let load = |metric: &AtomicU64| metric.load(Ordering::Relaxed);
+        let seconds = |nanos: u64| nanos as f64 / 1e9;
+
+        let metrics: [(&str, &str, &str, f64); 11] = [
+            (
+                "ledmatrix_refreshes_total",
+                "counter",
+                "Number of times the matrix was refreshed.",
+                load(&self.refreshes) as f64,
+            ),
+            (
+                "ledmatrix_refresh_rate_hertz",
+                "gauge",
+                "Refreshes of the matrix during the last second.",
+                load(&self.refresh_rate) as f64,
+            ),
+            (
+                "ledmatrix_flush_seconds",
+                "gauge",
+                "Time it took to refresh the matrix the last time.",
+                seconds(load(&self.flush_nanos)),
+            ),
+            (
+                "ledmatrix_frames_per_second",
+                "gauge",
+                "Animation frames drawn during the last second.",
+                load(&self.fps) as f64,
+            ),
+            (
+                "ledmatrix_target_frames_per_second",
+                "gauge",
+                "Animation frames per second the display aims for.",
+                load(&self.target_fps) as f64,
+            ),
+            (
+                "ledmatrix_ticks_sent_total",
+                "counter",
+                "Number of frame ticks sent to the drawing thread.",
+                load(&self.ticks_sent) as f64,
+            ),
+            (
+                "ledmatrix_frames_total",
+                "counter",
+                "Number of animation frames drawn.",
+                load(&self.frames) as f64,
+            ),
+            (
+                "ledmatrix_dropped_ticks_total",
+                "counter",
+                "Number of frame ticks dropped because a frame took too long.",
+                load(&self.dropped_ticks) as f64,
+            ),
+            (
+                "ledmatrix_frame_render_seconds",
+                "gauge",
+                "Time it took to draw the last animation frame.",
+                seconds(load(&self.render_nanos)),
+            ),
+            (
+                "ledmatrix_frame_render_seconds_total",
+                "counter",
+                "Time it took to draw every animation frame.",
+                seconds(load(&self.render_nanos_total)),
+            ),
+            (
+                "ledmatrix_brightness",
+                "gauge",
+                "Brightness of the display, from 0 to 255.",
+                load(&self.brightness) as f64,
+            ),
+        ];
+
+        let summaries = [
+            (
+                "ledmatrix_frame_render_duration_seconds",
+                "Recent times it took to draw an animation frame.",
+                &self.render_times,
+            ),
+            (
+                "ledmatrix_refresh_cycle_seconds",
+                "Recent times between the starts of two refreshes of the matrix.",
+                &self.cycle_times,
+            ),
+        ];
+
+        let mut output = String::new();
+
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} {kind}");
+            let _ = writeln!(output, "{name} {value}");
+        }
+
+        for (name, help, samples) in summaries {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} summary");
+
+            if let Some(values) = samples.quantiles() {
+                for (quantile, value) in QUANTILES.iter().zip(values) {
+                    let _ = writeln!(output, "{name}{{quantile=\"{quantile}\"}} {value}");
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Formats the rates the display achieves as a JSON object.
+    pub fn stats_json(&self) -> String {
+        let load = |metric: &AtomicU64| metric.load(Ordering::Relaxed);
+        let seconds = |nanos: u64| nanos as f64 / 1e9;
+
+        format!(
+            concat!(
+                "{{\"refresh_rate_hz\":{},\"fps\":{},\"target_fps\":{},",
+                "\"flush_seconds\":{},\"render_seconds\":{},\"dropped_ticks\":{},",
+                "\"render_percentiles\":{},\"cycle_percentiles\":{},\"cycle_jitter_seconds\":{}}}"
+            ),
+            load(&self.refresh_rate),
+            load(&self.fps),
+            load(&self.target_fps),
+            seconds(load(&self.flush_nanos)),
+            seconds(load(&self.render_nanos)),
+            load(&self.dropped_ticks),
+            percentiles_json(&self.render_times),
+            percentiles_json(&self.cycle_times),
+            self.cycle_jitter()
+                .map_or("null".to_string(), |jitter| jitter.to_string()),
+        )
+    }
+
+    /// Returns how much longer the slowest refresh cycles take than the
+    /// typical one, as the difference between the 99th percentile and the
+    /// median, in seconds.
+    pub fn cycle_jitter(&self) -> Option<f64> {
+        let [median, _, slowest] = self.cycle_times.quantiles()?;
+
+        Some(slowest - median)
+    }
+}
+
+/// Formats the percentiles of `samples` as a JSON object, like
+/// `{"p50":0.001,"p90":0.002,"p99":0.004}`.
+fn percentiles_json(samples: &Samples) -> String {
+    let Some(values) = samples.quantiles() else {
+        return "null".to_string();
+    };
+
+    let fields: Vec<String> = QUANTILES
+        .iter()
+        .zip(values)
+        .map(|(quantile, value)| format!("\"p{}\":{}", (quantile * 100.0).round(), value))
+        .collect();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// The most recent durations of something, to compute percentiles from.
+#[derive(Default)]
+pub struct Samples {
+    nanos: Mutex<VecDeque<u64>>,
+}
+
+impl Samples {
+    pub fn record(&self, duration: Duration) {
+        let mut nanos = self.nanos.lock().unwrap();
+
+        if nanos.len() == SAMPLES {
+            nanos.pop_front();
+        }
+
+        nanos.push_back(duration.as_nanos() as u64);
+    }
+
+    /// Returns the percentiles of [`QUANTILES`] of the recent durations, in
+    /// seconds, or nothing if none were recorded yet.
+    pub fn quantiles(&self) -> Option<[f64; QUANTILES.len()]> {
+        let mut sorted: Vec<u64> = self.nanos.lock().unwrap().iter().copied().collect();
+
+        if sorted.is_empty() {
+            return None;
+        }
+
+        sorted.sort_unstable();
+
+        Some(QUANTILES.map(|quantile| {
+            let index = ((sorted.len() - 1) as f64 * quantile).round() as usize;
+
+            sorted[index] as f64 / 1e9
+        }))
+    }
+}
+
+/// Counts how many times something happens per second.
+pub struct RateMeter {
+    count: u64,
+    since: Instant,
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            since: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self) {
+        self.count += 1;
+    }
+
+    /// Returns how many times something happened during the last second, once
+    /// a second has gone by since the last time this did, and starts counting
+    /// again.
+    pub fn rate(&mut self) -> Option<u64> {
+        let elapsed = self.since.elapsed();
+
+        if elapsed < Duration::from_secs(1) {
+            return None;
+        }
+
+        let rate = (self.count as f64 / elapsed.as_secs_f64()).round() as u64;
+
+        self.count = 0;
+        self.since = Instant::now();
+
+        Some(rate)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    animation::Animation,
+    scene::{self, SceneContext},
+};
+use anyhow::Result;
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// What the display shows, which is one of the registered scenes, selected
+/// by name on the command line or switched to while it runs.
+///
+/// It holds the index of the scene in the registry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Mode(usize);
+
+impl Mode {
+    /// Returns the mode registered as `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        scene::registered()
+            .iter()
+            .position(|scene| scene.name.eq_ignore_ascii_case(name))
+            .map(Mode)
+    }
+
+    /// Returns every mode, in the order they were registered.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..scene::registered().len()).map(Mode)
+    }
+
+    /// Returns the name the mode is selected by, on the command line or in
+    /// the configuration.
+    pub fn name(self) -> &'static str {
+        scene::registered()[self.0].name
+    }
+
+    /// Creates the animation shown by the mode.
+    pub fn create(self, context: &SceneContext) -> Result<Box<dyn Animation>> {
+        (scene::registered()[self.0].create)(context)
+    }
+
+    /// Parses modes on the command line, listing the registered ones in the
+    /// help.
+    pub fn parser() -> impl TypedValueParser<Value = Self> {
+        let values = scene::registered()
+            .iter()
+            .map(|scene| PossibleValue::new(scene.name).help(scene.about));
+
+        // Only registered names get through the parser, so finding the mode
+        // can't fail
+        PossibleValuesParser::new(values).map(|name| Mode::from_name(&name).unwrap())
+    }
+}
+
+impl<'de> Deserialize<'de> for Mode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Mode::from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown mode {name:?}")))
+    }
+}
+
+impl Serialize for Mode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    interval::Interval,
+};
+use embedded_graphics::prelude::*;
+use std::{
+    f32::consts::TAU,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How often the phase is computed again.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A new moon, on 2000-01-06 at 18:14 UTC, as a Unix timestamp.
+const REFERENCE_NEW_MOON: f64 = 947_182_440.0;
+
+/// Average length of a lunar cycle, in seconds.
+const SYNODIC_MONTH: f64 = 29.530_588_853 * 24.0 * 60.0 * 60.0;
+
+/// Brightness of the unlit part of the disc, so that its outline stays
+/// visible.
+const SHADOW_LEVEL: u8 = 24;
+
+/// Samples taken along each axis of a pixel to work out how much of it is
+/// covered by the disc and its lit part.
+const SUBSAMPLES: usize = 4;
+
+/// Shows the current phase of the moon, as seen from the northern
+/// hemisphere.
+///
+/// The disc is shaded with intermediate brightness levels, so that the
+/// terminator falls between pixels smoothly.
+pub struct Moon {
+    // Fraction of the lunar cycle which went by since the last new moon
+    phase: f32,
+    interval: Interval,
+}
+
+impl Moon {
+    pub fn new() -> Self {
+        Self {
+            phase: 0.0,
+            interval: Interval::new(UPDATE_INTERVAL),
+        }
+    }
+}
+
+/// Returns the fraction of the lunar cycle which went by since the last new
+/// moon, from 0 to 1.
+fn current_phase() -> f32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    ((now - REFERENCE_NEW_MOON) / SYNODIC_MONTH).rem_euclid(1.0) as f32
+}
+
+/// Returns whether the point `(u, v)` of the unit disc is lit by the sun
+/// during `phase`.
+fn is_lit(u: f32, v: f32, phase: f32) -> bool {
+    // The terminator is half of an ellipse, which sweeps from the right edge
+    // of the disc to the left one while waxing, and again while waning
+    let terminator = (1.0 - v * v).sqrt() * (phase * TAU).cos();
+
+    if phase < 0.5 {
+        u > terminator
+    } else {
+        u < -terminator
+    }
+}
+
+impl Animation for Moon {
+    fn tick(&mut self) {
+        if self.interval.is_due() {
+            self.phase = current_phase();
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let radius = WIDTH.min(HEIGHT) as f32 / 2.0;
+        let samples = (SUBSAMPLES * SUBSAMPLES) as f32;
+
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let mut disc = 0;
+                let mut lit = 0;
+
+                for sy in 0..SUBSAMPLES {
+                    for sx in 0..SUBSAMPLES {
+                        // Position of the sample on the unit disc
+                        let sample_x = x as f32 + (sx as f32 + 0.5) / SUBSAMPLES as f32;
+                        let sample_y = y as f32 + (sy as f32 + 0.5) / SUBSAMPLES as f32;
+                        let u = (sample_x - WIDTH as f32 / 2.0) / radius;
+                        let v = (sample_y - HEIGHT as f32 / 2.0) / radius;
+
+                        if u * u + v * v > 1.0 {
+                            continue;
+                        }
+
+                        disc += 1;
+
+                        if is_lit(u, v, self.phase) {
+                            lit += 1;
+                        }
+                    }
+                }
+
+                let level = disc as f32 / samples * SHADOW_LEVEL as f32
+                    + lit as f32 / samples * (u8::MAX - SHADOW_LEVEL) as f32;
+
+                frame.set_level(Point::new(x as i32, y as i32), level.round() as u8);
+            }
+        }
+    }
+}
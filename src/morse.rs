@@ -0,0 +1,215 @@
+use crate::{
+    action::Action,
+    animation::Animation,
+    config::{MorseConfig, MorseLight},
+    framebuffer::{FrameBuffer, WIDTH},
+    text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use std::time::{Duration, Instant};
+
+/// Units of the standard word `PARIS`, which the words per minute are
+/// counted in.
+const UNITS_PER_WORD: u32 = 50;
+
+/// Units the light is on for a dot and a dash.
+const DOT: u32 = 1;
+const DASH: u32 = 3;
+
+/// Units the light is off for between two signals of a letter, two letters,
+/// and two words. The end of a message is also a word gap.
+const SIGNAL_GAP: u32 = 1;
+const LETTER_GAP: u32 = 3;
+const WORD_GAP: u32 = 7;
+
+/// Messages flashed in Morse code, one after the other.
+///
+/// Either the whole matrix flashes, or only the top right pixel while the
+/// message scrolls by as text. Letters which have no Morse code are skipped.
+pub struct Morse {
+    messages: Vec<String>,
+    current: usize,
+    // Whether the light is on, and for how many units, for the whole message
+    signals: Vec<(bool, u32)>,
+    text: Option<ScrollingText>,
+    light: MorseLight,
+    unit: Duration,
+    start: Instant,
+    lit: bool,
+}
+
+impl Morse {
+    /// Creates the animation, starting with the first of `messages`, which
+    /// can't be empty.
+    pub fn new(config: MorseConfig, messages: Vec<String>) -> Self {
+        let mut morse = Self {
+            messages,
+            current: 0,
+            signals: Vec::new(),
+            text: None,
+            light: config.light,
+            unit: Duration::from_secs(60) / (config.wpm.max(1) * UNITS_PER_WORD),
+            start: Instant::now(),
+            lit: false,
+        };
+
+        morse.show(0);
+        morse
+    }
+
+    /// Starts flashing the message at `index`.
+    fn show(&mut self, index: usize) {
+        let message = &self.messages[index];
+
+        self.current = index;
+        self.signals = encode(message);
+        self.text = (self.light == MorseLight::Corner).then(|| ScrollingText::new(message));
+        self.start = Instant::now();
+    }
+}
+
+/// Returns whether the light is on, and for how many units, to flash a
+/// message, ending with a word gap.
+fn encode(message: &str) -> Vec<(bool, u32)> {
+    let mut signals = Vec::new();
+    let mut gap = 0;
+
+    for word in message.split_whitespace() {
+        for code in word.chars().filter_map(code) {
+            for signal in code.chars() {
+                if gap > 0 {
+                    signals.push((false, gap));
+                }
+
+                signals.push((true, if signal == '.' { DOT } else { DASH }));
+                gap = SIGNAL_GAP;
+            }
+
+            gap = LETTER_GAP;
+        }
+
+        // Words with no letters which have a code aren't flashed at all
+        if gap > 0 {
+            gap = WORD_GAP;
+        }
+    }
+
+    signals.push((false, WORD_GAP));
+    signals
+}
+
+/// Returns the dots and dashes of a letter, digit or punctuation mark, or
+/// nothing if it has no Morse code.
+fn code(letter: char) -> Option<&'static str> {
+    let code = match letter.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '.' => ".-.-.-",
+        ',' => "--..--",
+        '?' => "..--..",
+        '\'' => ".----.",
+        '!' => "-.-.--",
+        '/' => "-..-.",
+        '(' => "-.--.",
+        ')' => "-.--.-",
+        '&' => ".-...",
+        ':' => "---...",
+        ';' => "-.-.-.",
+        '=' => "-...-",
+        '+' => ".-.-.",
+        '-' => "-....-",
+        '"' => ".-..-.",
+        '@' => ".--.-.",
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+impl Animation for Morse {
+    fn tick(&mut self) {
+        if let Some(text) = &mut self.text {
+            text.tick();
+        }
+
+        let mut units = (self.start.elapsed().as_nanos() / self.unit.as_nanos()) as u32;
+
+        for &(on, length) in &self.signals {
+            if units < length {
+                self.lit = on;
+                return;
+            }
+
+            units -= length;
+        }
+
+        // The whole message was flashed
+        self.show((self.current + 1) % self.messages.len());
+        self.lit = false;
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match &self.text {
+            Some(text) => {
+                text.draw(frame);
+
+                let color = if self.lit {
+                    BinaryColor::On
+                } else {
+                    BinaryColor::Off
+                };
+
+                let Ok(()) = Pixel(Point::new(WIDTH as i32 - 1, 0), color).draw(frame);
+            }
+            None if self.lit => {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            }
+            None => {}
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        if action == Action::NextMessage {
+            self.show((self.current + 1) % self.messages.len());
+        }
+    }
+
+    fn set_messages(&mut self, messages: &[String]) {
+        self.messages = messages.to_vec();
+        self.show(0);
+    }
+}
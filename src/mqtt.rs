@@ -0,0 +1,388 @@
+use crate::{
+    config::MqttConfig,
+    json::{Json, JsonPath},
+    log, template,
+    ticker::{Segment, Ticker},
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    io::{self, BufReader, Read, Write},
+    net::TcpStream,
+    sync::mpsc::{channel, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long the connection can go without packets before the broker drops
+/// it. A ping is sent after half of it.
+const KEEP_ALIVE: Duration = Duration::from_secs(60);
+
+/// How long to wait before connecting again after the connection failed.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+// Types of the packets, which are the high nibble of their first byte
+const CONNECT: u8 = 1;
+const CONNACK: u8 = 2;
+const PUBLISH: u8 = 3;
+const SUBSCRIBE: u8 = 8;
+const PINGREQ: u8 = 12;
+
+/// Creates a ticker showing a value of the messages of an MQTT topic, like
+/// those of home automation sensors.
+///
+/// The value is picked out of every message with a JSONPath expression, and
+/// shown in a template. Messages which aren't JSON are shown as they are if
+/// the path is `$`.
+pub fn ticker(config: MqttConfig) -> Result<Ticker> {
+    let path = JsonPath::parse(&config.path)?;
+    let (tx, rx) = channel();
+
+    thread::spawn(move || loop {
+        match subscribe(&config, &path, &tx) {
+            Ok(()) => return,
+            Err(err) => log::warn!("MQTT: {:#}", err),
+        }
+
+        // Nothing to show, but it stops connecting again once the mode is
+        // gone, even while the broker can't be reached
+        if tx.send(None).is_err() {
+            return;
+        }
+
+        thread::sleep(RETRY_DELAY);
+    });
+
+    Ok(Ticker::with_updates(rx))
+}
+
+/// Connects to the broker and sends a line for every message of the topic,
+/// until the connection or the ticker goes away.
+fn subscribe(
+    config: &MqttConfig,
+    path: &JsonPath,
+    lines: &Sender<Option<Vec<Segment>>>,
+) -> Result<()> {
+    let mut stream = TcpStream::connect(&config.broker)
+        .with_context(|| format!("failed to connect to {}", config.broker))?;
+
+    stream.set_read_timeout(Some(KEEP_ALIVE / 2))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    stream.write_all(&connect_packet(config))?;
+
+    match read_packet(&mut reader)? {
+        Some((CONNACK, body)) if body.get(1) == Some(&0) => {}
+        Some((CONNACK, body)) => {
+            return Err(anyhow!(
+                "the broker refused the connection with code {}",
+                body.get(1).copied().unwrap_or_default()
+            ))
+        }
+        _ => return Err(anyhow!("the broker didn't acknowledge the connection")),
+    }
+
+    stream.write_all(&subscribe_packet(&config.topic))?;
+
+    log::info!("Subscribed to {} on {}", config.topic, config.broker);
+
+    let mut last_sent = Instant::now();
+
+    loop {
+        let received = read_packet(&mut reader)?;
+
+        // The broker only counts what it receives to keep the connection
+        // alive
+        if last_sent.elapsed() >= KEEP_ALIVE / 2 {
+            stream.write_all(&packet(PINGREQ << 4, &[]))?;
+            last_sent = Instant::now();
+        }
+
+        let Some((PUBLISH, body)) = received else {
+            // Quiet topics only time out, so the ticker is checked for here
+            // as well
+            if lines.send(None).is_err() {
+                return Ok(());
+            }
+
+            continue;
+        };
+
+        let Some(message) = parse_publish(&body) else {
+            return Err(anyhow!("malformed message from the broker"));
+        };
+
+        let text = String::from_utf8_lossy(message);
+
+        let value = match Json::parse(&text) {
+            Ok(document) => path.select(&document).cloned(),
+            // Plain values, like `ON`, are common on MQTT
+            Err(_) if config.path.trim() == "$" => Some(Json::String(text.trim().to_string())),
+            Err(err) => {
+                log::warn!("MQTT: the message isn't JSON: {:#}", err);
+                continue;
+            }
+        };
+
+        let Some(value) = value else {
+            log::warn!("MQTT: no value at {} in the message", config.path);
+            continue;
+        };
+
+        let line = template::render(&config.format, |_| Some(&value));
+
+        if lines.send(Some(vec![Segment::Text(line)])).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+fn connect_packet(config: &MqttConfig) -> Vec<u8> {
+    // Clean session, since nothing is kept from one connection to the next
+    let mut flags = 0b0000_0010;
+
+    if config.user.is_some() {
+        flags |= 0b1000_0000;
+    }
+
+    if config.password.is_some() {
+        flags |= 0b0100_0000;
+    }
+
+    let mut body = Vec::new();
+    push_string(&mut body, "MQTT");
+    // Version 3.1.1
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    // The broker picks a client id, which is all right with a clean session
+    push_string(&mut body, "");
+
+    for field in [&config.user, &config.password].into_iter().flatten() {
+        push_string(&mut body, field);
+    }
+
+    packet(CONNECT << 4, &body)
+}
+
+fn subscribe_packet(topic: &str) -> Vec<u8> {
+    // Packet id 1, and the topic at QoS 0
+    let mut body = vec![0, 1];
+    push_string(&mut body, topic);
+    body.push(0);
+
+    packet(SUBSCRIBE << 4 | 0b0010, &body)
+}
+
+/// Returns the payload of a PUBLISH packet.
+fn parse_publish(body: &[u8]) -> Option<&[u8]> {
+    let topic_length = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+
+    // Only messages with a QoS above 0 have a packet id, and they aren't
+    // subscribed to
+    body.get(2 + topic_length..)
+}
+
+/// Adds a string prefixed with its length.
+fn push_string(body: &mut Vec<u8>, string: &str) {
+    body.extend_from_slice(&(string.len() as u16).to_be_bytes());
+    body.extend_from_slice(string.as_bytes());
+}
+
+/// Returns a packet whose first byte is `header`.
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut length = body.len();
+
+    // The remaining length takes 7 bits per byte, the lowest ones first
+    loop {
+        let byte = (length % 128) as u8;
+        length /= 128;
+
+        if length == 0 {
+            packet.push(byte);
+            break;
+        }
+
+        packet.push(byte | 0x80);
+    }
+
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Reads the next packet, returning its type and body, or nothing if none
+/// came before the read timeout.
+fn read_packet(reader: &mut impl Read) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0];
+
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            return Ok(None)
+        }
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            return Err(anyhow!("the broker closed the connection"))
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut length = 0;
+
+    for shift in (0..28).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+
+        length |= ((byte[0] & 0x7F) as usize) << shift;
+
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some((header[0] >> 4, body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUBACK: u8 = 9;
+
+    fn config(user: Option<&str>, password: Option<&str>) -> MqttConfig {
+        MqttConfig {
+            user: user.map(str::to_string),
+            password: password.map(str::to_string),
+            ..toml::from_str("broker = \"localhost:1883\"\ntopic = \"t\"").unwrap()
+        }
+    }
+
+    /// Reads back the only packet of `bytes`.
+    fn read(bytes: &[u8]) -> (u8, Vec<u8>) {
+        let mut reader = bytes;
+        let packet = read_packet(&mut reader).unwrap().unwrap();
+
+        assert!(reader.is_empty(), "{} bytes were left", reader.len());
+        packet
+    }
+
+    #[test]
+    fn encodes_the_remaining_length() {
+        for (length, encoded) in [
+            (0, &[0x00][..]),
+            (127, &[0x7f][..]),
+            (128, &[0x80, 0x01][..]),
+            (16_383, &[0xff, 0x7f][..]),
+            (16_384, &[0x80, 0x80, 0x01][..]),
+            (2_097_152, &[0x80, 0x80, 0x80, 0x01][..]),
+        ] {
+            let body = vec![0xaa; length];
+            let packet = packet(PUBLISH << 4, &body);
+
+            assert_eq!(&packet[1..1 + encoded.len()], encoded, "{}", length);
+            assert_eq!(read(&packet), (PUBLISH, body));
+        }
+    }
+
+    #[test]
+    fn builds_connect_packets() {
+        let packet = connect_packet(&config(None, None));
+
+        assert_eq!(
+            packet,
+            [
+                CONNECT << 4,
+                12,
+                0,
+                4,
+                b'M',
+                b'Q',
+                b'T',
+                b'T',
+                4,
+                0b0000_0010,
+                0,
+                60,
+                0,
+                0
+            ]
+        );
+        assert_eq!(read(&packet).0, CONNECT);
+    }
+
+    #[test]
+    fn sends_credentials_when_connecting() {
+        let (_, body) = read(&connect_packet(&config(Some("me"), Some("pw"))));
+
+        assert_eq!(body[7], 0b1100_0010);
+        assert_eq!(&body[12..], [0, 2, b'm', b'e', 0, 2, b'p', b'w']);
+    }
+
+    #[test]
+    fn builds_subscribe_packets() {
+        let packet = subscribe_packet("home/temp");
+
+        assert_eq!(packet[0], SUBSCRIBE << 4 | 0b0010);
+
+        let (_, body) = read(&packet);
+
+        assert_eq!(&body[..4], [0, 1, 0, 9]);
+        assert_eq!(&body[4..13], b"home/temp");
+        assert_eq!(body[13], 0);
+    }
+
+    #[test]
+    fn reads_publish_packets() {
+        let mut body = Vec::new();
+        push_string(&mut body, "home/temp");
+        body.extend_from_slice(br#"{"value": 21.5}"#);
+
+        let (kind, body) = read(&packet(PUBLISH << 4, &body));
+
+        assert_eq!(kind, PUBLISH);
+        assert_eq!(parse_publish(&body), Some(&br#"{"value": 21.5}"#[..]));
+    }
+
+    #[test]
+    fn rejects_truncated_publish_packets() {
+        assert_eq!(parse_publish(&[]), None);
+        assert_eq!(parse_publish(&[0]), None);
+        assert_eq!(parse_publish(&[0, 5, b'a']), None);
+    }
+
+    #[test]
+    fn reads_acknowledgements() {
+        assert_eq!(read(&[CONNACK << 4, 2, 0, 0]), (CONNACK, vec![0, 0]));
+        assert_eq!(read(&[SUBACK << 4, 3, 0, 1, 0]), (SUBACK, vec![0, 1, 0]));
+    }
+
+    #[test]
+    fn notices_closed_connections() {
+        let err = read_packet(&mut &[][..]).unwrap_err();
+        assert!(err.to_string().contains("closed"));
+
+        // Cut short in the middle of the body
+        assert!(read_packet(&mut &[PUBLISH << 4, 5, 0][..]).is_err());
+    }
+
+    #[test]
+    fn returns_nothing_when_the_read_times_out() {
+        struct TimedOut;
+
+        impl Read for TimedOut {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+
+        assert!(read_packet(&mut TimedOut).unwrap().is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    animation::Animation,
+    config::NetworkConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    interval::Interval,
+    widget::BarGraph,
+};
+use anyhow::Result;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use std::{collections::VecDeque, fs, time::Duration};
+
+/// How often the traffic counters are sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Height of each of the two bar graphs, in pixels.
+const GRAPH_HEIGHT: u32 = HEIGHT as u32 / 2;
+
+/// System monitor showing the traffic of a network interface.
+///
+/// The bytes received per second are drawn as a bar graph on the top half of
+/// the matrix, and the bytes sent per second on the bottom half, with the
+/// newest sample on the right. Each graph is scaled to the largest sample
+/// currently on screen.
+pub struct Network {
+    config: NetworkConfig,
+    // Bytes received and sent during each sample, the newest at the back
+    samples: VecDeque<(u64, u64)>,
+    // Counters from the previous sample
+    last_counters: Option<(u64, u64)>,
+    interval: Interval,
+}
+
+impl Network {
+    pub fn new(config: NetworkConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::with_capacity(WIDTH),
+            last_counters: None,
+            interval: Interval::new(SAMPLE_INTERVAL),
+        }
+    }
+
+    fn sample(&mut self) -> Result<()> {
+        let counters = read_counters(&self.config.interface)?;
+
+        if let Some((last_rx, last_tx)) = self.last_counters {
+            if self.samples.len() == WIDTH {
+                self.samples.pop_front();
+            }
+
+            self.samples.push_back((
+                counters.0.saturating_sub(last_rx),
+                counters.1.saturating_sub(last_tx),
+            ));
+        }
+
+        self.last_counters = Some(counters);
+
+        Ok(())
+    }
+}
+
+/// Reads the total bytes received and sent by `interface`.
+fn read_counters(interface: &str) -> Result<(u64, u64)> {
+    let read = |counter: &str| -> Result<u64> {
+        let path = format!("/sys/class/net/{}/statistics/{}", interface, counter);
+
+        Ok(fs::read_to_string(path)?.trim().parse()?)
+    };
+
+    Ok((read("rx_bytes")?, read("tx_bytes")?))
+}
+
+impl Animation for Network {
+    fn tick(&mut self) {
+        if self.interval.is_due() {
+            // Skip this sample if the counters can't be read
+            let _ = self.sample();
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let half = |top: u32| {
+            Rectangle::new(
+                Point::new(0, top as i32),
+                Size::new(WIDTH as u32, GRAPH_HEIGHT),
+            )
+        };
+
+        let received = self.samples.iter().map(|&(rx, _)| rx as f32);
+        let sent = self.samples.iter().map(|&(_, tx)| tx as f32);
+
+        let Ok(()) = BarGraph::new(received).in_area(half(0)).draw(frame);
+        let Ok(()) = BarGraph::new(sent).in_area(half(GRAPH_HEIGHT)).draw(frame);
+    }
+}
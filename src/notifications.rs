@@ -0,0 +1,283 @@
+use crate::{
+    command::{DisplayCommand, DisplayHandle},
+    config::NotificationsConfig,
+    log,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+/// Calls which show a desktop notification.
+const MATCH_RULE: &str = "interface='org.freedesktop.Notifications',member='Notify'";
+
+/// How long to wait before watching again after `dbus-monitor` stopped.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Starts watching the desktop notifications on a new thread, announcing
+/// their summaries on the display.
+///
+/// This runs the `dbus-monitor` command, since there is no D-Bus library to
+/// do it directly, and reads the `Notify` calls it prints.
+pub fn spawn(config: NotificationsConfig, display: DisplayHandle) {
+    thread::spawn(move || loop {
+        if let Err(err) = watch(&config, &display) {
+            log::warn!("Notifications: {:#}", err);
+        }
+
+        thread::sleep(RETRY_DELAY);
+    });
+}
+
+/// Announces the notifications until `dbus-monitor` or the display goes
+/// away.
+fn watch(config: &NotificationsConfig, display: &DisplayHandle) -> Result<()> {
+    let mut command = Command::new("dbus-monitor");
+
+    match &config.address {
+        Some(address) => command.args(["--address", address]),
+        None => command.arg("--session"),
+    };
+
+    let mut child = command
+        .arg(MATCH_RULE)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to run dbus-monitor")?;
+
+    let Some(stdout) = child.stdout.take() else {
+        return Err(anyhow!("the output of dbus-monitor isn't piped"));
+    };
+
+    let mut parser = NotifyParser::default();
+
+    for line in BufReader::new(stdout).lines() {
+        let Some(notification) = parser.line(&line?) else {
+            continue;
+        };
+
+        let wanted = (config.apps.is_empty() || config.apps.contains(&notification.app))
+            && !config.ignored_apps.contains(&notification.app);
+
+        if !wanted || notification.summary.trim().is_empty() {
+            continue;
+        }
+
+        log::debug!("Notification from {}", notification.app);
+
+        if display
+            .send(DisplayCommand::Announce(notification.summary))
+            .is_err()
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+
+            return Ok(());
+        }
+    }
+
+    let _ = child.wait();
+
+    Err(anyhow!("dbus-monitor stopped"))
+}
+
+struct Notification {
+    app: String,
+    summary: String,
+}
+
+/// How the headers printed by `dbus-monitor` for every message start.
+const HEADERS: [&str; 4] = ["method call ", "method return ", "signal ", "error "];
+
+/// Picks the notifications out of the lines printed by `dbus-monitor`.
+///
+/// A `Notify` call is printed as a header, followed by its arguments on
+/// lines of their own, indented by three spaces. The strings among them are
+/// the name of the app, its icon, the summary and the body, in that order.
+/// Strings with line breaks go on over several lines.
+#[derive(Default)]
+struct NotifyParser {
+    // Strings of the call being read, if any
+    strings: Option<Vec<String>>,
+    // Start of a string which goes on over several lines
+    pending: Option<String>,
+}
+
+impl NotifyParser {
+    /// Reads the next line, returning the notification it completes, if any.
+    fn line(&mut self, line: &str) -> Option<Notification> {
+        // A string which was cut short doesn't swallow the calls after it
+        let is_header = HEADERS.iter().any(|header| line.starts_with(header));
+
+        if is_header || (!line.starts_with(' ') && self.pending.is_none()) {
+            let is_notify = line.starts_with("method call ") && line.contains("member=Notify");
+
+            self.strings = is_notify.then(Vec::new);
+            self.pending = None;
+            return None;
+        }
+
+        let strings = self.strings.as_mut()?;
+
+        let string = if let Some(mut pending) = self.pending.take() {
+            pending.push('\n');
+            pending.push_str(line);
+            pending
+        } else if let Some(start) = line.strip_prefix("   string \"") {
+            start.to_string()
+        } else {
+            return None;
+        };
+
+        match string.strip_suffix('"') {
+            Some(string) => strings.push(string.to_string()),
+            None => {
+                self.pending = Some(string);
+                return None;
+            }
+        }
+
+        if let [app, _icon, summary] = strings.as_slice() {
+            let notification = Notification {
+                app: app.clone(),
+                summary: summary.clone(),
+            };
+
+            self.strings = None;
+
+            return Some(notification);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTIFY: &str = "method call time=1760000000.1 sender=:1.42 -> \
+        destination=org.freedesktop.Notifications serial=7 \
+        path=/org/freedesktop/Notifications; \
+        interface=org.freedesktop.Notifications; member=Notify";
+
+    /// Returns the app and summary of every notification of `lines`.
+    fn parse(lines: &[&str]) -> Vec<(String, String)> {
+        let mut parser = NotifyParser::default();
+
+        lines
+            .iter()
+            .filter_map(|line| parser.line(line))
+            .map(|notification| (notification.app, notification.summary))
+            .collect()
+    }
+
+    fn notification(app: &str, summary: &str) -> (String, String) {
+        (app.to_string(), summary.to_string())
+    }
+
+    #[test]
+    fn reads_notifications() {
+        let notifications = parse(&[
+            NOTIFY,
+            "   string \"Slack\"",
+            "   uint32 0",
+            "   string \"slack\"",
+            "   string \"New message\"",
+            "   string \"Hello\"",
+            "   array [",
+            "   ]",
+            "   int32 -1",
+        ]);
+
+        assert_eq!(notifications, [notification("Slack", "New message")]);
+    }
+
+    #[test]
+    fn joins_strings_over_several_lines() {
+        let notifications = parse(&[
+            NOTIFY,
+            "   string \"Mail\"",
+            "   uint32 0",
+            "   string \"\"",
+            "   string \"Two",
+            "lines \"quoted\" here\"",
+            "   string \"Body",
+            "goes on\"",
+            NOTIFY,
+            "   string \"Chat\"",
+            "   uint32 0",
+            "   string \"\"",
+            "   string \"Next\"",
+        ]);
+
+        assert_eq!(
+            notifications,
+            [
+                notification("Mail", "Two\nlines \"quoted\" here"),
+                notification("Chat", "Next"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_other_messages() {
+        let notifications = parse(&[
+            "signal time=1760000000.1 sender=org.freedesktop.DBus -> destination=:1.42 \
+             serial=2 path=/org/freedesktop/DBus; interface=org.freedesktop.DBus; \
+             member=NameAcquired",
+            "   string \":1.42\"",
+            "   string \"a\"",
+            "   string \"b\"",
+            "method call time=1760000000.2 sender=:1.42 -> \
+             destination=org.freedesktop.Notifications serial=8 \
+             path=/org/freedesktop/Notifications; \
+             interface=org.freedesktop.Notifications; member=GetServerInformation",
+            "   string \"a\"",
+            "   string \"b\"",
+            "   string \"c\"",
+            "method return time=1760000000.3 sender=:1.10 -> destination=:1.42 serial=9",
+            "   uint32 12",
+        ]);
+
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn recovers_from_calls_cut_short() {
+        let notifications = parse(&[
+            NOTIFY,
+            "   string \"Lost\"",
+            NOTIFY,
+            "   string \"Also lost",
+            NOTIFY,
+            "   string \"Kept\"",
+            "   uint32 0",
+            "   string \"\"",
+            "   string \"Summary\"",
+        ]);
+
+        assert_eq!(notifications, [notification("Kept", "Summary")]);
+    }
+
+    #[test]
+    fn ignores_malformed_lines() {
+        let notifications = parse(&[
+            "",
+            "   string \"Before any call\"",
+            "garbage",
+            NOTIFY,
+            "   string \"App\"",
+            "   string",
+            "   uint32 0",
+            "   string \"\"",
+            "   string \"Summary\"",
+        ]);
+
+        assert_eq!(notifications, [notification("App", "Summary")]);
+    }
+}
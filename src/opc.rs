@@ -0,0 +1,101 @@
+use crate::{
+    command::{DisplayCommand, DisplayHandle},
+    config::OpcConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    log,
+};
+use anyhow::{Context, Result};
+use std::{
+    io::{BufReader, ErrorKind, Read},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+/// Channel of messages meant for every display.
+const BROADCAST: u8 = 0;
+
+/// Command setting the colors of the pixels.
+const SET_PIXEL_COLORS: u8 = 0;
+
+/// Starts an Open Pixel Control server on a new thread, showing the pixels
+/// streamed by OPC clients, which handles every client on a thread of its
+/// own.
+///
+/// Pixels are numbered row by row. The display only has one color, so every
+/// pixel is as bright as its brightest component. The display goes back to
+/// its mode when a client disconnects.
+pub fn spawn(config: OpcConfig, display: DisplayHandle) -> Result<()> {
+    let listener = TcpListener::bind(&config.address)
+        .with_context(|| format!("failed to listen on {}", config.address))?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+
+            let display = display.clone();
+
+            thread::spawn(move || {
+                // Whether a frame from this client is on the display
+                let mut showing = false;
+
+                if let Err(err) = receive(stream, config.channel, &display, &mut showing) {
+                    log::warn!("OPC: {:#}", err);
+                }
+
+                if showing {
+                    let _ = display.send(DisplayCommand::Clear);
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Shows the pixels of the messages on `channel` the client sends, until it
+/// disconnects, and sets `showing` once one was shown.
+fn receive(
+    stream: TcpStream,
+    channel: u8,
+    display: &DisplayHandle,
+    showing: &mut bool,
+) -> Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut data = Vec::new();
+
+    loop {
+        // Channel, command and length of the data
+        let mut header = [0; 4];
+
+        match stream.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+
+        let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+
+        data.resize(length, 0);
+        stream.read_exact(&mut data)?;
+
+        if header[1] != SET_PIXEL_COLORS || (header[0] != BROADCAST && header[0] != channel) {
+            continue;
+        }
+
+        // Pixels the message doesn't have are off, and extra ones are ignored
+        let levels: Vec<u8> = (0..WIDTH * HEIGHT)
+            .map(|pixel| {
+                data.get(pixel * 3..pixel * 3 + 3)
+                    .and_then(|rgb| rgb.iter().max().copied())
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        if let Some(frame) = FrameBuffer::from_levels(&levels) {
+            display.send(DisplayCommand::ShowFrame(frame))?;
+            *showing = true;
+        }
+    }
+}
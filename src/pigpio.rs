@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+/// Address the pigpio daemon listens on by default.
+const ADDRESS: &str = "127.0.0.1:8888";
+
+// Commands of the pigpio socket interface
+const BC1: u32 = 12;
+const BS1: u32 = 14;
+const WVCLR: u32 = 27;
+const WVAG: u32 = 28;
+const WVHLT: u32 = 33;
+const WVCRE: u32 = 49;
+const WVDEL: u32 = 50;
+const WVTXM: u32 = 100;
+
+/// Transmit mode which repeats a wave, switching from the previous one once
+/// it finishes its current cycle.
+const WAVE_MODE_REPEAT_SYNC: u32 = 3;
+
+/// Number of waves kept around, since the one being replaced keeps being
+/// sent until the end of its cycle.
+const KEPT_WAVES: usize = 2;
+
+/// A step of a waveform: GPIOs to drive high and low, and how long to wait
+/// before the next step.
+pub struct Pulse {
+    pub high: u32,
+    pub low: u32,
+    pub delay: Duration,
+}
+
+/// A connection to the pigpio daemon, `pigpiod`, which can play waveforms on
+/// the GPIOs timed by DMA rather than by the CPU.
+pub struct Pigpio {
+    stream: TcpStream,
+    // Waves which were created, from the oldest to the one being sent
+    waves: VecDeque<u32>,
+}
+
+impl Pigpio {
+    pub fn connect() -> Result<Self> {
+        let stream = TcpStream::connect(ADDRESS)
+            .with_context(|| format!("failed to connect to pigpiod on {}", ADDRESS))?;
+
+        let mut pigpio = Self {
+            stream,
+            waves: VecDeque::new(),
+        };
+
+        // Start from a clean slate, in case an earlier run left waves behind
+        pigpio.command(WVCLR, 0, 0, &[])?;
+
+        Ok(pigpio)
+    }
+
+    /// Sends a command and returns its result.
+    fn command(&mut self, command: u32, p1: u32, p2: u32, extension: &[u8]) -> Result<u32> {
+        let mut request = Vec::with_capacity(16 + extension.len());
+
+        for word in [command, p1, p2, extension.len() as u32] {
+            request.extend_from_slice(&word.to_le_bytes());
+        }
+
+        request.extend_from_slice(extension);
+        self.stream.write_all(&request)?;
+
+        // The answer repeats the request, with the result in place of the
+        // extension's length
+        let mut response = [0; 16];
+        self.stream.read_exact(&mut response)?;
+
+        let result = i32::from_le_bytes([response[12], response[13], response[14], response[15]]);
+
+        u32::try_from(result)
+            .map_err(|_| anyhow!("pigpio command {} failed with {}", command, result))
+    }
+
+    /// Drives the GPIOs whose bits are set in `mask` high.
+    pub fn set(&mut self, mask: u32) -> Result<()> {
+        self.command(BS1, mask, 0, &[])?;
+
+        Ok(())
+    }
+
+    /// Drives the GPIOs whose bits are set in `mask` low.
+    pub fn clear(&mut self, mask: u32) -> Result<()> {
+        self.command(BC1, mask, 0, &[])?;
+
+        Ok(())
+    }
+
+    /// Plays `pulses` over and over, once the waveform currently playing, if
+    /// any, gets to its end.
+    pub fn repeat(&mut self, pulses: &[Pulse]) -> Result<()> {
+        let mut extension = Vec::with_capacity(pulses.len() * 12);
+
+        for pulse in pulses {
+            for word in [pulse.high, pulse.low, pulse.delay.as_micros() as u32] {
+                extension.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+
+        self.command(WVAG, 0, 0, &extension)?;
+
+        let wave = self.command(WVCRE, 0, 0, &[])?;
+        self.command(WVTXM, wave, WAVE_MODE_REPEAT_SYNC, &[])?;
+        self.waves.push_back(wave);
+
+        while self.waves.len() > KEPT_WAVES {
+            if let Some(old) = self.waves.pop_front() {
+                self.command(WVDEL, old, 0, &[])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops playing waveforms.
+    pub fn halt(&mut self) -> Result<()> {
+        self.command(WVHLT, 0, 0, &[])?;
+
+        Ok(())
+    }
+}
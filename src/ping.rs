@@ -0,0 +1,133 @@
+use crate::{
+    animation::Animation, config::PingConfig, framebuffer::FrameBuffer, text::ScrollingText,
+};
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use std::{
+    process::{Command, Stdio},
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Number of frames the grid is shown for between two scrolls of the hosts
+/// which are down.
+const GRID_FRAMES: u32 = 15;
+
+/// Host reachability indicator.
+///
+/// Every configured host gets a 2x2 cell, going left to right and top to
+/// bottom, which is lit while the host answers pings. The names of the hosts
+/// which don't are scrolled in between.
+pub struct Ping {
+    hosts: Vec<String>,
+    // Whether each host answered the last ping, once it was pinged
+    reachable: Vec<Option<bool>>,
+    results: Receiver<Vec<bool>>,
+    // Names of the hosts which are down, and for how many more frames they
+    // are shown
+    warning: Option<(ScrollingText, u32)>,
+    grid_frames: u32,
+}
+
+impl Ping {
+    pub fn new(config: PingConfig) -> Self {
+        let (tx, rx) = channel();
+
+        let hosts = config.hosts.clone();
+
+        // Pinging blocks until the hosts answer or time out, so it happens on
+        // its own thread
+        thread::spawn(move || loop {
+            let results = config.hosts.iter().map(|host| ping(host)).collect();
+
+            if tx.send(results).is_err() {
+                return;
+            }
+
+            thread::sleep(Duration::from_secs(config.interval_secs));
+        });
+
+        Self {
+            reachable: vec![None; hosts.len()],
+            hosts,
+            results: rx,
+            warning: None,
+            grid_frames: 0,
+        }
+    }
+}
+
+/// Sends a single ping to `host`, returning whether it answered.
+///
+/// This uses the system `ping` command, since sending ICMP packets directly
+/// needs extra privileges.
+fn ping(host: &str) -> bool {
+    Command::new("ping")
+        .args(["-c", "1", "-W", "1", host])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+impl Animation for Ping {
+    fn tick(&mut self) {
+        if let Some(results) = self.results.try_iter().last() {
+            self.reachable = results.into_iter().map(Some).collect();
+        }
+
+        if let Some((text, frames)) = &mut self.warning {
+            text.tick();
+            *frames -= 1;
+
+            if *frames == 0 {
+                self.warning = None;
+                self.grid_frames = GRID_FRAMES;
+            }
+
+            return;
+        }
+
+        self.grid_frames = self.grid_frames.saturating_sub(1);
+
+        let down: Vec<_> = self
+            .hosts
+            .iter()
+            .zip(&self.reachable)
+            .filter(|(_, reachable)| **reachable == Some(false))
+            .map(|(host, _)| host.as_str())
+            .collect();
+
+        if !down.is_empty() && self.grid_frames == 0 {
+            let text = ScrollingText::new(format!("Down: {}", down.join(", ")));
+            let frames = text.width();
+
+            self.warning = Some((text, frames));
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some((text, _)) = &self.warning {
+            text.draw(frame);
+            return;
+        }
+
+        let style = PrimitiveStyle::with_fill(BinaryColor::On);
+
+        for (i, reachable) in self.reachable.iter().enumerate() {
+            if *reachable != Some(true) {
+                continue;
+            }
+
+            let top_left = Point::new(i as i32 % 4 * 2, i as i32 / 4 * 2);
+
+            let Ok(()) = Rectangle::new(top_left, Size::new(2, 2))
+                .into_styled(style)
+                .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    input::{Button, InputEvent},
+};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_4X6, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle},
+    text::{Baseline, Text},
+};
+use rand::Rng;
+
+/// Height of both paddles, in pixels.
+const PADDLE_HEIGHT: i32 = 3;
+
+/// Number of frames the score is flashed for after every point.
+const SCORE_FRAMES: u32 = 10;
+
+/// Number of frames the score is flashed for once a player won.
+const FINAL_SCORE_FRAMES: u32 = 30;
+
+/// Points a player needs to win. The scores are flashed as single digits, so
+/// this can't be more than 9.
+const WINNING_SCORE: u32 = 9;
+
+/// Chance of the computer player not moving its paddle on a given frame, so
+/// that it can actually be beaten.
+const COMPUTER_HESITATION: f64 = 0.3;
+
+/// Two-paddle Pong.
+///
+/// The left paddle is moved with the up and down buttons. The right paddle is
+/// played by the computer until the left and right buttons are used, which
+/// hands it over to a second player (left moves it up, right moves it down).
+///
+/// The first player to `WINNING_SCORE` points wins, and a new game starts.
+pub struct Pong {
+    // Top edge of the left and right paddles
+    paddles: [i32; 2],
+    ball: Point,
+    velocity: Point,
+    scores: [u32; 2],
+    second_player: bool,
+    // Frames left to flash the score for, while the ball waits to be served
+    score_frames: u32,
+    frame_count: u32,
+}
+
+impl Pong {
+    pub fn new() -> Self {
+        let mut pong = Self {
+            paddles: [(HEIGHT as i32 - PADDLE_HEIGHT) / 2; 2],
+            ball: Point::zero(),
+            velocity: Point::zero(),
+            scores: [0; 2],
+            second_player: false,
+            score_frames: 0,
+            frame_count: 0,
+        };
+
+        pong.serve();
+
+        pong
+    }
+
+    /// Puts the ball back in the middle, heading towards a random side.
+    fn serve(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        self.ball = Point::new(WIDTH as i32 / 2, rng.gen_range(1..HEIGHT as i32 - 1));
+        self.velocity = Point::new(
+            if rng.gen() { 1 } else { -1 },
+            if rng.gen() { 1 } else { -1 },
+        );
+    }
+
+    fn move_paddle(&mut self, paddle: usize, dy: i32) {
+        self.paddles[paddle] = (self.paddles[paddle] + dy).clamp(0, HEIGHT as i32 - PADDLE_HEIGHT);
+    }
+
+    fn paddle_covers(&self, paddle: usize, y: i32) -> bool {
+        (self.paddles[paddle]..self.paddles[paddle] + PADDLE_HEIGHT).contains(&y)
+    }
+
+    fn move_computer_paddle(&mut self) {
+        if rand::thread_rng().gen_bool(COMPUTER_HESITATION) {
+            return;
+        }
+
+        let center = self.paddles[1] + PADDLE_HEIGHT / 2;
+
+        self.move_paddle(1, (self.ball.y - center).signum());
+    }
+
+    fn move_ball(&mut self) {
+        let max_y = HEIGHT as i32 - 1;
+
+        // Bounce off the top and bottom edges
+        if !(0..=max_y).contains(&(self.ball.y + self.velocity.y)) {
+            self.velocity.y = -self.velocity.y;
+        }
+
+        let next = self.ball + self.velocity;
+
+        // The paddles sit in the first and last columns
+        let paddle = if next.x <= 0 {
+            Some(0)
+        } else if next.x >= WIDTH as i32 - 1 {
+            Some(1)
+        } else {
+            None
+        };
+
+        match paddle {
+            Some(paddle) if self.paddle_covers(paddle, next.y) => {
+                self.velocity.x = -self.velocity.x;
+                self.ball.y = next.y;
+            }
+            Some(paddle) => {
+                // The ball got past the paddle, so the other player scores
+                self.scores[1 - paddle] += 1;
+                self.score_frames = if self.scores[1 - paddle] == WINNING_SCORE {
+                    FINAL_SCORE_FRAMES
+                } else {
+                    SCORE_FRAMES
+                };
+                self.serve();
+            }
+            None => self.ball = next,
+        }
+    }
+}
+
+impl Animation for Pong {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if self.score_frames > 0 {
+            self.score_frames -= 1;
+
+            // The next game starts once the final score was flashed
+            if self.score_frames == 0 && self.scores.contains(&WINNING_SCORE) {
+                self.scores = [0; 2];
+            }
+
+            return;
+        }
+
+        if !self.second_player {
+            self.move_computer_paddle();
+        }
+
+        self.move_ball();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.score_frames > 0 {
+            // Flash the scores of both players side by side
+            if self.frame_count.is_multiple_of(2) {
+                let style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+
+                for (player, score) in self.scores.iter().enumerate() {
+                    let position = Point::new(player as i32 * 4, 1);
+                    let score = score.to_string();
+
+                    let Ok(_) =
+                        Text::with_baseline(&score, position, style, Baseline::Top).draw(frame);
+                }
+            }
+
+            return;
+        }
+
+        let style = PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+
+        for (paddle, x) in [(0, 0), (1, WIDTH as i32 - 1)] {
+            let top = Point::new(x, self.paddles[paddle]);
+
+            let Ok(()) = Line::new(top, top + Point::new(0, PADDLE_HEIGHT - 1))
+                .into_styled(style)
+                .draw(frame);
+        }
+
+        let Ok(()) = Pixel(self.ball, BinaryColor::On).draw(frame);
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let InputEvent::Pressed(button) = event else {
+            return;
+        };
+
+        match button {
+            Button::Up => self.move_paddle(0, -1),
+            Button::Down => self.move_paddle(0, 1),
+            Button::Left => {
+                self.second_player = true;
+                self.move_paddle(1, -1);
+            }
+            Button::Right => {
+                self.second_player = true;
+                self.move_paddle(1, 1);
+            }
+            Button::Select => {}
+        }
+    }
+}
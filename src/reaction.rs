@@ -0,0 +1,88 @@
+use crate::{
+    animation::Animation, framebuffer::FrameBuffer, input::InputEvent, text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+/// Shortest time the display stays blank before lighting up.
+const MIN_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest time the display stays blank before lighting up.
+const MAX_DELAY: Duration = Duration::from_secs(6);
+
+/// Reaction timer game.
+///
+/// The display stays blank for a random amount of time and then lights up,
+/// and the player has to press any button as fast as possible. The reaction
+/// time is then scrolled until a button is pressed to start another round.
+pub struct Reaction {
+    state: State,
+}
+
+enum State {
+    // Waiting to light up the display at the given time
+    Waiting { until: Instant },
+    // The display lit up at the given time
+    Lit { since: Instant },
+    Result(ScrollingText),
+}
+
+impl Reaction {
+    pub fn new() -> Self {
+        Self { state: wait() }
+    }
+}
+
+fn wait() -> State {
+    let delay = rand::thread_rng().gen_range(MIN_DELAY..MAX_DELAY);
+
+    State::Waiting {
+        until: Instant::now() + delay,
+    }
+}
+
+impl Animation for Reaction {
+    fn tick(&mut self) {
+        match &mut self.state {
+            State::Waiting { until } if Instant::now() >= *until => {
+                self.state = State::Lit {
+                    since: Instant::now(),
+                }
+            }
+            State::Result(text) => text.tick(),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match &self.state {
+            State::Waiting { .. } => {}
+            State::Lit { .. } => {
+                let Ok(()) = frame.clear(BinaryColor::On);
+            }
+            State::Result(text) => text.draw(frame),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        if !matches!(event, InputEvent::Pressed(_)) {
+            return;
+        }
+
+        let message = match &self.state {
+            State::Waiting { .. } => "Too soon!".to_string(),
+            State::Lit { since } => format!("{} ms", since.elapsed().as_millis()),
+            State::Result(_) => {
+                self.state = wait();
+                return;
+            }
+        };
+
+        self.state = State::Result(ScrollingText::new(message));
+    }
+}
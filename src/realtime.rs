@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use std::{io, mem};
+
+/// Makes the calling thread a real-time thread with the `SCHED_FIFO` policy
+/// and `priority`, from 1 to 99, which only threads of a higher priority can
+/// preempt.
+///
+/// This needs root or the `CAP_SYS_NICE` capability.
+pub fn set_fifo_priority(priority: u8) -> Result<()> {
+    // SAFETY: plain system calls with no pointers
+    let (min, max) = unsafe {
+        (
+            libc::sched_get_priority_min(libc::SCHED_FIFO),
+            libc::sched_get_priority_max(libc::SCHED_FIFO),
+        )
+    };
+
+    if !(min..=max).contains(&(priority as i32)) {
+        return Err(anyhow!(
+            "real-time priority {} isn't between {} and {}",
+            priority,
+            min,
+            max
+        ));
+    }
+
+    // SAFETY: `sched_param` is plain data for which all zeros is a valid value
+    let mut param: libc::sched_param = unsafe { mem::zeroed() };
+    param.sched_priority = priority as i32;
+
+    // SAFETY: `param` is a valid, initialized struct, and the thread is the
+    // calling one
+    let result =
+        unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+
+    // Unlike most calls, this returns the error rather than setting `errno`
+    if result != 0 {
+        return Err(io::Error::from_raw_os_error(result).into());
+    }
+
+    Ok(())
+}
+
+/// Restricts the calling thread to only run on the CPU core `cpu`, counting
+/// from 0.
+pub fn pin_to_cpu(cpu: usize) -> Result<()> {
+    if cpu >= libc::CPU_SETSIZE as usize {
+        return Err(anyhow!("there is no CPU core {}", cpu));
+    }
+
+    // SAFETY: `cpu_set_t` is plain data for which all zeros is an empty set
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+
+    // SAFETY: `cpu` was checked to fit in the set, and `set` is passed with
+    // its actual size
+    let result = unsafe {
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+
+    if result == -1 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use anyhow::{anyhow, Result};
+
+/// A regular expression, for picking values out of text.
+///
+/// Only a small part of the usual syntax is supported, which is enough for
+/// values: literal characters, `.`, classes like `[0-9.]` or `[^ ]`, the
+/// escapes `\d`, `\w` and `\s`, the greedy quantifiers `*`, `+` and `?`, the
+/// anchors `^` and `$`, and a single group which captures the value. There is
+/// no alternation.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    items: Vec<Item>,
+    anchored: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Item {
+    Match(Matcher, Quantifier),
+    GroupStart,
+    GroupEnd,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Quantifier {
+    One,
+    Optional,
+    Any,
+    AtLeastOne,
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Char(char),
+    AnyChar,
+    Digit,
+    Word,
+    Space,
+    /// Ranges of characters, and whether the class is negated.
+    Class(Vec<(char, char)>, bool),
+}
+
+impl Matcher {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Matcher::Char(expected) => c == *expected,
+            Matcher::AnyChar => true,
+            Matcher::Digit => c.is_ascii_digit(),
+            Matcher::Word => c.is_alphanumeric() || c == '_',
+            Matcher::Space => c.is_whitespace(),
+            Matcher::Class(ranges, negated) => {
+                ranges.iter().any(|&(low, high)| (low..=high).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut chars = pattern.chars().peekable();
+        let mut items = Vec::new();
+        let anchored = chars.next_if_eq(&'^').is_some();
+        let mut groups = 0;
+
+        while let Some(c) = chars.next() {
+            let matcher = match c {
+                '(' if groups == 0 => {
+                    groups += 1;
+                    items.push(Item::GroupStart);
+                    continue;
+                }
+                ')' if groups == 1 && !matches!(items.last(), Some(Item::GroupEnd)) => {
+                    items.push(Item::GroupEnd);
+                    continue;
+                }
+                '$' if chars.peek().is_none() => {
+                    items.push(Item::End);
+                    continue;
+                }
+                '.' => Matcher::AnyChar,
+                '\\' => match chars.next() {
+                    Some('d') => Matcher::Digit,
+                    Some('w') => Matcher::Word,
+                    Some('s') => Matcher::Space,
+                    Some(c) if !c.is_alphanumeric() => Matcher::Char(c),
+                    _ => return Err(anyhow!("unsupported escape in regex {:?}", pattern)),
+                },
+                '[' => {
+                    let negated = chars.next_if_eq(&'^').is_some();
+                    let mut ranges = Vec::new();
+                    let unterminated = || anyhow!("unterminated class in regex {:?}", pattern);
+
+                    loop {
+                        let low = match chars.next() {
+                            Some(']') if !ranges.is_empty() => break,
+                            Some('\\') => chars.next(),
+                            c => c,
+                        }
+                        .ok_or_else(unterminated)?;
+
+                        let high = match chars.next_if_eq(&'-') {
+                            // A dash at the end is a dash
+                            Some(_) if chars.peek() == Some(&']') => {
+                                ranges.push(('-', '-'));
+                                low
+                            }
+                            Some(_) => chars.next().ok_or_else(unterminated)?,
+                            None => low,
+                        };
+
+                        ranges.push((low, high));
+                    }
+
+                    Matcher::Class(ranges, negated)
+                }
+                '(' | ')' | '|' | '*' | '+' | '?' | '{' | '}' | '^' | '$' => {
+                    return Err(anyhow!("unsupported {:?} in regex {:?}", c, pattern))
+                }
+                c => Matcher::Char(c),
+            };
+
+            let quantifier = match chars.next_if(|c| matches!(c, '*' | '+' | '?')) {
+                Some('*') => Quantifier::Any,
+                Some('+') => Quantifier::AtLeastOne,
+                Some('?') => Quantifier::Optional,
+                _ => Quantifier::One,
+            };
+
+            items.push(Item::Match(matcher, quantifier));
+        }
+
+        if groups == 1 && !items.iter().any(|item| matches!(item, Item::GroupEnd)) {
+            return Err(anyhow!("unterminated group in regex {:?}", pattern));
+        }
+
+        Ok(Self { items, anchored })
+    }
+
+    /// Returns what the group captures in the first match in `text`, or the
+    /// whole match if there is no group.
+    pub fn find<'a>(&self, text: &'a str) -> Option<&'a str> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        // Byte offset of every character, and of the end of the text
+        let offset = |i: usize| chars.get(i).map_or(text.len(), |&(offset, _)| offset);
+
+        let starts = if self.anchored {
+            0..1
+        } else {
+            0..chars.len() + 1
+        };
+
+        for start in starts {
+            let mut group = (start, None);
+
+            if let Some(end) = self.match_at(&self.items, &chars, start, &mut group) {
+                let (group_start, group_end) = group;
+
+                return Some(&text[offset(group_start)..offset(group_end.unwrap_or(end))]);
+            }
+        }
+
+        None
+    }
+
+    /// Matches `items` at the character `position`, returning where the
+    /// match ends, and setting where the group starts and ends.
+    fn match_at(
+        &self,
+        items: &[Item],
+        chars: &[(usize, char)],
+        position: usize,
+        group: &mut (usize, Option<usize>),
+    ) -> Option<usize> {
+        let Some((item, rest)) = items.split_first() else {
+            return Some(position);
+        };
+
+        match item {
+            Item::GroupStart => {
+                let saved = *group;
+                group.0 = position;
+
+                let end = self.match_at(rest, chars, position, group);
+
+                if end.is_none() {
+                    *group = saved;
+                }
+
+                end
+            }
+            Item::GroupEnd => {
+                let saved = *group;
+                group.1 = Some(position);
+
+                let end = self.match_at(rest, chars, position, group);
+
+                if end.is_none() {
+                    *group = saved;
+                }
+
+                end
+            }
+            Item::End => (position == chars.len()).then_some(position),
+            Item::Match(matcher, quantifier) => {
+                let (min, max) = match quantifier {
+                    Quantifier::One => (1, 1),
+                    Quantifier::Optional => (0, 1),
+                    Quantifier::Any => (0, usize::MAX),
+                    Quantifier::AtLeastOne => (1, usize::MAX),
+                };
+
+                let available = chars[position..]
+                    .iter()
+                    .take(max)
+                    .take_while(|(_, c)| matcher.matches(*c))
+                    .count();
+
+                // Greedy, so the longest repetition is tried first
+                (min..=available)
+                    .rev()
+                    .find_map(|count| self.match_at(rest, chars, position + count, group))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(pattern: &str, text: &'a str) -> Option<&'a str> {
+        Regex::new(pattern).unwrap().find(text)
+    }
+
+    #[test]
+    fn finds_the_first_match() {
+        assert_eq!(find("\\d+", "up 42 days, 7 users"), Some("42"));
+        assert_eq!(find("[0-9.]+", "v1.25 released"), Some("1.25"));
+        assert_eq!(find("a.c", "xxabcxx"), Some("abc"));
+        assert_eq!(find("\\w+", "  hello world"), Some("hello"));
+        assert_eq!(find("x", "abc"), None);
+    }
+
+    #[test]
+    fn returns_what_the_group_captures() {
+        assert_eq!(find("temp=(\\d+)C", "temp=21C"), Some("21"));
+        assert_eq!(find("load: ([^ ]*)", "load: 0.52 0.40"), Some("0.52"));
+        assert_eq!(find("(a*)b", "b"), Some(""));
+    }
+
+    #[test]
+    fn quantifiers_are_greedy() {
+        assert_eq!(find("a+", "caaat"), Some("aaa"));
+        assert_eq!(find("ab?c", "ac abc"), Some("ac"));
+        assert_eq!(find("<.*>", "<a><b>"), Some("<a><b>"));
+    }
+
+    #[test]
+    fn anchors_match_the_ends() {
+        assert_eq!(find("^\\d+", "12 ab 34"), Some("12"));
+        assert_eq!(find("^\\d+", "ab 34"), None);
+        assert_eq!(find("\\d+$", "12 ab 34"), Some("34"));
+        assert_eq!(find("\\d$", "12 ab"), None);
+    }
+
+    #[test]
+    fn parses_classes() {
+        assert_eq!(find("[^ ]+", "  word  "), Some("word"));
+        assert_eq!(find("[a-]+", "x-a-b"), Some("-a-"));
+        assert_eq!(find("[]]", "a]"), Some("]"));
+        assert_eq!(find("[\\]]+", "a]]"), Some("]]"));
+        assert_eq!(find("\\.\\d", "1.5"), Some(".5"));
+    }
+
+    #[test]
+    fn rejects_unterminated_classes() {
+        for pattern in ["[", "[a", "[a-", "[^", "[\\"] {
+            let err = Regex::new(pattern).unwrap_err();
+
+            assert!(
+                err.to_string().contains("unterminated class"),
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        for pattern in ["a|b", "a{2}", "(a)(b)", "(a", "a)", "\\p", "*a", "a^"] {
+            assert!(Regex::new(pattern).is_err(), "{}", pattern);
+        }
+    }
+}
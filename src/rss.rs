@@ -0,0 +1,219 @@
+use crate::{
+    animation::Animation, config::RssConfig, framebuffer::FrameBuffer, http, log,
+    text::ScrollingText,
+};
+use anyhow::{anyhow, Result};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Returns the titles of the items of an RSS feed or the entries of an Atom
+/// feed, in the order they appear in it.
+///
+/// This only looks for the tags it needs rather than parsing the XML, which
+/// is enough for the feeds found in the wild.
+fn titles(xml: &str) -> Vec<String> {
+    let mut titles = Vec::new();
+    let mut rest = xml;
+
+    while let Some((item, after)) = next_element(rest, &["item", "entry"]) {
+        if let Some((title, _)) = next_element(item, &["title"]) {
+            titles.push(decode(title).trim().to_string());
+        }
+
+        rest = after;
+    }
+
+    titles
+}
+
+/// Finds the first element named one of `names` in `xml`, returning its
+/// contents and everything after it.
+fn next_element<'a>(xml: &'a str, names: &[&str]) -> Option<(&'a str, &'a str)> {
+    let mut offset = 0;
+
+    loop {
+        let start = offset + xml[offset..].find('<')?;
+        let tag = &xml[start + 1..];
+
+        let name = names.iter().find(|name| {
+            tag.starts_with(*name)
+                && tag[name.len()..].starts_with(|c: char| c == '>' || c.is_whitespace())
+        });
+
+        let Some(name) = name else {
+            offset = start + 1;
+            continue;
+        };
+
+        let contents_start = start + 1 + tag.find('>')? + 1;
+        let closing = format!("</{name}>");
+        let contents_end = contents_start + xml[contents_start..].find(&closing)?;
+
+        return Some((
+            &xml[contents_start..contents_end],
+            &xml[contents_end + closing.len()..],
+        ));
+    }
+}
+
+/// Turns the contents of an element into plain text, unwrapping CDATA
+/// sections and decoding entities.
+fn decode(text: &str) -> String {
+    if let Some(cdata) = text
+        .trim()
+        .strip_prefix("<![CDATA[")
+        .and_then(|text| text.strip_suffix("]]>"))
+    {
+        return cdata.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes the entity `&name;`.
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Fetches the latest headlines of the configured feed.
+fn fetch(config: &RssConfig) -> Result<Vec<String>> {
+    let mut headlines = titles(&http::get(&config.url)?);
+
+    if headlines.is_empty() {
+        return Err(anyhow!("no headlines found in {}", config.url));
+    }
+
+    headlines.truncate(config.headlines);
+
+    Ok(headlines)
+}
+
+/// Scrolls the latest headlines of an RSS or Atom feed, one after the other.
+///
+/// The headlines are fetched again periodically, and the last ones which
+/// could be fetched keep being shown in between and while offline.
+pub struct Headlines {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<Vec<String>>>,
+    headlines: Vec<String>,
+    current: usize,
+    text: Option<ScrollingText>,
+    // Frames left until the current headline is done scrolling
+    scroll_frames: u32,
+}
+
+impl Headlines {
+    pub fn new(config: RssConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Fetching takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch(&config) {
+                Ok(headlines) => (
+                    Some(headlines),
+                    Duration::from_secs(config.interval_mins * 60),
+                ),
+                Err(err) => {
+                    log::warn!("RSS: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            headlines: Vec::new(),
+            current: 0,
+            text: None,
+            scroll_frames: 0,
+        }
+    }
+}
+
+impl Animation for Headlines {
+    fn tick(&mut self) {
+        if let Some(text) = &mut self.text {
+            text.tick();
+            self.scroll_frames = self.scroll_frames.saturating_sub(1);
+        }
+
+        if self.scroll_frames > 0 {
+            return;
+        }
+
+        // New headlines start from the first one, once the current one is
+        // done scrolling
+        if let Some(headlines) = self.updates.try_iter().flatten().last() {
+            self.headlines = headlines;
+            self.current = 0;
+        } else if !self.headlines.is_empty() {
+            self.current = (self.current + 1) % self.headlines.len();
+        }
+
+        if let Some(headline) = self.headlines.get(self.current) {
+            let text = ScrollingText::new(headline.clone());
+
+            self.scroll_frames = text.width();
+            self.text = Some(text);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some(text) = &self.text {
+            text.draw(frame);
+        }
+    }
+}
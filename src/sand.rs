@@ -0,0 +1,93 @@
+use crate::{
+    accelerometer::Accelerometer,
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use rand::{seq::SliceRandom, Rng};
+
+/// Number of sand grains on the matrix.
+const GRAINS: usize = 20;
+
+/// Falling-sand simulation.
+///
+/// Grains of sand fall towards whichever side of the board is tilted down,
+/// sliding off each other diagonally when they land on top of another grain.
+pub struct Sand {
+    accelerometer: Accelerometer,
+    grains: Vec<Point>,
+}
+
+impl Sand {
+    pub fn new(accelerometer: Accelerometer) -> Self {
+        let mut cells: Vec<_> = (0..WIDTH as i32)
+            .flat_map(|x| (0..HEIGHT as i32).map(move |y| Point::new(x, y)))
+            .collect();
+
+        cells.shuffle(&mut rand::thread_rng());
+        cells.truncate(GRAINS);
+
+        Self {
+            accelerometer,
+            grains: cells,
+        }
+    }
+
+    fn is_free(&self, p: Point) -> bool {
+        let inside = (0..WIDTH as i32).contains(&p.x) && (0..HEIGHT as i32).contains(&p.y);
+
+        inside && !self.grains.contains(&p)
+    }
+
+    /// Lets every grain fall one pixel in the `down` direction, if possible.
+    fn step(&mut self, down: Point) {
+        // Move the grains closest to the bottom first, so the ones above them
+        // can fall into the space they leave behind
+        self.grains
+            .sort_by_key(|grain| -(grain.x * down.x + grain.y * down.y));
+
+        let mut rng = rand::thread_rng();
+
+        for i in 0..self.grains.len() {
+            let grain = self.grains[i];
+
+            // The two directions 45 degrees to either side of straight down
+            let mut slides = [
+                Point::new(down.x - down.y, down.y + down.x),
+                Point::new(down.x + down.y, down.y - down.x),
+            ]
+            .map(|slide| Point::new(slide.x.signum(), slide.y.signum()));
+
+            if rng.gen() {
+                slides.swap(0, 1);
+            }
+
+            let target = [down, slides[0], slides[1]]
+                .into_iter()
+                .map(|direction| grain + direction)
+                .find(|&target| self.is_free(target));
+
+            if let Some(target) = target {
+                self.grains[i] = target;
+            }
+        }
+    }
+}
+
+impl Animation for Sand {
+    fn tick(&mut self) {
+        // Keep the sand still if the accelerometer can't be read
+        if let Ok(Some(down)) = self.accelerometer.gravity() {
+            self.step(down);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let grains = self
+            .grains
+            .iter()
+            .map(|&grain| Pixel(grain, BinaryColor::On));
+
+        let Ok(()) = frame.draw_iter(grains);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    accelerometer,
+    action::Action,
+    animation::Animation,
+    compositor::{Blend, Compositor},
+    config::{Config, PlaylistEntry},
+    framebuffer::FrameBuffer,
+    input::InputEvent,
+    log,
+    mode::Mode,
+    source,
+};
+use anyhow::Result;
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// Everything creating a scene can use.
+pub struct SceneContext {
+    /// Messages given on the command line.
+    pub messages: Vec<String>,
+    /// Accelerometer selected on the command line.
+    pub accelerometer: accelerometer::Model,
+    /// File selected on the command line for the tail mode.
+    pub file: Option<PathBuf>,
+    /// Latest values of the data sources.
+    pub sources: source::Values,
+    pub config: Config,
+}
+
+/// Creates the animation of a scene, or fails if it can't be shown, for
+/// example because its hardware is missing.
+pub type Create = fn(&SceneContext) -> Result<Box<dyn Animation>>;
+
+/// A scene which the display can show as its mode.
+pub struct RegisteredScene {
+    /// Name the scene is selected by.
+    pub name: &'static str,
+    /// What the scene shows, for the help of the command line.
+    pub about: &'static str,
+    pub create: Create,
+}
+
+/// Every scene there is, in the order they are cycled through, so that new
+/// ones can be added without touching the code which selects them.
+#[derive(Default)]
+pub struct Registry {
+    scenes: Vec<RegisteredScene>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a scene, which is selected by `name`.
+    ///
+    /// # Panics
+    ///
+    /// If a scene was already registered as `name`.
+    pub fn register(mut self, name: &'static str, about: &'static str, create: Create) -> Self {
+        assert!(
+            self.scenes.iter().all(|scene| scene.name != name),
+            "scene {name} is registered twice"
+        );
+
+        self.scenes.push(RegisteredScene {
+            name,
+            about,
+            create,
+        });
+
+        self
+    }
+
+    /// Makes the scenes of the registry the ones modes are selected from. Only
+    /// the first registry installed is used.
+    pub fn install(self) {
+        let _ = REGISTERED.set(self.scenes);
+    }
+}
+
+static REGISTERED: OnceLock<Vec<RegisteredScene>> = OnceLock::new();
+
+/// Returns the scenes of the installed registry.
+pub fn registered() -> &'static [RegisteredScene] {
+    REGISTERED.get().map_or(&[], Vec::as_slice)
+}
+
+/// Creates the animation shown by a mode.
+pub type Factory = Box<dyn Fn(Mode) -> Result<Box<dyn Animation>>>;
+
+/// Owns the scene the display is running, which is the animation of a mode
+/// with layers on top of it, and switches to other modes while it runs.
+///
+/// Switching crossfades from the old mode to the new one. Input, and the
+/// actions which aren't about switching modes, go to the current mode and
+/// the layers on top of it.
+///
+/// Messages set while it runs replace the ones of the current mode, and of
+/// every mode switched to afterwards.
+///
+/// With a playlist, it also switches to the next mode of the playlist on its
+/// own whenever the current one was shown for long enough.
+pub struct SceneManager {
+    mode: Mode,
+    layers: Compositor,
+    factory: Factory,
+    playlist: Vec<PlaylistEntry>,
+    // Entry of the playlist being shown, and since when
+    position: usize,
+    since: Instant,
+    // Shared rather than copied, since the state of the screen is saved
+    // after every refresh
+    messages: Arc<[String]>,
+}
+
+impl SceneManager {
+    /// Creates the scene of `mode`, whose text mode shows `messages`, which
+    /// can't be empty.
+    pub fn new(
+        mode: Mode,
+        factory: Factory,
+        messages: Vec<String>,
+        crossfade_frames: u32,
+    ) -> Result<Self> {
+        Ok(Self {
+            mode,
+            layers: Compositor::new(factory(mode)?, crossfade_frames),
+            factory,
+            playlist: Vec::new(),
+            position: 0,
+            since: Instant::now(),
+            messages: messages.into(),
+        })
+    }
+
+    /// Starts cycling through `playlist`, from the entry of the current mode
+    /// if there is one.
+    pub fn set_playlist(&mut self, playlist: Vec<PlaylistEntry>) {
+        self.position = playlist
+            .iter()
+            .position(|entry| entry.mode == self.mode)
+            .unwrap_or(0);
+        self.since = Instant::now();
+        self.playlist = playlist;
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the messages of the text mode.
+    pub fn messages(&self) -> Arc<[String]> {
+        self.messages.clone()
+    }
+
+    /// Adds a layer on top of the mode and the other layers.
+    pub fn push_layer(&mut self, animation: Box<dyn Animation>, blend: Blend, opacity: u8) {
+        self.layers.push(animation, blend, opacity);
+    }
+
+    /// Switches to `mode`, or keeps the current one if `mode` can't be
+    /// started.
+    pub fn switch_to(&mut self, mode: Mode) -> Result<()> {
+        let mut animation = (self.factory)(mode)?;
+
+        animation.set_messages(&self.messages);
+
+        log::info!("Switching to {} mode", mode.name());
+
+        self.mode = mode;
+        self.layers.set_base(animation);
+
+        Ok(())
+    }
+
+    /// Switches to the next mode which can be started, skipping the ones
+    /// which can't, for example because their hardware is missing.
+    pub fn next(&mut self) {
+        let modes: Vec<Mode> = Mode::all().collect();
+        let mut index = modes.iter().position(|&m| m == self.mode).unwrap();
+
+        loop {
+            index = (index + 1) % modes.len();
+
+            match self.switch_to(modes[index]) {
+                Ok(()) => break,
+                Err(err) => log::debug!("Skipping {} mode: {:#}", modes[index].name(), err),
+            }
+        }
+    }
+
+    /// Switches to the next entry of the playlist, skipping the ones whose
+    /// mode can't be started.
+    fn advance_playlist(&mut self) {
+        self.since = Instant::now();
+
+        for _ in 0..self.playlist.len() {
+            self.position = (self.position + 1) % self.playlist.len();
+
+            let mode = self.playlist[self.position].mode;
+
+            // Staying on the same mode doesn't start it over
+            if mode == self.mode {
+                return;
+            }
+
+            match self.switch_to(mode) {
+                Ok(()) => return,
+                Err(err) => log::warn!("Skipping {} mode of the playlist: {:#}", mode.name(), err),
+            }
+        }
+    }
+}
+
+impl Animation for SceneManager {
+    fn tick(&mut self) {
+        if let Some(entry) = self.playlist.get(self.position) {
+            if self.since.elapsed() >= Duration::from_secs(entry.duration_secs) {
+                self.advance_playlist();
+            }
+        }
+
+        self.layers.tick();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        self.layers.draw(frame);
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        self.layers.handle_input(event);
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.layers.is_interactive()
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::NextMode => self.next(),
+            Action::SwitchMode(mode) => {
+                if let Err(err) = self.switch_to(mode) {
+                    log::warn!("Can't switch to {} mode: {:#}", mode.name(), err);
+                }
+            }
+            _ => self.layers.handle_action(action),
+        }
+    }
+
+    fn set_messages(&mut self, messages: &[String]) {
+        self.messages = messages.into();
+        self.layers.set_messages(messages);
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{
+    animation::Animation,
+    config::ScriptConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use anyhow::{anyhow, Context, Result};
+use embedded_graphics::prelude::*;
+use std::{collections::HashMap, mem::MaybeUninit, time::Instant};
+
+/// An animation written as an expression in the configuration, which is
+/// worked out for every pixel on every frame to give its brightness, from `0`
+/// (off) to `1` (brightest).
+///
+/// The expression can use:
+///
+/// - `x` and `y`, the column and row of the pixel, from `0` to `7`
+/// - `t`, the seconds since the mode started, and `frame`, the number of
+///   frames since then
+/// - `hour`, `minute` and `second`, the local time
+/// - the variables of the configuration, by name
+/// - numbers, `+ - * / %`, comparisons, `&&`, `||`, `!` and `a ? b : c`
+/// - the functions `sin`, `cos`, `abs`, `floor`, `sqrt`, `min` and `max`
+///
+/// Comparisons give `1` when they hold and `0` otherwise, so for example
+/// `x == y` draws a diagonal and `(x + y + t * 4) % 8 < 2` sweeps stripes
+/// across the matrix. Results which aren't numbers, like dividing by zero,
+/// leave the pixel off.
+///
+/// This is a small language of its own rather than an embedded scripting
+/// engine like Rhai, which isn't a dependency: there are no statements or
+/// state kept from one frame to the next, only the expression.
+pub struct Script {
+    expression: Expr,
+    start: Instant,
+    frame_count: u32,
+}
+
+impl Script {
+    pub fn new(config: ScriptConfig) -> Result<Self> {
+        let expression = Parser::new(&config.expression, &config.variables)
+            .parse()
+            .context("invalid script")?;
+
+        Ok(Self {
+            expression,
+            start: Instant::now(),
+            frame_count: 0,
+        })
+    }
+}
+
+impl Animation for Script {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let (hour, minute, second) = local_time();
+
+        let mut inputs = Inputs {
+            x: 0.0,
+            y: 0.0,
+            time: self.start.elapsed().as_secs_f64(),
+            frame: self.frame_count as f64,
+            hour,
+            minute,
+            second,
+        };
+
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                inputs.x = x as f64;
+                inputs.y = y as f64;
+
+                let level = level(self.expression.eval(&inputs));
+
+                frame.set_level(Point::new(x as i32, y as i32), level);
+            }
+        }
+    }
+}
+
+/// Returns the level of a pixel the expression gave `value` for.
+fn level(value: f64) -> u8 {
+    // Invalid results, like dividing by zero, leave the pixel off
+    if !value.is_finite() {
+        return 0;
+    }
+
+    (value.clamp(0.0, 1.0) * u8::MAX as f64).round() as u8
+}
+
+/// Returns the local hour, minute and second.
+fn local_time() -> (f64, f64, f64) {
+    let mut tm = MaybeUninit::<libc::tm>::uninit();
+
+    // SAFETY: a null pointer is allowed, and `tm` is only read once
+    // `localtime_r` filled it in
+    let tm = unsafe {
+        let now = libc::time(std::ptr::null_mut());
+
+        if libc::localtime_r(&now, tm.as_mut_ptr()).is_null() {
+            return (0.0, 0.0, 0.0);
+        }
+
+        tm.assume_init()
+    };
+
+    (tm.tm_hour as f64, tm.tm_min as f64, tm.tm_sec as f64)
+}
+
+/// What an expression can read, which changes from one pixel or frame to the
+/// next.
+#[derive(Clone, Copy)]
+enum Input {
+    X,
+    Y,
+    Time,
+    Frame,
+    Hour,
+    Minute,
+    Second,
+}
+
+struct Inputs {
+    x: f64,
+    y: f64,
+    time: f64,
+    frame: f64,
+    hour: f64,
+    minute: f64,
+    second: f64,
+}
+
+impl Inputs {
+    fn get(&self, input: Input) -> f64 {
+        match input {
+            Input::X => self.x,
+            Input::Y => self.y,
+            Input::Time => self.time,
+            Input::Frame => self.frame,
+            Input::Hour => self.hour,
+            Input::Minute => self.minute,
+            Input::Second => self.second,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Le,
+    Ge,
+    Lt,
+    Gt,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Clone, Copy)]
+enum Function {
+    Sin,
+    Cos,
+    Abs,
+    Floor,
+    Sqrt,
+    Min,
+    Max,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => Function::Sin,
+            "cos" => Function::Cos,
+            "abs" => Function::Abs,
+            "floor" => Function::Floor,
+            "sqrt" => Function::Sqrt,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            _ => return None,
+        })
+    }
+
+    /// Number of arguments the function takes.
+    fn arity(self) -> usize {
+        match self {
+            Function::Min | Function::Max => 2,
+            _ => 1,
+        }
+    }
+}
+
+enum Expr {
+    /// A number, which is also what the variables of the configuration turn
+    /// into.
+    Number(f64),
+    Input(Input),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `condition ? then : otherwise`
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+fn truth(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+impl Expr {
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        match self {
+            Expr::Number(number) => *number,
+            Expr::Input(input) => inputs.get(*input),
+            Expr::Negate(operand) => -operand.eval(inputs),
+            Expr::Not(operand) => truth(operand.eval(inputs) == 0.0),
+            Expr::Binary(op, left, right) => {
+                let left = left.eval(inputs);
+
+                // Only work out the right side when it makes a difference
+                match op {
+                    BinaryOp::Or if left != 0.0 => return 1.0,
+                    BinaryOp::And if left == 0.0 => return 0.0,
+                    _ => {}
+                }
+
+                let right = right.eval(inputs);
+
+                match op {
+                    BinaryOp::Or | BinaryOp::And => truth(right != 0.0),
+                    BinaryOp::Eq => truth(left == right),
+                    BinaryOp::Ne => truth(left != right),
+                    BinaryOp::Le => truth(left <= right),
+                    BinaryOp::Ge => truth(left >= right),
+                    BinaryOp::Lt => truth(left < right),
+                    BinaryOp::Gt => truth(left > right),
+                    BinaryOp::Add => left + right,
+                    BinaryOp::Sub => left - right,
+                    BinaryOp::Mul => left * right,
+                    BinaryOp::Div => left / right,
+                    // Wraps negative numbers around too, so that patterns
+                    // moving left repeat like the ones moving right
+                    BinaryOp::Rem => left.rem_euclid(right),
+                }
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if condition.eval(inputs) != 0.0 {
+                    then.eval(inputs)
+                } else {
+                    otherwise.eval(inputs)
+                }
+            }
+            Expr::Call(function, arguments) => {
+                let argument = |index: usize| arguments[index].eval(inputs);
+
+                match function {
+                    Function::Sin => argument(0).sin(),
+                    Function::Cos => argument(0).cos(),
+                    Function::Abs => argument(0).abs(),
+                    Function::Floor => argument(0).floor(),
+                    Function::Sqrt => argument(0).sqrt(),
+                    Function::Min => argument(0).min(argument(1)),
+                    Function::Max => argument(0).max(argument(1)),
+                }
+            }
+        }
+    }
+}
+
+/// Binary operators, from the loosest to the tightest binding. Operators
+/// starting like another one have to come before it.
+const PRECEDENCE: [&[(&str, BinaryOp)]; 5] = [
+    &[("||", BinaryOp::Or)],
+    &[("&&", BinaryOp::And)],
+    &[
+        ("==", BinaryOp::Eq),
+        ("!=", BinaryOp::Ne),
+        ("<=", BinaryOp::Le),
+        (">=", BinaryOp::Ge),
+        ("<", BinaryOp::Lt),
+        (">", BinaryOp::Gt),
+    ],
+    &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+    &[
+        ("*", BinaryOp::Mul),
+        ("/", BinaryOp::Div),
+        ("%", BinaryOp::Rem),
+    ],
+];
+
+struct Parser<'a> {
+    // What is left to parse
+    rest: &'a str,
+    variables: &'a HashMap<String, f64>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, variables: &'a HashMap<String, f64>) -> Self {
+        Self {
+            rest: source,
+            variables,
+        }
+    }
+
+    fn parse(mut self) -> Result<Expr> {
+        let expression = self.parse_conditional()?;
+
+        self.skip_whitespace();
+
+        match self.rest.chars().next() {
+            None => Ok(expression),
+            Some(c) => Err(anyhow!("unexpected {:?}", c)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    /// Skips `symbol` if it comes next, and returns whether it did.
+    fn eat(&mut self, symbol: &str) -> bool {
+        self.skip_whitespace();
+
+        match self.rest.strip_prefix(symbol) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(anyhow!("expected {:?}", symbol))
+        }
+    }
+
+    /// Takes the characters at the start of what is left for as long as
+    /// `accept` holds.
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c| !accept(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+
+        self.rest = rest;
+
+        taken
+    }
+
+    fn parse_conditional(&mut self) -> Result<Expr> {
+        let condition = self.parse_binary(0)?;
+
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+
+        let then = self.parse_conditional()?;
+        self.expect(":")?;
+        let otherwise = self.parse_conditional()?;
+
+        Ok(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn parse_binary(&mut self, level: usize) -> Result<Expr> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.parse_unary();
+        };
+
+        let mut left = self.parse_binary(level + 1)?;
+
+        'operators: loop {
+            for &(symbol, op) in *operators {
+                if self.eat(symbol) {
+                    let right = self.parse_binary(level + 1)?;
+                    left = Expr::Binary(op, Box::new(left), Box::new(right));
+
+                    continue 'operators;
+                }
+            }
+
+            return Ok(left);
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.eat("-") {
+            Ok(Expr::Negate(Box::new(self.parse_unary()?)))
+        } else if self.eat("!") {
+            Ok(Expr::Not(Box::new(self.parse_unary()?)))
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        if self.eat("(") {
+            let expression = self.parse_conditional()?;
+            self.expect(")")?;
+
+            return Ok(expression);
+        }
+
+        match self.rest.chars().next() {
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+
+                number
+                    .parse()
+                    .map(Expr::Number)
+                    .map_err(|_| anyhow!("invalid number {:?}", number))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+
+                if self.eat("(") {
+                    self.parse_call(name)
+                } else {
+                    self.parse_variable(name)
+                }
+            }
+            Some(c) => Err(anyhow!("unexpected {:?}", c)),
+            None => Err(anyhow!("the expression ended too early")),
+        }
+    }
+
+    /// Parses the arguments of a call to the function `name`, once its opening
+    /// parenthesis was skipped.
+    fn parse_call(&mut self, name: &str) -> Result<Expr> {
+        let function = Function::parse(name).ok_or_else(|| anyhow!("unknown function {}", name))?;
+        let mut arguments = Vec::new();
+
+        if !self.eat(")") {
+            loop {
+                arguments.push(self.parse_conditional()?);
+
+                if self.eat(")") {
+                    break;
+                }
+
+                self.expect(",")?;
+            }
+        }
+
+        if arguments.len() != function.arity() {
+            return Err(anyhow!(
+                "{} takes {} arguments but was given {}",
+                name,
+                function.arity(),
+                arguments.len()
+            ));
+        }
+
+        Ok(Expr::Call(function, arguments))
+    }
+
+    fn parse_variable(&self, name: &str) -> Result<Expr> {
+        let input = match name {
+            "x" => Input::X,
+            "y" => Input::Y,
+            "t" => Input::Time,
+            "frame" => Input::Frame,
+            "hour" => Input::Hour,
+            "minute" => Input::Minute,
+            "second" => Input::Second,
+            _ => {
+                return self
+                    .variables
+                    .get(name)
+                    .map(|&value| Expr::Number(value))
+                    .ok_or_else(|| anyhow!("unknown variable {}", name))
+            }
+        };
+
+        Ok(Expr::Input(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> f64 {
+        eval_at(expression, 0.0, 0.0)
+    }
+
+    fn eval_at(expression: &str, x: f64, y: f64) -> f64 {
+        let variables = HashMap::from([("speed".to_string(), 2.0)]);
+        let inputs = Inputs {
+            x,
+            y,
+            time: 1.5,
+            frame: 3.0,
+            hour: 12.0,
+            minute: 30.0,
+            second: 45.0,
+        };
+
+        Parser::new(expression, &variables)
+            .parse()
+            .unwrap()
+            .eval(&inputs)
+    }
+
+    fn parse_error(expression: &str) -> String {
+        match Parser::new(expression, &HashMap::new()).parse() {
+            Ok(_) => panic!("{:?} parsed", expression),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn follows_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("-2 * -3"), 6.0);
+        assert_eq!(eval("1 + 1 == 2 && 3 > 2"), 1.0);
+        assert_eq!(eval("0 || 2 < 1"), 0.0);
+        assert_eq!(eval("!0 + 1"), 2.0);
+    }
+
+    #[test]
+    fn evaluates_conditionals() {
+        assert_eq!(eval("1 ? 2 : 3"), 2.0);
+        assert_eq!(eval("0 ? 2 : 1 ? 4 : 5"), 4.0);
+        assert_eq!(eval("(0 ? 2 : 3) + 1"), 4.0);
+    }
+
+    #[test]
+    fn reads_inputs_and_variables() {
+        assert_eq!(eval_at("x * 10 + y", 3.0, 5.0), 35.0);
+        assert_eq!(eval("t * speed"), 3.0);
+        assert_eq!(eval("frame + hour + minute + second"), 90.0);
+    }
+
+    #[test]
+    fn calls_functions() {
+        assert_eq!(eval("abs(-2.5)"), 2.5);
+        assert_eq!(eval("floor(2.7)"), 2.0);
+        assert_eq!(eval("sqrt(16)"), 4.0);
+        assert_eq!(eval("min(3, max(1, 2))"), 2.0);
+        assert_eq!(eval("sin(0) + cos(0)"), 1.0);
+    }
+
+    #[test]
+    fn remainders_wrap_around() {
+        assert_eq!(eval("7 % 3"), 1.0);
+        assert_eq!(eval("-1 % 8"), 7.0);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(parse_error("1 +").contains("ended too early"));
+        assert!(parse_error("(1").contains("expected"));
+        assert!(parse_error("1 2").contains("unexpected"));
+        assert!(parse_error("z").contains("unknown variable z"));
+        assert!(parse_error("tan(1)").contains("unknown function tan"));
+        assert!(parse_error("min(1)").contains("takes 2 arguments"));
+        assert!(parse_error("1..2").contains("invalid number"));
+        assert!(parse_error("1 ? 2").contains("expected"));
+    }
+
+    #[test]
+    fn invalid_results_leave_the_pixel_off() {
+        assert_eq!(level(eval("1 / 0")), 0);
+        assert_eq!(level(eval("-1 / 0")), 0);
+        assert_eq!(level(eval("0 / 0")), 0);
+        assert_eq!(level(eval("sqrt(-1)")), 0);
+    }
+
+    #[test]
+    fn levels_are_clamped() {
+        assert_eq!(level(-0.5), 0);
+        assert_eq!(level(0.5), 128);
+        assert_eq!(level(2.0), u8::MAX);
+    }
+}
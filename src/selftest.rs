@@ -0,0 +1,123 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    log,
+};
+use embedded_graphics::prelude::*;
+
+/// Number of frames every pixel is lit for at the start.
+const ALL_ON_FRAMES: u32 = 10;
+
+/// Number of frames every row or column of a sweep is lit for.
+const SWEEP_FRAMES: u32 = 2;
+
+/// Number of frames each of the two checkerboards is shown for, twice.
+const CHECKERBOARD_FRAMES: u32 = 5;
+
+/// Patterns which each light up the pixels in a way that shows a different
+/// fault.
+#[derive(Clone, Copy)]
+enum Pattern {
+    /// Dead LEDs.
+    AllOn,
+    /// Broken or swapped row lines.
+    Rows,
+    /// Broken or swapped column lines.
+    Columns,
+    /// Shorts between neighbouring lines.
+    Checkerboard,
+    /// One pixel at a time, which shows whether the pixels are in order.
+    Walk,
+}
+
+const PATTERNS: [Pattern; 5] = [
+    Pattern::AllOn,
+    Pattern::Rows,
+    Pattern::Columns,
+    Pattern::Checkerboard,
+    Pattern::Walk,
+];
+
+impl Pattern {
+    fn name(self) -> &'static str {
+        match self {
+            Pattern::AllOn => "all on",
+            Pattern::Rows => "row sweep",
+            Pattern::Columns => "column sweep",
+            Pattern::Checkerboard => "checkerboard",
+            Pattern::Walk => "pixel walk",
+        }
+    }
+
+    /// Returns how many frames the pattern takes.
+    fn frames(self) -> u32 {
+        match self {
+            Pattern::AllOn => ALL_ON_FRAMES,
+            Pattern::Rows => HEIGHT as u32 * SWEEP_FRAMES,
+            Pattern::Columns => WIDTH as u32 * SWEEP_FRAMES,
+            Pattern::Checkerboard => 4 * CHECKERBOARD_FRAMES,
+            Pattern::Walk => (WIDTH * HEIGHT) as u32,
+        }
+    }
+
+    /// Returns whether the pixel at `x`, `y` is lit on the `frame` of the
+    /// pattern.
+    fn is_lit(self, frame: u32, x: u32, y: u32) -> bool {
+        match self {
+            Pattern::AllOn => true,
+            Pattern::Rows => y == frame / SWEEP_FRAMES,
+            Pattern::Columns => x == frame / SWEEP_FRAMES,
+            Pattern::Checkerboard => (x + y + frame / CHECKERBOARD_FRAMES).is_multiple_of(2),
+            Pattern::Walk => y * WIDTH as u32 + x == frame,
+        }
+    }
+}
+
+/// Cycles through test patterns, to find dead LEDs and wiring faults: every
+/// pixel lit, a sweep of the rows and then of the columns, a checkerboard
+/// and its negative, and a walk through the pixels one by one.
+///
+/// The name of every pattern is logged as it starts.
+pub struct SelfTest {
+    pattern: usize,
+    // Frames since the pattern started
+    frame_count: u32,
+}
+
+impl SelfTest {
+    pub fn new() -> Self {
+        log::info!("Self-test: {}", PATTERNS[0].name());
+
+        Self {
+            pattern: 0,
+            frame_count: 0,
+        }
+    }
+}
+
+impl Animation for SelfTest {
+    fn tick(&mut self) {
+        self.frame_count += 1;
+
+        if self.frame_count < PATTERNS[self.pattern].frames() {
+            return;
+        }
+
+        self.pattern = (self.pattern + 1) % PATTERNS.len();
+        self.frame_count = 0;
+
+        log::info!("Self-test: {}", PATTERNS[self.pattern].name());
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let pattern = PATTERNS[self.pattern];
+
+        for y in 0..HEIGHT as u32 {
+            for x in 0..WIDTH as u32 {
+                if pattern.is_lit(self.frame_count, x, y) {
+                    frame.set_level(Point::new(x as i32, y as i32), u8::MAX);
+                }
+            }
+        }
+    }
+}
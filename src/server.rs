@@ -0,0 +1,969 @@
+use crate::{
+    action::Action,
+    command::{DisplayCommand, DisplayHandle},
+    config::{Config, ServerConfig, StartupConfig},
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    json::Json,
+    log::{self, Level, Span},
+    metrics::Metrics,
+    mode::Mode,
+    state::ScreenState,
+    webhook::Webhook,
+    websocket::WebSocket,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Largest request body which is accepted, in bytes.
+const MAX_BODY_LENGTH: usize = 64 * 1024;
+
+/// Longest request line or header which is accepted, in bytes.
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// Most headers a request can have.
+const MAX_HEADERS: usize = 64;
+
+/// How long a client can take to send a bit more of its request before the
+/// connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most connections which are handled at once, each on a thread of its own.
+const MAX_CONNECTIONS: usize = 32;
+
+/// Page of the pixel editor, whose clicks are shown on the display as they
+/// happen.
+const EDITOR_PAGE: &str = include_str!("editor.html");
+
+/// Page of the dashboard, which shows what is on the display and changes the
+/// settings saved to the startup section of the configuration.
+///
+/// There is no schedule among them, since the display has no notion of one
+/// to change: it doesn't turn on, off or dim at set times of day.
+const DASHBOARD_PAGE: &str = include_str!("dashboard.html");
+
+/// Paths of the endpoints which send a command to the display when `POST`ed
+/// to.
+const COMMAND_PATHS: [&str; 10] = [
+    "/text",
+    "/mode",
+    "/announce",
+    "/brightness",
+    "/frame",
+    "/pause",
+    "/resume",
+    "/power/on",
+    "/power/off",
+    "/clear",
+];
+
+/// An HTTP request, as far as the server needs to understand it.
+pub struct Request {
+    pub method: String,
+    /// Path of the request, without the query string.
+    pub path: String,
+    /// Query string of the request, without the `?`.
+    pub query: String,
+    /// Headers of the request, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads a request from `stream`.
+    ///
+    /// Requests with a line longer than `MAX_LINE_LENGTH` or with more than
+    /// `MAX_HEADERS` headers fail with [`HeadTooLarge`].
+    fn read(stream: &mut impl BufRead) -> Result<Self> {
+        let mut line = String::new();
+        read_line(stream, &mut line)?;
+
+        // `GET /metrics HTTP/1.1`
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(anyhow!("malformed request line {:?}", line));
+        };
+
+        let method = method.to_string();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let (path, query) = (path.to_string(), query.to_string());
+
+        let mut headers = Vec::new();
+
+        loop {
+            read_line(stream, &mut line)?;
+
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+
+            if headers.len() == MAX_HEADERS {
+                return Err(HeadTooLarge.into());
+            }
+
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+
+        let mut request = Self {
+            method,
+            path,
+            query,
+            headers,
+            body: Vec::new(),
+        };
+
+        let length: usize = request
+            .header("content-length")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or(0);
+
+        if length > MAX_BODY_LENGTH {
+            return Err(anyhow!("request body of {} bytes is too large", length));
+        }
+
+        request.body.resize(length, 0);
+        stream.read_exact(&mut request.body)?;
+
+        Ok(request)
+    }
+
+    /// Returns the value of the header `name`, which has to be lowercase.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of the query parameter `name`, as it is in the
+    /// query string.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the token of the `Authorization: Bearer` header, if there is
+    /// one.
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.header("authorization")?.strip_prefix("Bearer ")
+    }
+}
+
+/// Reads a line of the head of a request into `line`, replacing what it had.
+fn read_line(stream: &mut impl BufRead, line: &mut String) -> Result<()> {
+    line.clear();
+
+    // One more byte than is accepted tells a line which is too long from one
+    // which is just as long as it can be
+    stream.take(MAX_LINE_LENGTH as u64 + 1).read_line(line)?;
+
+    if line.len() > MAX_LINE_LENGTH {
+        return Err(HeadTooLarge.into());
+    }
+
+    Ok(())
+}
+
+/// Error of a request whose line or headers are too long, or whose headers
+/// are too many.
+#[derive(Debug)]
+struct HeadTooLarge;
+
+impl fmt::Display for HeadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the request line and headers should have at most {} bytes each, and there should be at most {} headers",
+            MAX_LINE_LENGTH, MAX_HEADERS
+        )
+    }
+}
+
+impl std::error::Error for HeadTooLarge {}
+
+/// An HTTP response.
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    pub fn text(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body)
+    }
+
+    fn write(&self, stream: &mut TcpStream) -> Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            503 => "Service Unavailable",
+            _ => "",
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        )?;
+
+        stream.write_all(&self.body)?;
+
+        Ok(())
+    }
+}
+
+/// Everything the server's endpoints need to get at.
+#[derive(Clone)]
+pub struct State {
+    pub metrics: Arc<Metrics>,
+    pub display: DisplayHandle,
+    /// State of the screen the last time it was drawn, if it ever was.
+    pub screen: Arc<Mutex<Option<ScreenState>>>,
+    /// Last frame drawn on the display.
+    pub frame: Arc<Mutex<FrameBuffer>>,
+    /// Configuration file the dashboard saves its changes to, if there is
+    /// one.
+    pub config_path: Option<PathBuf>,
+    /// When the display started.
+    pub started: Instant,
+    /// Token the commands and saving the settings need, if there is one.
+    pub token: Option<String>,
+    /// Rules of the webhook, if it's configured.
+    pub webhook: Option<Arc<Webhook>>,
+}
+
+impl State {
+    /// Whether `request` gives the token, as the `token` query parameter or a
+    /// bearer token. Without a token, every request is.
+    fn authorized(&self, request: &Request) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+
+        [request.query_param("token"), request.bearer_token()].contains(&Some(token.as_str()))
+    }
+}
+
+/// Starts the HTTP server on a new thread, which handles every connection on
+/// a thread of its own, and returns the address it listens on.
+pub fn spawn(config: ServerConfig, state: State) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(&config.address)
+        .with_context(|| format!("failed to listen on {}", config.address))?;
+
+    let address = listener.local_addr()?;
+
+    if state.token.is_none() && !address.ip().is_loopback() {
+        log::warn!(
+            "HTTP server: there is no token, so anyone who can reach {} can send commands",
+            address
+        );
+    }
+
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+
+            // Slow clients would otherwise pile up threads
+            if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                connections.fetch_sub(1, Ordering::SeqCst);
+
+                log::warn!("HTTP server: too many connections, dropping one");
+
+                let _ = Response::text(503, "Too many connections\n").write(&mut stream);
+                continue;
+            }
+
+            let state = state.clone();
+            let connections = connections.clone();
+
+            thread::spawn(move || {
+                if let Err(err) = handle(stream, &state) {
+                    log::warn!("HTTP server: {:#}", err);
+                }
+
+                connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+
+    Ok(address)
+}
+
+fn handle(stream: TcpStream, state: &State) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+
+    let response = match Request::read(&mut reader) {
+        Ok(request) => {
+            let _span = Span::new(Level::Debug, "HTTP request");
+
+            log::debug!("HTTP server: {} {}", request.method, request.path);
+
+            if request.path != "/editor/socket" {
+                route(&request, state)
+            } else if !same_origin(&request) {
+                // Pages elsewhere could otherwise draw on the display through
+                // the browser of someone on the network
+                Response::text(403, "The editor's socket only takes pages of the server\n")
+            } else if !state.authorized(&request) {
+                Response::text(401, "Wrong or missing token\n")
+            } else {
+                // The editor can be left alone for as long as it likes
+                stream.set_read_timeout(None)?;
+
+                // Its socket keeps the connection for itself
+                return edit_frames(WebSocket::accept(&request, reader, stream)?, state);
+            }
+        }
+        Err(err) if err.is::<HeadTooLarge>() => Response::text(431, format!("{:#}\n", err)),
+        Err(err) => Response::text(400, format!("{:#}\n", err)),
+    };
+
+    response.write(&mut stream)
+}
+
+/// Whether `request` comes from a page of the server itself, going by its
+/// `Origin`. Clients other than browsers don't send one, and can't be tricked
+/// into sending a request anyway.
+fn same_origin(request: &Request) -> bool {
+    let Some(origin) = request.header("origin") else {
+        return true;
+    };
+
+    // `http://matrix.local:8080`
+    let origin = origin.split_once("://").map_or(origin, |(_, host)| host);
+
+    request
+        .header("host")
+        .is_some_and(|host| host.eq_ignore_ascii_case(origin))
+}
+
+/// Returns the response of the endpoint `request` is for.
+fn route(request: &Request, state: &State) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => {
+            Response::new(200, "text/plain; version=0.0.4", state.metrics.render())
+        }
+        ("GET", "/stats") => Response::new(200, "application/json", state.metrics.stats_json()),
+        ("GET", "/status") => match lock(&state.screen).as_ref() {
+            Some(screen) => Response::new(200, "application/json", status_json(screen, state)),
+            None => Response::text(503, "The display didn't start yet\n"),
+        },
+        ("POST", path) if COMMAND_PATHS.contains(&path) => command(request, state),
+        ("POST", "/webhook") => webhook(request, state),
+        ("GET", "/editor") => Response::new(200, "text/html; charset=utf-8", EDITOR_PAGE),
+        ("GET", "/dashboard") => Response::new(200, "text/html; charset=utf-8", DASHBOARD_PAGE),
+        ("GET", "/frame") => Response::text(200, format_frame(&lock(&state.frame))),
+        ("GET", "/settings") => match lock(&state.screen).as_ref() {
+            Some(screen) => Response::new(200, "application/json", settings_json(screen, state)),
+            None => Response::text(503, "The display didn't start yet\n"),
+        },
+        ("POST", "/settings") => save_settings(request, state),
+        (
+            _,
+            "/metrics" | "/stats" | "/status" | "/editor" | "/dashboard" | "/settings" | "/webhook",
+        ) => Response::text(405, "Method not allowed\n"),
+        (_, path) if COMMAND_PATHS.contains(&path) => Response::text(405, "Method not allowed\n"),
+        _ => Response::text(404, "Not found\n"),
+    }
+}
+
+/// Sends the command posted to one of the display's endpoints, once the token
+/// was checked if there is one.
+fn command(request: &Request, state: &State) -> Response {
+    if !state.authorized(request) {
+        return Response::text(401, "Wrong or missing token\n");
+    }
+
+    match parse_command(&request.path, &request.body) {
+        Ok(command) => match state.display.send(command) {
+            Ok(()) => Response::text(200, "OK\n"),
+            Err(err) => Response::text(500, format!("{:#}\n", err)),
+        },
+        Err(err) => Response::text(400, format!("{:#}\n", err)),
+    }
+}
+
+/// Applies and saves the settings posted by the dashboard, once its token was
+/// checked.
+fn save_settings(request: &Request, state: &State) -> Response {
+    // Another page could post to the server from the browser, but not with
+    // the token
+    let Some(token) = &state.token else {
+        return Response::text(
+            403,
+            "Saving the settings needs a token in the server section of the configuration\n",
+        );
+    };
+
+    if request.bearer_token() != Some(token.as_str()) {
+        return Response::text(401, "Wrong or missing token\n");
+    }
+
+    match parse_settings(&request.body) {
+        Ok(settings) => match apply_settings(settings, state) {
+            Ok(()) => Response::text(200, "OK\n"),
+            Err(err) => Response::text(500, format!("{:#}\n", err)),
+        },
+        Err(err) => Response::text(400, format!("{:#}\n", err)),
+    }
+}
+
+/// Announces the payload posted to the webhook, once its token was checked.
+fn webhook(request: &Request, state: &State) -> Response {
+    let Some(webhook) = &state.webhook else {
+        return Response::text(404, "The webhook isn't configured\n");
+    };
+
+    // Nothing of the body is looked at before the sender is known
+    if !webhook.authorized(request) {
+        return Response::text(401, "Wrong or missing token\n");
+    }
+
+    let payload = match parse_json(&request.body) {
+        Ok(payload) => payload,
+        Err(err) => return Response::text(400, format!("{:#}\n", err)),
+    };
+
+    match webhook.command(&payload) {
+        Some(command) => match state.display.send(command) {
+            Ok(()) => Response::text(200, "OK\n"),
+            Err(err) => Response::text(500, format!("{:#}\n", err)),
+        },
+        None => Response::text(200, "No rule matches the payload\n"),
+    }
+}
+
+/// Parses the levels of the 64 pixels of a frame, row by row, separated by
+/// whitespace.
+fn parse_frame(text: &str) -> Result<FrameBuffer> {
+    let levels = text
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| anyhow!("the levels should be from 0 to 255"))?;
+
+    FrameBuffer::from_levels(&levels).ok_or_else(|| {
+        anyhow!(
+            "the frame should have {} levels, not {}",
+            WIDTH * HEIGHT,
+            levels.len()
+        )
+    })
+}
+
+/// Formats the levels of `frame` the way [`parse_frame`] parses them, a row
+/// per line.
+fn format_frame(frame: &FrameBuffer) -> String {
+    let mut text = String::new();
+
+    for y in 0..HEIGHT {
+        let row: Vec<String> = frame.row(y).iter().map(u8::to_string).collect();
+
+        text.push_str(&row.join(" "));
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Returns what the display is doing, for scripts and dashboards to check.
+fn status_json(screen: &ScreenState, state: &State) -> String {
+    let strings =
+        |strings: &[String]| Json::Array(strings.iter().cloned().map(Json::String).collect());
+    let load = |metric: &AtomicU64| Json::Number(metric.load(Ordering::Relaxed) as f64);
+
+    Json::Object(vec![
+        (
+            "mode".to_string(),
+            Json::String(screen.mode.name().to_string()),
+        ),
+        ("messages".to_string(), strings(&screen.messages)),
+        (
+            "announcement".to_string(),
+            screen.announcement.clone().map_or(Json::Null, Json::String),
+        ),
+        ("queued_announcements".to_string(), strings(&screen.queued)),
+        (
+            "brightness".to_string(),
+            Json::Number(screen.brightness as f64),
+        ),
+        ("powered".to_string(), Json::Bool(screen.powered)),
+        ("paused".to_string(), Json::Bool(screen.paused)),
+        (
+            "refresh_rate_hz".to_string(),
+            load(&state.metrics.refresh_rate),
+        ),
+        ("fps".to_string(), load(&state.metrics.fps)),
+        (
+            "uptime_seconds".to_string(),
+            Json::Number(state.started.elapsed().as_secs() as f64),
+        ),
+    ])
+    .to_string()
+}
+
+/// Returns what the dashboard shows and changes, with the modes it can
+/// switch to and whether its changes are saved.
+fn settings_json(screen: &ScreenState, state: &State) -> String {
+    let string = |string: &str| Json::String(string.to_string());
+
+    Json::Object(vec![
+        ("mode".to_string(), string(screen.mode.name())),
+        (
+            "modes".to_string(),
+            Json::Array(Mode::all().map(|mode| string(mode.name())).collect()),
+        ),
+        (
+            "messages".to_string(),
+            Json::Array(
+                screen
+                    .messages
+                    .iter()
+                    .map(|message| string(message))
+                    .collect(),
+            ),
+        ),
+        (
+            "brightness".to_string(),
+            Json::Number(screen.brightness as f64),
+        ),
+        ("saved".to_string(), Json::Bool(state.config_path.is_some())),
+    ])
+    .to_string()
+}
+
+/// Parses the settings posted by the dashboard, a JSON object with the
+/// `mode`, the `messages` and the `brightness`.
+fn parse_settings(body: &[u8]) -> Result<StartupConfig> {
+    let settings = parse_json(body)?;
+
+    let mode = settings
+        .get("mode")
+        .and_then(Json::as_str)
+        .ok_or_else(|| anyhow!("the mode is missing"))?;
+    let mode = Mode::from_name(mode).ok_or_else(|| anyhow!("unknown mode {:?}", mode))?;
+
+    let messages = settings
+        .get("messages")
+        .and_then(Json::as_array)
+        .and_then(|messages| {
+            messages
+                .iter()
+                .map(|message| message.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+        })
+        .filter(|messages| !messages.is_empty())
+        .ok_or_else(|| anyhow!("the messages should be a list of at least one string"))?;
+
+    let brightness = settings
+        .get("brightness")
+        .and_then(Json::as_f64)
+        .filter(|brightness| (0.0..=255.0).contains(brightness))
+        .ok_or_else(|| anyhow!("the brightness should be a level from 0 to 255"))?;
+
+    Ok(StartupConfig {
+        mode: Some(mode),
+        messages,
+        brightness: brightness as u8,
+    })
+}
+
+fn parse_json(body: &[u8]) -> Result<Json> {
+    Json::parse(std::str::from_utf8(body).context("the body isn't UTF-8")?)
+}
+
+/// Shows `settings` on the display, and saves them to the configuration file
+/// if there is one, so that the display starts with them.
+fn apply_settings(settings: StartupConfig, state: &State) -> Result<()> {
+    let current_mode = lock(&state.screen).as_ref().map(|screen| screen.mode);
+
+    // Switching to the mode which is shown would start it over
+    if let Some(mode) = settings.mode.filter(|&mode| Some(mode) != current_mode) {
+        state
+            .display
+            .send(DisplayCommand::Action(Action::SwitchMode(mode)))?;
+    }
+
+    state
+        .display
+        .send(DisplayCommand::SetMessages(settings.messages.clone()))?;
+    state
+        .display
+        .send(DisplayCommand::SetBrightness(settings.brightness))?;
+
+    if let Some(path) = &state.config_path {
+        Config::save_startup(path, &settings)?;
+    }
+
+    Ok(())
+}
+
+/// Locks `mutex`, even if the drawing thread panicked while holding it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Shows the frames drawn in the editor as they come over its socket, until
+/// it is closed.
+///
+/// Every message is either a frame, in the format of `/frame`, or `clear` to
+/// go back to the mode.
+fn edit_frames(mut socket: WebSocket, state: &State) -> Result<()> {
+    while let Some(message) = socket.read_message()? {
+        let command = match message.trim() {
+            "clear" => DisplayCommand::Clear,
+            frame => match parse_frame(frame) {
+                Ok(frame) => DisplayCommand::ShowFrame(frame),
+                Err(err) => {
+                    log::warn!("Editor: {:#}", err);
+                    continue;
+                }
+            },
+        };
+
+        state.display.send(command)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the command a `POST` to one of the display's endpoints is for.
+///
+/// The text endpoints take the text as the body, `/mode` the name of a mode,
+/// `/brightness` a level from `0` to `255`, and `/frame` a frame as parsed by
+/// [`parse_frame`].
+fn parse_command(path: &str, body: &[u8]) -> Result<DisplayCommand> {
+    let body = std::str::from_utf8(body).context("the body isn't UTF-8")?;
+
+    let command = match path {
+        "/text" => DisplayCommand::SetText(body.trim().to_string()),
+        "/mode" => {
+            let mode = Mode::from_name(body.trim())
+                .ok_or_else(|| anyhow!("unknown mode {:?}", body.trim()))?;
+
+            DisplayCommand::Action(Action::SwitchMode(mode))
+        }
+        "/announce" => DisplayCommand::Announce(body.trim().to_string()),
+        "/brightness" => DisplayCommand::SetBrightness(
+            body.trim()
+                .parse()
+                .map_err(|_| anyhow!("the brightness should be a level from 0 to 255"))?,
+        ),
+        "/frame" => DisplayCommand::ShowFrame(parse_frame(body)?),
+        "/pause" => DisplayCommand::Pause,
+        "/resume" => DisplayCommand::Resume,
+        "/power/on" => DisplayCommand::PowerOn,
+        "/power/off" => DisplayCommand::PowerOff,
+        "/clear" => DisplayCommand::Clear,
+        _ => return Err(anyhow!("there is no command at {}", path)),
+    };
+
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command, config::WebhookConfig};
+    use std::sync::mpsc::Receiver;
+
+    const TOKEN: &str = "s3cret";
+
+    fn state(webhook: Option<&str>) -> (State, Receiver<DisplayCommand>) {
+        let (display, commands) = command::channel();
+
+        let webhook = webhook.map(|rules| {
+            let config: WebhookConfig =
+                toml::from_str(&format!("token = {:?}\n{}", TOKEN, rules)).unwrap();
+
+            Arc::new(Webhook::new(&config).unwrap())
+        });
+
+        let state = State {
+            metrics: Arc::new(Metrics::new()),
+            display,
+            screen: Arc::new(Mutex::new(None)),
+            frame: Arc::new(Mutex::new(FrameBuffer::new())),
+            config_path: None,
+            started: Instant::now(),
+            token: Some(TOKEN.to_string()),
+            webhook,
+        };
+
+        (state, commands)
+    }
+
+    fn post(path: &str, query: &str, body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn with_bearer(mut request: Request, token: &str) -> Request {
+        request
+            .headers
+            .push(("authorization".to_string(), format!("Bearer {}", token)));
+
+        request
+    }
+
+    #[test]
+    fn requests_are_read() {
+        let mut stream = &b"POST /text?token=a HTTP/1.1\r\nContent-Length: 2\r\n\r\nHi"[..];
+
+        let request = Request::read(&mut stream).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/text");
+        assert_eq!(request.query_param("token"), Some("a"));
+        assert_eq!(request.body, b"Hi");
+    }
+
+    #[test]
+    fn requests_with_long_lines_are_rejected() {
+        let head = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_LENGTH));
+
+        let err = Request::read(&mut head.as_bytes()).err().unwrap();
+
+        assert!(err.is::<HeadTooLarge>());
+    }
+
+    #[test]
+    fn requests_with_many_headers_are_rejected() {
+        let head = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-A: b\r\n".repeat(MAX_HEADERS + 1)
+        );
+
+        let err = Request::read(&mut head.as_bytes()).err().unwrap();
+
+        assert!(err.is::<HeadTooLarge>());
+    }
+
+    #[test]
+    fn commands_need_the_token() {
+        let (state, commands) = state(None);
+
+        for request in [
+            post("/text", "", "Hi"),
+            post("/power/off", "token=wrong", ""),
+            with_bearer(post("/clear", "", ""), "wrong"),
+        ] {
+            assert_eq!(route(&request, &state).status, 401);
+        }
+
+        assert!(commands.try_recv().is_err());
+
+        for request in [
+            post("/text", "token=s3cret", "Hi"),
+            with_bearer(post("/text", "", "Hi"), TOKEN),
+        ] {
+            assert_eq!(route(&request, &state).status, 200);
+        }
+
+        assert_eq!(commands.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn commands_are_open_without_a_configured_token() {
+        let (mut state, commands) = state(None);
+        state.token = None;
+
+        assert_eq!(route(&post("/text", "", "Hi"), &state).status, 200);
+        assert!(matches!(
+            commands.try_recv(),
+            Ok(DisplayCommand::SetText(text)) if text == "Hi"
+        ));
+    }
+
+    fn with_headers(mut request: Request, headers: &[(&str, &str)]) -> Request {
+        for (name, value) in headers {
+            request.headers.push((name.to_string(), value.to_string()));
+        }
+
+        request
+    }
+
+    #[test]
+    fn pages_of_the_server_are_the_same_origin() {
+        let request = |headers| with_headers(post("/editor/socket", "", ""), headers);
+
+        for headers in [
+            &[("host", "matrix.local:8080")][..],
+            &[
+                ("host", "matrix.local:8080"),
+                ("origin", "http://matrix.local:8080"),
+            ],
+            &[("host", "Matrix.local"), ("origin", "https://matrix.local")],
+        ] {
+            assert!(same_origin(&request(headers)));
+        }
+
+        for headers in [
+            &[("host", "matrix.local:8080"), ("origin", "http://evil.com")][..],
+            &[
+                ("host", "matrix.local:8080"),
+                ("origin", "http://matrix.local:8081"),
+            ],
+            &[("origin", "http://matrix.local:8080")],
+        ] {
+            assert!(!same_origin(&request(headers)));
+        }
+    }
+
+    const SETTINGS: &str = r#"{"mode": "text", "messages": ["Hi"], "brightness": 9}"#;
+
+    #[test]
+    fn settings_are_applied_with_the_token() {
+        // The mode is looked up by name
+        crate::scenes().install();
+
+        let (state, commands) = state(None);
+
+        let response = route(&with_bearer(post("/settings", "", SETTINGS), TOKEN), &state);
+
+        assert_eq!(response.status, 200);
+        assert!(commands
+            .try_iter()
+            .any(|command| matches!(command, DisplayCommand::SetBrightness(9))));
+    }
+
+    #[test]
+    fn settings_need_the_token() {
+        let (state, commands) = state(None);
+
+        for request in [
+            post("/settings", "", SETTINGS),
+            post("/settings", "token=s3cret", SETTINGS),
+            with_bearer(post("/settings", "", SETTINGS), "wrong"),
+        ] {
+            assert_eq!(route(&request, &state).status, 401);
+        }
+
+        assert!(commands.try_recv().is_err());
+    }
+
+    #[test]
+    fn settings_cant_be_saved_without_a_configured_token() {
+        let (mut state, commands) = state(None);
+        state.token = None;
+
+        let response = route(&with_bearer(post("/settings", "", SETTINGS), ""), &state);
+
+        assert_eq!(response.status, 403);
+        assert!(commands.try_recv().is_err());
+    }
+
+    const RULES: &str = "[[rules]]\nmessage = \"{$.title}\"\n";
+
+    #[test]
+    fn webhook_announces_payloads() {
+        let (state, commands) = state(Some(RULES));
+
+        let response = route(
+            &post("/webhook", "token=s3cret", r#"{"title": "Hi"}"#),
+            &state,
+        );
+
+        assert_eq!(response.status, 200);
+        assert!(matches!(
+            commands.try_recv(),
+            Ok(DisplayCommand::Alert(message, _)) if message == "Hi"
+        ));
+    }
+
+    #[test]
+    fn webhook_takes_a_bearer_token() {
+        let (state, _commands) = state(Some(RULES));
+
+        let request = with_bearer(post("/webhook", "", r#"{"title": "Hi"}"#), TOKEN);
+
+        assert_eq!(route(&request, &state).status, 200);
+    }
+
+    #[test]
+    fn webhook_cant_have_an_empty_token() {
+        for token in ["", " "] {
+            let config: WebhookConfig =
+                toml::from_str(&format!("token = {:?}\n{}", token, RULES)).unwrap();
+
+            assert!(Webhook::new(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn webhook_is_off_unless_configured() {
+        let (state, commands) = state(None);
+
+        let response = route(&post("/webhook", "token=s3cret", "{}"), &state);
+
+        assert_eq!(response.status, 404);
+        assert!(commands.try_recv().is_err());
+    }
+
+    #[test]
+    fn webhook_checks_the_token_before_the_body() {
+        let (state, commands) = state(Some(RULES));
+
+        for query in ["", "token=wrong"] {
+            let response = route(&post("/webhook", query, "not even JSON"), &state);
+
+            assert_eq!(response.status, 401);
+        }
+
+        assert!(commands.try_recv().is_err());
+    }
+
+    #[test]
+    fn webhook_rejects_deeply_nested_payloads() {
+        let (state, commands) = state(Some(RULES));
+
+        let body = "[".repeat(60_000);
+        let response = route(&post("/webhook", "token=s3cret", &body), &state);
+
+        assert_eq!(response.status, 400);
+        assert!(commands.try_recv().is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    input::{Button, InputEvent},
+    text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::PrimitiveStyle};
+use rand::seq::SliceRandom;
+
+/// Frames of darkness before the sequence is played back.
+const LEAD_IN_FRAMES: usize = 3;
+
+/// Frames every quadrant of the sequence is lit for.
+const FLASH_FRAMES: usize = 2;
+
+/// Frames of darkness between two flashes of the sequence.
+const GAP_FRAMES: usize = 1;
+
+/// Frames a quadrant is lit for after the player presses its button.
+const FEEDBACK_FRAMES: u32 = 2;
+
+/// A Simon-style memory game.
+///
+/// The quadrants of the matrix flash in a sequence which the player has to
+/// repeat with the buttons, going clockwise from the top left: up, right, down
+/// and left. Every correctly repeated sequence gets one step longer.
+pub struct Simon {
+    sequence: Vec<Button>,
+    state: State,
+    high_score: usize,
+    // Quadrant lit up after the player pressed its button, and for how many
+    // more frames
+    feedback: Option<(Button, u32)>,
+}
+
+enum State {
+    // Playing back the sequence, `frame` frames since the playback started
+    Showing { frame: usize },
+    // Waiting for the player to press the button for `sequence[index]`
+    Repeating { index: usize },
+    // The scores are scrolled until any button is pressed
+    GameOver(ScrollingText),
+}
+
+impl Simon {
+    pub fn new() -> Self {
+        let mut simon = Self {
+            sequence: Vec::new(),
+            state: State::Showing { frame: 0 },
+            high_score: 0,
+            feedback: None,
+        };
+
+        simon.restart();
+
+        simon
+    }
+
+    fn restart(&mut self) {
+        self.sequence.clear();
+        self.extend_sequence();
+    }
+
+    fn extend_sequence(&mut self) {
+        let button = *Button::ALL.choose(&mut rand::thread_rng()).unwrap();
+
+        self.sequence.push(button);
+        self.state = State::Showing { frame: 0 };
+    }
+
+    fn game_over(&mut self) {
+        // Only fully repeated sequences count towards the score
+        let score = self.sequence.len() - 1;
+
+        self.high_score = self.high_score.max(score);
+
+        let message = format!("Score: {} Best: {}", score, self.high_score);
+
+        self.state = State::GameOver(ScrollingText::new(message));
+    }
+}
+
+impl Animation for Simon {
+    fn tick(&mut self) {
+        if let Some((_, frames)) = &mut self.feedback {
+            *frames -= 1;
+
+            if *frames == 0 {
+                self.feedback = None;
+            }
+        }
+
+        match &mut self.state {
+            State::Showing { frame } => {
+                *frame += 1;
+
+                let played = frame.saturating_sub(LEAD_IN_FRAMES) / (FLASH_FRAMES + GAP_FRAMES);
+
+                if played >= self.sequence.len() {
+                    self.state = State::Repeating { index: 0 };
+                }
+            }
+            State::Repeating { .. } => {}
+            State::GameOver(text) => text.tick(),
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let lit = match &self.state {
+            State::Showing { frame } => frame.checked_sub(LEAD_IN_FRAMES).and_then(|frame| {
+                let index = frame / (FLASH_FRAMES + GAP_FRAMES);
+
+                if frame % (FLASH_FRAMES + GAP_FRAMES) < FLASH_FRAMES {
+                    self.sequence.get(index).copied()
+                } else {
+                    None
+                }
+            }),
+            State::Repeating { .. } => self.feedback.map(|(button, _)| button),
+            State::GameOver(text) => {
+                text.draw(frame);
+                None
+            }
+        };
+
+        if let Some(quadrant) = lit.and_then(Button::quadrant) {
+            let Ok(()) = quadrant
+                .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+                .draw(frame);
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let InputEvent::Pressed(button) = event else {
+            return;
+        };
+
+        // Select isn't one of the quadrants, and is held down to leave the
+        // game
+        if button == Button::Select {
+            return;
+        }
+
+        match self.state {
+            // Presses during the playback are ignored
+            State::Showing { .. } => {}
+            State::Repeating { index } => {
+                self.feedback = Some((button, FEEDBACK_FRAMES));
+
+                if button != self.sequence[index] {
+                    self.game_over();
+                } else if index + 1 == self.sequence.len() {
+                    self.extend_sequence();
+                } else {
+                    self.state = State::Repeating { index: index + 1 };
+                }
+            }
+            State::GameOver(_) => self.restart(),
+        }
+    }
+}
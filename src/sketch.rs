@@ -0,0 +1,85 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    input::{Encoder, InputEvent},
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+
+/// Etch-a-sketch drawing toy.
+///
+/// The X and Y rotary encoders move a blinking cursor which leaves a trail of
+/// lit pixels behind. Pressing any button clears the drawing.
+pub struct Sketch {
+    drawing: FrameBuffer,
+    cursor: Point,
+    cursor_visible: bool,
+}
+
+impl Sketch {
+    pub fn new() -> Self {
+        let mut sketch = Self {
+            drawing: FrameBuffer::new(),
+            cursor: Point::new(WIDTH as i32 / 2, HEIGHT as i32 / 2),
+            cursor_visible: true,
+        };
+
+        sketch.drawing.set_level(sketch.cursor, u8::MAX);
+
+        sketch
+    }
+}
+
+impl Animation for Sketch {
+    fn tick(&mut self) {
+        self.cursor_visible = !self.cursor_visible;
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        frame.overlay(&self.drawing);
+
+        // The cursor blinks between fully lit and off, so it can also be found
+        // on top of the drawing
+        let color = if self.cursor_visible {
+            BinaryColor::On
+        } else {
+            BinaryColor::Off
+        };
+
+        let Ok(()) = Pixel(self.cursor, color).draw(frame);
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let (encoder, detents) = match event {
+            InputEvent::Pressed(_) => {
+                self.drawing.clear_screen();
+
+                self.drawing.set_level(self.cursor, u8::MAX);
+                return;
+            }
+            InputEvent::Turned(encoder, detents) => (encoder, detents),
+            _ => return,
+        };
+
+        let step = match encoder {
+            Encoder::X => Point::new(detents.signum(), 0),
+            Encoder::Y => Point::new(0, detents.signum()),
+            Encoder::Brightness => return,
+        };
+
+        // Move one pixel at a time, so fast turns still draw a solid line
+        for _ in 0..detents.abs() {
+            let next = self.cursor + step;
+
+            if !self.drawing.bounding_box().contains(next) {
+                break;
+            }
+
+            self.cursor = next;
+            self.drawing.set_level(next, u8::MAX);
+        }
+    }
+}
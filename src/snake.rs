@@ -0,0 +1,163 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    input::{Button, InputEvent},
+    text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use rand::seq::IteratorRandom;
+use std::collections::VecDeque;
+
+/// Length of the snake at the start of every game.
+const START_LENGTH: usize = 3;
+
+/// Brightness of the food, dimmer than the snake so the two can be told apart.
+const FOOD_LEVEL: u8 = u8::MAX / 3;
+
+/// The classic Snake game, steered with the direction buttons.
+pub struct Snake {
+    state: State,
+}
+
+enum State {
+    Playing(Game),
+    // The final score is scrolled until any button is pressed
+    GameOver(ScrollingText),
+}
+
+struct Game {
+    // The head of the snake is at the front
+    body: VecDeque<Point>,
+    direction: Point,
+    // Direction chosen by the player, applied on the next move so that two
+    // quick presses can't turn the snake back into itself
+    next_direction: Point,
+    food: Option<Point>,
+}
+
+impl Snake {
+    pub fn new() -> Self {
+        Self {
+            state: State::Playing(Game::new()),
+        }
+    }
+}
+
+impl Game {
+    fn new() -> Self {
+        let y = HEIGHT as i32 / 2;
+        let body = (0..START_LENGTH as i32)
+            .map(|i| Point::new(START_LENGTH as i32 - 1 - i, y))
+            .collect();
+
+        let mut game = Self {
+            body,
+            direction: Point::new(1, 0),
+            next_direction: Point::new(1, 0),
+            food: None,
+        };
+
+        game.place_food();
+
+        game
+    }
+
+    fn score(&self) -> usize {
+        self.body.len() - START_LENGTH
+    }
+
+    /// Puts the food on a random cell not covered by the snake.
+    fn place_food(&mut self) {
+        self.food = (0..WIDTH as i32)
+            .flat_map(|x| (0..HEIGHT as i32).map(move |y| Point::new(x, y)))
+            .filter(|p| !self.body.contains(p))
+            .choose(&mut rand::thread_rng());
+    }
+
+    /// Moves the snake one cell, returning `false` once the game is over,
+    /// either because it crashed or because it covers every cell.
+    fn step(&mut self) -> bool {
+        self.direction = self.next_direction;
+
+        let head = self.body[0] + self.direction;
+
+        let inside = (0..WIDTH as i32).contains(&head.x) && (0..HEIGHT as i32).contains(&head.y);
+
+        if !inside || self.body.contains(&head) {
+            return false;
+        }
+
+        self.body.push_front(head);
+
+        if Some(head) == self.food {
+            self.place_food();
+        } else {
+            self.body.pop_back();
+        }
+
+        // There is nowhere left to put food, so the player has won
+        self.food.is_some()
+    }
+}
+
+impl Animation for Snake {
+    fn tick(&mut self) {
+        match &mut self.state {
+            State::Playing(game) => {
+                if !game.step() {
+                    // Food is only missing once there is no cell left for it
+                    let message = match game.food {
+                        Some(_) => format!("Game over! Score: {}", game.score()),
+                        None => format!("You win! Score: {}", game.score()),
+                    };
+
+                    self.state = State::GameOver(ScrollingText::new(message));
+                }
+            }
+            State::GameOver(text) => text.tick(),
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match &self.state {
+            State::Playing(game) => {
+                let body = game.body.iter().map(|&p| Pixel(p, BinaryColor::On));
+
+                let Ok(()) = frame.draw_iter(body);
+
+                if let Some(food) = game.food {
+                    frame.set_level(food, FOOD_LEVEL);
+                }
+            }
+            State::GameOver(text) => text.draw(frame),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let InputEvent::Pressed(button) = event else {
+            return;
+        };
+
+        match &mut self.state {
+            State::Playing(game) => {
+                let direction = match button {
+                    Button::Up => Point::new(0, -1),
+                    Button::Down => Point::new(0, 1),
+                    Button::Left => Point::new(-1, 0),
+                    Button::Right => Point::new(1, 0),
+                    Button::Select => return,
+                };
+
+                // The snake can't turn around on itself
+                if direction != -game.direction {
+                    game.next_direction = direction;
+                }
+            }
+            State::GameOver(_) => self.state = State::Playing(Game::new()),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    config::SourceConfig,
+    http,
+    json::{Json, JsonPath},
+    log,
+    regex::Regex,
+    template,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::HashMap,
+    io::Read,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How often a command is checked on while waiting for it to finish.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Latest value of every data source which could be fetched, by name.
+#[derive(Clone, Default)]
+pub struct Values(Arc<Mutex<HashMap<String, Json>>>);
+
+impl Values {
+    /// Fills in the placeholders of `template` with the latest values, like
+    /// `{temperature:.1}` with the value of the source named `temperature`.
+    pub fn render(&self, template: &str) -> String {
+        let values = lock(&self.0);
+
+        template::render(template, |name| values.get(name))
+    }
+
+    fn set(&self, name: &str, value: Json) {
+        lock(&self.0).insert(name.to_string(), value);
+    }
+}
+
+/// Locks `mutex`, even if a thread panicked while holding it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Where a source gets its response from.
+enum Origin {
+    Url(String),
+    /// Shell command, whose output is the response, and how long it can take
+    /// before it is killed.
+    Command(String, Duration),
+}
+
+impl Origin {
+    fn new(config: &SourceConfig) -> Result<Self> {
+        match (&config.url, &config.command) {
+            (Some(url), None) => Ok(Origin::Url(url.clone())),
+            // A command taking longer than the interval would hold up the
+            // next run anyway
+            (None, Some(command)) => Ok(Origin::Command(
+                command.clone(),
+                Duration::from_secs(config.interval_secs.max(1)),
+            )),
+            _ => Err(anyhow!(
+                "the data source {} needs either a URL or a command",
+                config.name
+            )),
+        }
+    }
+
+    fn fetch(&self) -> Result<String> {
+        let (command, timeout) = match self {
+            Origin::Url(url) => return http::get(url),
+            Origin::Command(command, timeout) => (command, *timeout),
+        };
+
+        let mut child = Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to run sh")?;
+
+        // The output is read while waiting, so that a command with a lot of
+        // it doesn't get stuck on a full pipe
+        let stdout = read_all(child.stdout.take());
+        let stderr = read_all(child.stderr.take());
+
+        let deadline = Instant::now() + timeout;
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+
+                return Err(anyhow!(
+                    "the command was killed after running for {} seconds",
+                    timeout.as_secs()
+                ));
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let output = |pipe: JoinHandle<Vec<u8>>| {
+            String::from_utf8_lossy(&pipe.join().unwrap_or_default()).into_owned()
+        };
+
+        if !status.success() {
+            return Err(anyhow!(
+                "the command failed with {}: {}",
+                status,
+                output(stderr).trim()
+            ));
+        }
+
+        Ok(output(stdout))
+    }
+}
+
+/// Reads everything out of `pipe` on a thread of its own.
+fn read_all(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut data = Vec::new();
+
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut data);
+        }
+
+        data
+    })
+}
+
+/// How a value is picked out of what a source fetched.
+enum Extractor {
+    /// The first line of the response, trimmed.
+    Body,
+    Path(JsonPath),
+    Regex(Regex),
+}
+
+impl Extractor {
+    fn new(config: &SourceConfig) -> Result<Self> {
+        match (&config.path, &config.regex) {
+            (Some(_), Some(_)) => Err(anyhow!(
+                "the data source {} has both a path and a regex",
+                config.name
+            )),
+            (Some(path), None) => Ok(Extractor::Path(JsonPath::parse(path)?)),
+            (None, Some(regex)) => Ok(Extractor::Regex(Regex::new(regex)?)),
+            (None, None) => Ok(Extractor::Body),
+        }
+    }
+
+    fn extract(&self, body: &str) -> Result<Json> {
+        let text = match self {
+            Extractor::Body => body.trim_start().lines().next().unwrap_or_default().trim(),
+            Extractor::Path(path) => {
+                return path
+                    .select(&Json::parse(body)?)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("no value at the path in the response"))
+            }
+            Extractor::Regex(regex) => regex
+                .find(body)
+                .ok_or_else(|| anyhow!("the regex doesn't match the response"))?,
+        };
+
+        // Numbers are kept as such, so that templates can round them
+        Ok(match text.parse() {
+            Ok(number) => Json::Number(number),
+            Err(_) => Json::String(text.to_string()),
+        })
+    }
+}
+
+/// Starts fetching every data source on a thread of its own, and returns
+/// where their latest values are kept.
+///
+/// Fails right away if a source is misconfigured, like with an invalid path
+/// or regex, rather than on the first fetch.
+pub fn spawn(configs: &[SourceConfig]) -> Result<Values> {
+    let values = Values::default();
+
+    for config in configs {
+        let origin = Origin::new(config)?;
+        let extractor = Extractor::new(config)?;
+        let config = config.clone();
+        let values = values.clone();
+
+        thread::spawn(move || loop {
+            let delay = match origin.fetch().and_then(|body| extractor.extract(&body)) {
+                Ok(value) => {
+                    values.set(&config.name, value);
+
+                    Duration::from_secs(config.interval_secs)
+                }
+                Err(err) => {
+                    log::warn!("Data source {}: {:#}", config.name, err);
+
+                    RETRY_DELAY
+                }
+            };
+
+            thread::sleep(delay);
+        });
+    }
+
+    Ok(values)
+}
@@ -0,0 +1,62 @@
+use crate::{
+    animation::Animation,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use embedded_graphics::prelude::*;
+use rand::Rng;
+
+/// Chance of a new spark appearing on every frame.
+const SPAWN_CHANCE: f64 = 0.6;
+
+/// How much brightness a spark loses on every frame.
+const FADE_PER_TICK: u8 = 64;
+
+/// Random pixels which briefly flash and then fade out.
+///
+/// Sparks only ever brighten the pixels below them, so this can be drawn on
+/// top of other animations as an overlay.
+#[derive(Default)]
+pub struct Sparkle {
+    sparks: Vec<Spark>,
+}
+
+struct Spark {
+    position: Point,
+    level: u8,
+}
+
+impl Sparkle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Animation for Sparkle {
+    fn tick(&mut self) {
+        for spark in &mut self.sparks {
+            spark.level = spark.level.saturating_sub(FADE_PER_TICK);
+        }
+
+        self.sparks.retain(|spark| spark.level > 0);
+
+        let mut rng = rand::thread_rng();
+
+        if rng.gen_bool(SPAWN_CHANCE) {
+            self.sparks.push(Spark {
+                position: Point::new(
+                    rng.gen_range(0..WIDTH as i32),
+                    rng.gen_range(0..HEIGHT as i32),
+                ),
+                level: u8::MAX,
+            });
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        for spark in &self.sparks {
+            let level = frame.level(spark.position).max(spark.level);
+
+            frame.set_level(spark.position, level);
+        }
+    }
+}
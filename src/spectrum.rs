@@ -0,0 +1,119 @@
+use crate::{
+    animation::Animation,
+    audio::{self, Capture, BLOCK_SAMPLES},
+    config::AudioConfig,
+    framebuffer::{FrameBuffer, WIDTH},
+    widget::BarGraph,
+};
+use anyhow::Result;
+use embedded_graphics::prelude::*;
+use std::{ops::Range, time::Instant};
+
+/// Frequencies covered by the bands, in hertz. Everything above the highest
+/// one is mostly hiss for a small microphone.
+const LOWEST_FREQUENCY: f32 = 60.0;
+const HIGHEST_FREQUENCY: f32 = 8000.0;
+
+/// Decibels between a band filling its column and being empty.
+const RANGE_DB: f32 = 40.0;
+
+/// Quietest level, in dBFS, the gain is turned up to, so that silence isn't
+/// turned up to noise filling the columns.
+const QUIETEST_DB: f32 = -50.0;
+
+/// How fast the gain comes back up once the sound gets quieter, in decibels
+/// per second. It goes down right away.
+const GAIN_RECOVERY: f32 = 6.0;
+
+/// How fast the bars fall back, in heights per second. They rise right away.
+const FALL_RATE: f32 = 3.0;
+
+/// Spectrum analyzer, showing how loud a microphone hears every frequency.
+///
+/// Every column is a band of frequencies, the lowest on the left, with bands
+/// getting wider towards the highest ones like octaves do. The gain follows
+/// the loudest band, so that quiet music fills the columns as well as loud
+/// music does.
+pub struct Spectrum {
+    capture: Capture,
+    // Frequencies of every band, as the bins of the spectrum
+    bands: Vec<Range<usize>>,
+    // Heights of the bars, from 0 to 1
+    levels: Vec<f32>,
+    // Level of the loudest band recently, in dBFS, which fills a column
+    reference_db: f32,
+    last_tick: Instant,
+}
+
+impl Spectrum {
+    pub fn new(config: &AudioConfig) -> Result<Self> {
+        let bin_width = config.sample_rate as f32 / BLOCK_SAMPLES as f32;
+        let highest = HIGHEST_FREQUENCY.min(config.sample_rate as f32 / 2.0);
+
+        // Edges spaced evenly on a logarithmic scale, with at least a bin
+        // in every band
+        let edge = |band: usize| {
+            let frequency =
+                LOWEST_FREQUENCY * (highest / LOWEST_FREQUENCY).powf(band as f32 / WIDTH as f32);
+
+            ((frequency / bin_width).round() as usize).clamp(1, BLOCK_SAMPLES / 2)
+        };
+
+        let bands = (0..WIDTH)
+            .map(|band| edge(band)..edge(band + 1).max(edge(band) + 1))
+            .collect();
+
+        Ok(Self {
+            capture: Capture::new(config)?,
+            bands,
+            levels: vec![0.0; WIDTH],
+            reference_db: QUIETEST_DB,
+            last_tick: Instant::now(),
+        })
+    }
+}
+
+impl Animation for Spectrum {
+    fn tick(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f32();
+        self.last_tick = Instant::now();
+
+        // Loudest amplitude of every band in the blocks since the last frame
+        let mut loudest = [0.0; WIDTH];
+
+        for block in self.capture.blocks() {
+            let spectrum = audio::spectrum(&block);
+
+            for (loudest, band) in loudest.iter_mut().zip(&self.bands) {
+                let amplitude = spectrum
+                    .get(band.clone())
+                    .map_or(0.0, |bins| bins.iter().copied().fold(0.0, f32::max));
+
+                *loudest = amplitude.max(*loudest);
+            }
+        }
+
+        let db: Vec<f32> = loudest
+            .iter()
+            .map(|amplitude| 20.0 * amplitude.max(f32::MIN_POSITIVE).log10())
+            .collect();
+
+        let loudest_db = db.iter().copied().fold(f32::MIN, f32::max);
+
+        self.reference_db = loudest_db
+            .max(self.reference_db - GAIN_RECOVERY * elapsed)
+            .max(QUIETEST_DB);
+
+        for (level, db) in self.levels.iter_mut().zip(db) {
+            let height = ((db - self.reference_db + RANGE_DB) / RANGE_DB).clamp(0.0, 1.0);
+
+            *level = height.max(*level - FALL_RATE * elapsed);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Ok(()) = BarGraph::new(self.levels.iter().copied())
+            .with_max(1.0)
+            .draw(frame);
+    }
+}
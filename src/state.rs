@@ -0,0 +1,20 @@
+use crate::mode::Mode;
+use std::sync::Arc;
+
+/// What the screen is doing, saved after every refresh so that a restarted
+/// drawing thread can pick up where the last one left off, and so that the
+/// HTTP server can tell.
+#[derive(Clone)]
+pub struct ScreenState {
+    pub mode: Mode,
+    /// Messages of the text mode.
+    pub messages: Arc<[String]>,
+    /// Announcement being scrolled, if any.
+    pub announcement: Option<String>,
+    /// Announcements waiting to be scrolled.
+    pub queued: Vec<String>,
+    pub powered: bool,
+    pub paused: bool,
+    pub inverted: bool,
+    pub brightness: u8,
+}
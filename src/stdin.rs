@@ -0,0 +1,33 @@
+use crate::{
+    command::{DisplayCommand, DisplayHandle},
+    log,
+};
+use std::{
+    io::{self, BufRead},
+    thread,
+};
+
+/// Starts reading standard input on a new thread, replacing the messages
+/// with every line which isn't blank, so that other programs can pipe what
+/// the display shows into it.
+pub fn spawn(display: DisplayHandle) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    log::warn!("Failed to read standard input: {}", err);
+                    return;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if display.send(DisplayCommand::SetText(line)).is_err() {
+                return;
+            }
+        }
+    });
+}
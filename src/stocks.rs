@@ -0,0 +1,110 @@
+use crate::{
+    config::{StockProvider, StocksConfig},
+    http,
+    json::Json,
+    ticker::{Segment, Ticker},
+};
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+/// Price of a stock.
+#[derive(Clone, Copy, Debug)]
+pub struct Quote {
+    pub price: f64,
+    /// Price at the close of the previous trading day.
+    pub previous_close: f64,
+}
+
+/// A web API which stock quotes can be fetched from.
+pub trait QuoteProvider: Send {
+    fn fetch(&self, symbol: &str) -> Result<Quote>;
+}
+
+/// Yahoo Finance's chart API, which needs no API key.
+pub struct Yahoo;
+
+impl QuoteProvider for Yahoo {
+    fn fetch(&self, symbol: &str) -> Result<Quote> {
+        let url = format!("https://query1.finance.yahoo.com/v8/finance/chart/{symbol}");
+        let response = Json::parse(&http::get(&url)?)?;
+
+        let meta = response
+            .get("chart")
+            .and_then(|chart| chart.get("result"))
+            .and_then(|result| result.index(0))
+            .and_then(|result| result.get("meta"))
+            .ok_or_else(|| anyhow!("no quote for {} in the answer of Yahoo", symbol))?;
+
+        let field = |name| {
+            meta.get(name)
+                .and_then(Json::as_f64)
+                .ok_or_else(|| anyhow!("no {} for {} in the answer of Yahoo", name, symbol))
+        };
+
+        Ok(Quote {
+            price: field("regularMarketPrice")?,
+            previous_close: field("chartPreviousClose")?,
+        })
+    }
+}
+
+/// Finnhub's quote API, which needs a free API key.
+pub struct Finnhub {
+    pub api_key: String,
+}
+
+impl QuoteProvider for Finnhub {
+    fn fetch(&self, symbol: &str) -> Result<Quote> {
+        let url = format!("https://finnhub.io/api/v1/quote?symbol={symbol}");
+        let response = Json::parse(&http::get_with_headers(
+            &url,
+            &[("X-Finnhub-Token", &self.api_key)],
+        )?)?;
+
+        let field = |name| {
+            response
+                .get(name)
+                .and_then(Json::as_f64)
+                .ok_or_else(|| anyhow!("no {} for {} in the answer of Finnhub", name, symbol))
+        };
+
+        Ok(Quote {
+            price: field("c")?,
+            previous_close: field("pc")?,
+        })
+    }
+}
+
+/// Creates the provider selected by `config`.
+pub fn provider(config: &StockProvider) -> Box<dyn QuoteProvider> {
+    match config {
+        StockProvider::Yahoo => Box::new(Yahoo),
+        StockProvider::Finnhub { api_key } => Box::new(Finnhub {
+            api_key: api_key.clone(),
+        }),
+    }
+}
+
+/// Creates a ticker scrolling the price of every configured stock, with a
+/// triangle showing whether it went up or down since the previous close.
+pub fn ticker(config: StocksConfig) -> Ticker {
+    let provider = provider(&config.provider);
+
+    Ticker::new(
+        "Stocks",
+        Duration::from_secs(config.interval_mins * 60),
+        move || {
+            let mut segments = Vec::new();
+
+            for symbol in &config.symbols {
+                let quote = provider.fetch(symbol)?;
+
+                segments.push(Segment::Text(format!("{symbol} ")));
+                segments.push(Segment::change(quote.price - quote.previous_close));
+                segments.push(Segment::Text(format!(" {:.2}  ", quote.price)));
+            }
+
+            Ok(segments)
+        },
+    )
+}
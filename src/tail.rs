@@ -0,0 +1,137 @@
+use crate::{
+    animation::Animation, config::TailConfig, framebuffer::FrameBuffer, log, text::ScrollingText,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Most lines waiting to be scrolled.
+const MAX_QUEUED: usize = 3;
+
+/// Scrolls the new lines of a file as they are written, like `tail -f`,
+/// each once, keeping the last one scrolling until the next one comes.
+///
+/// This runs the `tail` command, which also follows the file when it is
+/// rotated. It is killed when the mode is dropped.
+pub struct Tail {
+    child: Child,
+    lines: Receiver<String>,
+    text: ScrollingText,
+    // Frames until the line was scrolled all the way through
+    frames_left: u32,
+}
+
+impl Tail {
+    pub fn new(config: TailConfig) -> Result<Self> {
+        // `tail` waits for files which can't be read, rather than failing
+        File::open(&config.file)
+            .with_context(|| format!("failed to open {}", config.file.display()))?;
+
+        let mut child = Command::new("tail")
+            .args(["-F", "-n", "0"])
+            .arg(&config.file)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run tail")?;
+
+        let Some(stdout) = child.stdout.take() else {
+            return Err(anyhow!("the output of tail isn't piped"));
+        };
+
+        let (tx, rx) = sync_channel(MAX_QUEUED);
+
+        thread::spawn(move || {
+            // This also happens whenever the mode is dropped
+            if let Err(err) = read_lines(&config, stdout, tx) {
+                log::debug!("Tail: {:#}", err);
+            }
+        });
+
+        Ok(Self {
+            child,
+            lines: rx,
+            text: ScrollingText::new(""),
+            frames_left: 0,
+        })
+    }
+}
+
+impl Drop for Tail {
+    fn drop(&mut self) {
+        // Killing the command also ends the thread reading from it
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Sends the lines printed by `tail` which are wanted, as often as allowed,
+/// until either it or the mode goes away.
+fn read_lines(config: &TailConfig, stdout: ChildStdout, lines: SyncSender<String>) -> Result<()> {
+    let keywords: Vec<String> = config
+        .keywords
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect();
+    let spacing = Duration::from_secs(60) / config.max_lines_per_minute.max(1);
+
+    let mut stdout = BufReader::new(stdout);
+    let mut bytes = Vec::new();
+    let mut last_sent: Option<Instant> = None;
+
+    loop {
+        bytes.clear();
+
+        if stdout.read_until(b'\n', &mut bytes)? == 0 {
+            return Err(anyhow!("tail stopped"));
+        }
+
+        // Logs aren't always valid UTF-8
+        let line = String::from_utf8_lossy(&bytes).trim().to_string();
+
+        let wanted = !line.is_empty()
+            && (keywords.is_empty() || {
+                let lowercase = line.to_lowercase();
+
+                keywords.iter().any(|word| lowercase.contains(word))
+            });
+
+        if !wanted || last_sent.is_some_and(|sent| sent.elapsed() < spacing) {
+            continue;
+        }
+
+        match lines.try_send(line) {
+            Ok(()) => last_sent = Some(Instant::now()),
+            Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Disconnected(_)) => return Ok(()),
+        }
+    }
+}
+
+impl Animation for Tail {
+    fn tick(&mut self) {
+        self.text.tick();
+        self.frames_left = self.frames_left.saturating_sub(1);
+
+        if self.frames_left > 0 {
+            return;
+        }
+
+        if let Ok(line) = self.lines.try_recv() {
+            self.text = ScrollingText::new(line);
+        }
+
+        self.frames_left = self.text.width();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        self.text.draw(frame);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    animation::Animation,
+    config::TallyConfig,
+    framebuffer::FrameBuffer,
+    input::{Button, Encoder, InputEvent},
+    log,
+    widget::show_number,
+};
+use anyhow::{Context, Result};
+use embedded_graphics::prelude::*;
+use std::{fs, io, path::Path};
+
+/// A count kept across restarts, like a scoreboard or a visitor counter.
+///
+/// The up button counts one more and the down button one less, and so does
+/// turning the horizontal encoder. Holding the down button resets the count
+/// to zero. The count is saved to a file every time it changes.
+pub struct Tally {
+    config: TallyConfig,
+    count: i64,
+    // Frames since the count changed, for the numbers which are animated
+    frame_count: u32,
+}
+
+impl Tally {
+    pub fn new(config: TallyConfig) -> Result<Self> {
+        let count = load(&config.path)?;
+
+        Ok(Self {
+            config,
+            count,
+            frame_count: 0,
+        })
+    }
+
+    fn set(&mut self, count: i64) {
+        if count == self.count {
+            return;
+        }
+
+        self.count = count;
+        self.frame_count = 0;
+
+        if let Err(err) = save(&self.config.path, count) {
+            log::warn!("{:#}", err);
+        }
+    }
+}
+
+/// Reads the saved count, or returns zero if it was never saved.
+fn load(path: &Path) -> Result<i64> {
+    match fs::read_to_string(path) {
+        Ok(contents) => contents
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid count in {}", path.display())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn save(path: &Path, count: i64) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+
+    // The file is replaced at once, so that it's never left half written
+    let temporary = path.with_extension("tmp");
+
+    fs::write(&temporary, format!("{}\n", count))
+        .and_then(|()| fs::rename(&temporary, path))
+        .with_context(|| format!("failed to save the count to {}", path.display()))
+}
+
+impl Animation for Tally {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Ok(()) = show_number(self.count)
+            .at_frame(self.frame_count)
+            .draw(frame);
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::Pressed(Button::Up) => self.set(self.count.saturating_add(1)),
+            InputEvent::Pressed(Button::Down) => self.set(self.count.saturating_sub(1)),
+            // Pressing it already counted one less, which doesn't matter
+            InputEvent::LongPressed(Button::Down) => self.set(0),
+            InputEvent::Turned(Encoder::X, detents) => {
+                self.set(self.count.saturating_add(detents as i64))
+            }
+            _ => {}
+        }
+    }
+}
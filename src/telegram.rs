@@ -0,0 +1,132 @@
+use crate::{
+    action::Action,
+    command::{DisplayCommand, DisplayHandle},
+    config::TelegramConfig,
+    http,
+    json::Json,
+    log::{self, Level, Span},
+    mode::Mode,
+};
+use anyhow::{anyhow, Result};
+use std::{thread, time::Duration};
+
+/// Seconds Telegram holds on to a request for updates while there are none,
+/// which has to stay below the timeout of our HTTP requests.
+const POLL_TIMEOUT_SECS: u32 = 10;
+
+/// How long to wait before polling again after a failed request.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Starts polling a Telegram bot for messages on a new thread.
+///
+/// Commands like `/brightness 50` are turned into actions for the display,
+/// and every other message is announced on it.
+///
+/// The bot is long polled, so nothing has to be reachable from the internet.
+pub fn spawn(config: TelegramConfig, display: DisplayHandle) {
+    thread::spawn(move || {
+        // Id of the next update, so that every message is only handled once
+        let mut offset = 0;
+
+        loop {
+            if let Err(err) = poll(&config, &mut offset, &display) {
+                log::warn!("Telegram: {:#}", err);
+
+                thread::sleep(RETRY_DELAY);
+            }
+        }
+    });
+}
+
+/// Waits for new messages to the bot, and handles them.
+fn poll(config: &TelegramConfig, offset: &mut i64, display: &DisplayHandle) -> Result<()> {
+    let url = format!(
+        "https://api.telegram.org/bot{}/getUpdates?offset={}&timeout={}",
+        config.token, offset, POLL_TIMEOUT_SECS
+    );
+
+    let response = Json::parse(&http::get(&url)?)?;
+
+    if response.get("ok").and_then(Json::as_bool) != Some(true) {
+        return Err(anyhow!("unexpected answer from Telegram"));
+    }
+
+    let updates = response
+        .get("result")
+        .and_then(Json::as_array)
+        .unwrap_or_default();
+
+    for update in updates {
+        if let Some(id) = update.get("update_id").and_then(Json::as_f64) {
+            *offset = (*offset).max(id as i64 + 1);
+        }
+
+        let Some(message) = update.get("message") else {
+            continue;
+        };
+
+        let chat = message
+            .get("chat")
+            .and_then(|chat| chat.get("id"))
+            .and_then(Json::as_f64)
+            .map(|id| id as i64);
+
+        let Some(text) = message.get("text").and_then(Json::as_str) else {
+            continue;
+        };
+
+        if !config.allowed_chats.is_empty()
+            && !chat.is_some_and(|chat| config.allowed_chats.contains(&chat))
+        {
+            log::warn!("Telegram: ignoring a message from chat {:?}", chat);
+            continue;
+        }
+
+        let _span = Span::new(Level::Debug, "Telegram message");
+
+        log::debug!("Telegram: message from chat {:?}: {}", chat, text);
+
+        match text.strip_prefix('/') {
+            Some(command) => match parse_command(command) {
+                Some(action) => display.send(DisplayCommand::Action(action))?,
+                None => log::warn!("Telegram: unknown command /{}", command),
+            },
+            None => display.send(DisplayCommand::Announce(text.to_string()))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns a bot command, without its leading slash, into an action.
+fn parse_command(command: &str) -> Option<Action> {
+    let mut words = command.split_whitespace();
+
+    // Commands in groups can be addressed to a bot, like `/power@MyBot`
+    let name = words.next()?.split('@').next()?;
+
+    match name {
+        "power" => match words.next() {
+            Some("on") => Some(Action::PowerOn),
+            Some("off") => Some(Action::PowerOff),
+            Some(_) => None,
+            None => Some(Action::TogglePower),
+        },
+        "pause" => Some(Action::Pause),
+        "resume" => Some(Action::Resume),
+        "mode" => match words.next() {
+            Some(mode) => Some(Action::SwitchMode(Mode::from_name(mode)?)),
+            None => Some(Action::NextMode),
+        },
+        "message" => Some(Action::NextMessage),
+        "invert" => Some(Action::ToggleInvert),
+        "brightness" => {
+            let percent: u32 = words.next()?.trim_end_matches('%').parse().ok()?;
+
+            Some(Action::SetBrightness(
+                (percent.min(100) * u8::MAX as u32 / 100) as u8,
+            ))
+        }
+        _ => None,
+    }
+}
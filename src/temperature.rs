@@ -0,0 +1,101 @@
+use crate::{
+    animation::Animation,
+    config::TemperatureConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    icons::{self, WARNING},
+    interval::Interval,
+    widget::{show_number, Sparkline},
+};
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle},
+};
+use std::{fs, time::Duration};
+
+/// How often the temperature is read.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Temperature of the SoC, in millidegrees Celsius.
+const SYSFS_PATH: &str = "/sys/class/thermal/thermal_zone0/temp";
+
+/// Temperature at which the bar is full, in degrees Celsius.
+const BAR_MAX_CELSIUS: f32 = 100.0;
+
+/// Number of frames the temperature and its history are each shown for
+/// before switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// Shows the temperature of the Pi's SoC.
+///
+/// The temperature is shown in degrees Celsius, with a bar along the bottom
+/// row, alternating with a sparkline of the recent readings. Above the
+/// configured overheat threshold it flashes between the temperature and a
+/// warning sign.
+pub struct Temperature {
+    config: TemperatureConfig,
+    history: Sparkline,
+    interval: Interval,
+    frame_count: u32,
+}
+
+impl Temperature {
+    pub fn new(config: TemperatureConfig) -> Self {
+        Self {
+            config,
+            history: Sparkline::new(),
+            interval: Interval::new(SAMPLE_INTERVAL),
+            frame_count: 0,
+        }
+    }
+}
+
+fn read_celsius() -> Result<f32> {
+    let millidegrees: f32 = fs::read_to_string(SYSFS_PATH)?.trim().parse()?;
+
+    Ok(millidegrees / 1000.0)
+}
+
+impl Animation for Temperature {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if self.interval.is_due() {
+            // Keep showing the previous reading if it can't be read
+            if let Ok(celsius) = read_celsius() {
+                self.history.push(celsius);
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(celsius) = self.history.latest() else {
+            return;
+        };
+
+        if celsius >= self.config.overheat_celsius && self.frame_count % 2 == 1 {
+            icons::draw_icon(&WARNING, Point::zero(), frame);
+            return;
+        }
+
+        if !(self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            let Ok(()) = self.history.draw(frame);
+            return;
+        }
+
+        let Ok(()) = show_number(celsius.round() as i64)
+            .at_frame(self.frame_count % VIEW_FRAMES)
+            .draw(frame);
+
+        let length = (celsius / BAR_MAX_CELSIUS * WIDTH as f32).round() as i32;
+
+        if length > 0 {
+            let start = Point::new(0, HEIGHT as i32 - 1);
+
+            let Ok(()) = Line::new(start, start + Point::new(length.min(WIDTH as i32) - 1, 0))
+                .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::json::Json;
+
+/// Fills in the placeholders of `template` with the values `value` returns
+/// for their names.
+///
+/// A placeholder is a name in braces, like `{temperature}`, or `{}` for the
+/// value with an empty name. `{temperature:.1}` shows a number with a
+/// decimal. Values which are missing are shown as `?`.
+pub fn render<'a>(template: &str, value: impl Fn(&str) -> Option<&'a Json>) -> String {
+    let mut line = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        let (name, precision) = match rest[1..end].split_once(':') {
+            Some((name, format)) => (
+                name,
+                format
+                    .strip_prefix('.')
+                    .and_then(|decimals| decimals.parse::<usize>().ok()),
+            ),
+            None => (&rest[1..end], None),
+        };
+
+        match (value(name.trim()), precision) {
+            (Some(Json::Number(number)), Some(decimals)) => {
+                line.push_str(&format!("{:.*}", decimals, number));
+            }
+            (Some(Json::String(string)), _) => line.push_str(string),
+            (Some(value), _) => line.push_str(&value.to_string()),
+            (None, _) => line.push('?'),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    line.push_str(rest);
+    line
+}
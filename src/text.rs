@@ -0,0 +1,191 @@
+use crate::{action::Action, animation::Animation, framebuffer::FrameBuffer};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_5X8, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::Text,
+};
+use serde::Deserialize;
+use std::collections::VecDeque;
+
+/// Text which scrolls from right to left, wrapping around when it reaches
+/// the end.
+pub struct ScrollingText {
+    text: String,
+    style: MonoTextStyle<'static, BinaryColor>,
+    // Used to calculate the transition for the animation
+    offset_x: u32,
+}
+
+impl ScrollingText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            style: MonoTextStyle::new(&FONT_5X8, BinaryColor::On),
+            offset_x: 0,
+        }
+    }
+
+    /// Returns the width of the text in pixels, which is also the number of
+    /// frames it takes to scroll all the way through it.
+    pub fn width(&self) -> u32 {
+        self.text().bounding_box().size.width
+    }
+
+    fn text(&self) -> Text<'_, MonoTextStyle<'static, BinaryColor>> {
+        Text::new(&self.text, Point::new(0, 7), self.style)
+    }
+}
+
+impl Animation for ScrollingText {
+    fn tick(&mut self) {
+        let width = self.width().max(1);
+
+        self.offset_x = (self.offset_x + 1) % width;
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Ok(_) = self
+            .text()
+            .translate(Point::new(-(self.offset_x as i32), 0))
+            .draw(frame);
+    }
+}
+
+/// A list of messages, shown one at a time as scrolling text.
+pub struct Messages {
+    messages: Vec<String>,
+    current: usize,
+    text: ScrollingText,
+}
+
+impl Messages {
+    /// Creates the animation, starting with the first of `messages`, which
+    /// can't be empty.
+    pub fn new(messages: Vec<String>) -> Self {
+        let text = ScrollingText::new(messages[0].clone());
+
+        Self {
+            messages,
+            current: 0,
+            text,
+        }
+    }
+}
+
+impl Animation for Messages {
+    fn tick(&mut self) {
+        self.text.tick();
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        self.text.draw(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        if action == Action::NextMessage {
+            self.current = (self.current + 1) % self.messages.len();
+            self.text = ScrollingText::new(self.messages[self.current].clone());
+        }
+    }
+
+    fn set_messages(&mut self, messages: &[String]) {
+        self.messages = messages.to_vec();
+        self.current = 0;
+        self.text = ScrollingText::new(messages[0].clone());
+    }
+}
+
+/// How urgent an announcement is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Priority {
+    /// Dropped if other announcements are being scrolled.
+    Low,
+    /// Scrolled after the announcements before it.
+    #[default]
+    Normal,
+    /// Scrolled before the announcements which aren't high priority.
+    High,
+}
+
+/// Messages which are each scrolled once on top of everything else, one after
+/// the other, like announcements.
+#[derive(Default)]
+pub struct Announcements {
+    queue: VecDeque<String>,
+    // Number of high priority messages at the front of the queue
+    urgent: usize,
+    // Message being scrolled, and for how many more frames
+    current: Option<(ScrollingText, u32)>,
+}
+
+impl Announcements {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `message` to the ones waiting to be scrolled, unless it is
+    /// blank.
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.push_with_priority(message, Priority::Normal);
+    }
+
+    /// Adds `message` to the ones waiting to be scrolled, where its
+    /// `priority` puts it, unless it is blank.
+    pub fn push_with_priority(&mut self, message: impl Into<String>, priority: Priority) {
+        let message = message.into();
+
+        if message.trim().is_empty() {
+            return;
+        }
+
+        match priority {
+            Priority::Low if self.current.is_some() || !self.queue.is_empty() => {}
+            Priority::Low | Priority::Normal => self.queue.push_back(message),
+            Priority::High => {
+                self.queue.insert(self.urgent, message);
+                self.urgent += 1;
+            }
+        }
+    }
+
+    /// Returns the message being scrolled, if any.
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|(text, _)| text.text.as_str())
+    }
+
+    /// Returns the messages waiting to be scrolled, in order.
+    pub fn queued(&self) -> impl Iterator<Item = &str> {
+        self.queue.iter().map(String::as_str)
+    }
+}
+
+impl Animation for Announcements {
+    fn tick(&mut self) {
+        if let Some((text, frames)) = &mut self.current {
+            text.tick();
+            *frames -= 1;
+
+            if *frames > 0 {
+                return;
+            }
+        }
+
+        self.urgent = self.urgent.saturating_sub(1);
+        self.current = self.queue.pop_front().map(|message| {
+            let text = ScrollingText::new(message);
+            let frames = text.width();
+
+            (text, frames)
+        });
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if let Some((text, _)) = &self.current {
+            frame.clear_screen();
+
+            text.draw(frame);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    icons::{self, WARNING},
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use std::{
+    fs,
+    process::Command,
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// How often the throttling state is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Throttling state exposed by the firmware driver.
+const SYSFS_PATH: &str = "/sys/devices/platform/soc/soc:firmware/get_throttled";
+
+/// Bits of the throttling state meaning something is wrong right now:
+/// under-voltage, frequency capped, throttled and soft temperature limit.
+const CURRENT_PROBLEMS: u32 = 0xf;
+
+/// The warning is shown for this many frames out of every `FLASH_PERIOD`.
+const FLASH_FRAMES: u32 = 2;
+const FLASH_PERIOD: u32 = 10;
+
+/// Overlay which flashes a warning sign while the Pi reports under-voltage or
+/// throttling.
+///
+/// It does nothing on machines where the throttling state can't be read.
+pub struct ThrottleWarning {
+    problems: Receiver<bool>,
+    has_problem: bool,
+    frame_count: u32,
+}
+
+impl ThrottleWarning {
+    pub fn new() -> Self {
+        let (tx, rx) = channel();
+
+        // Running `vcgencmd` takes a while, which shouldn't hold up drawing
+        thread::spawn(move || {
+            while let Ok(state) = read_throttled() {
+                if tx.send(state & CURRENT_PROBLEMS != 0).is_err() {
+                    return;
+                }
+
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        Self {
+            problems: rx,
+            has_problem: false,
+            frame_count: 0,
+        }
+    }
+}
+
+/// Reads the throttling state bit field, from sysfs if possible and from
+/// `vcgencmd` otherwise.
+fn read_throttled() -> Result<u32> {
+    // sysfs gives a bare hex number, while `vcgencmd` gives `throttled=0x50000`
+    let output = match fs::read_to_string(SYSFS_PATH) {
+        Ok(state) => state,
+        Err(_) => String::from_utf8(
+            Command::new("vcgencmd")
+                .arg("get_throttled")
+                .output()?
+                .stdout,
+        )?,
+    };
+
+    let state = output.trim();
+    let state = state.strip_prefix("throttled=").unwrap_or(state);
+    let state = state.strip_prefix("0x").unwrap_or(state);
+
+    u32::from_str_radix(state, 16).map_err(|_| anyhow!("unexpected throttling state {:?}", output))
+}
+
+impl Animation for ThrottleWarning {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if let Some(has_problem) = self.problems.try_iter().last() {
+            self.has_problem = has_problem;
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        if self.has_problem && self.frame_count % FLASH_PERIOD < FLASH_FRAMES {
+            frame.clear_screen();
+
+            icons::draw_icon(&WARNING, Point::zero(), frame);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Creates a channel for frame ticks.
+///
+/// Unlike an unbounded channel, ticks never queue up behind a slow frame:
+/// ticks sent while an earlier one is still waiting are merged into it, and
+/// the receiver is told how many were.
+pub fn channel() -> (TickSender, TickReceiver) {
+    let pending = Arc::new(AtomicU64::new(0));
+
+    (TickSender(pending.clone()), TickReceiver(pending))
+}
+
+pub struct TickSender(Arc<AtomicU64>);
+
+impl TickSender {
+    pub fn send(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub struct TickReceiver(Arc<AtomicU64>);
+
+impl TickReceiver {
+    /// Takes the waiting tick, if there is one, and returns how many ticks
+    /// were sent since the last one was taken. All but one of them are
+    /// dropped.
+    pub fn try_recv(&self) -> Option<u64> {
+        match self.0.swap(0, Ordering::Relaxed) {
+            0 => None,
+            ticks => Some(ticks),
+        }
+    }
+}
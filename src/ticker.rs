@@ -0,0 +1,155 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    icons::{self, TRIANGLE_DOWN, TRIANGLE_UP},
+    log,
+};
+use anyhow::Result;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_5X8, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::Text,
+};
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Piece of a ticker line.
+#[derive(Clone, Debug)]
+pub enum Segment {
+    Text(String),
+    /// Triangle showing that a value went up.
+    Up,
+    /// Triangle showing that a value went down.
+    Down,
+}
+
+impl Segment {
+    /// Returns the triangle showing whether `change` is an increase or a
+    /// decrease, or a dash if there was no change.
+    pub fn change(change: f64) -> Self {
+        if change > 0.0 {
+            Segment::Up
+        } else if change < 0.0 {
+            Segment::Down
+        } else {
+            Segment::Text("-".to_string())
+        }
+    }
+
+    fn text(text: &str) -> Text<'_, MonoTextStyle<'static, BinaryColor>> {
+        Text::new(
+            text,
+            Point::new(0, 7),
+            MonoTextStyle::new(&FONT_5X8, BinaryColor::On),
+        )
+    }
+
+    fn width(&self) -> u32 {
+        match self {
+            Segment::Text(text) => Self::text(text).bounding_box().size.width,
+            Segment::Up => TRIANGLE_UP.size().width,
+            Segment::Down => TRIANGLE_DOWN.size().width,
+        }
+    }
+
+    /// Draws the segment with its left edge at `x`.
+    fn draw(&self, x: i32, frame: &mut FrameBuffer) {
+        match self {
+            Segment::Text(text) => {
+                let Ok(_) = Self::text(text).translate(Point::new(x, 0)).draw(frame);
+            }
+            Segment::Up => icons::draw_icon(&TRIANGLE_UP, Point::new(x, 0), frame),
+            Segment::Down => icons::draw_icon(&TRIANGLE_DOWN, Point::new(x, 0), frame),
+        }
+    }
+}
+
+/// A line of text and triangles which is fetched periodically and scrolled
+/// over and over again, like a stock ticker.
+///
+/// The line only changes in between two scrolls, so that an update doesn't
+/// make it jump. While offline, the last line which could be fetched keeps
+/// being shown.
+pub struct Ticker {
+    // Every new line, with nothing when there isn't one
+    updates: Receiver<Option<Vec<Segment>>>,
+    segments: Vec<Segment>,
+    // Used to calculate the transition for the animation
+    offset_x: u32,
+}
+
+impl Ticker {
+    /// Creates the ticker, calling `fetch` every `interval` on a new thread
+    /// to get its line. Errors are logged along with `name`.
+    pub fn new<F>(name: &'static str, interval: Duration, fetch: F) -> Self
+    where
+        F: Fn() -> Result<Vec<Segment>> + Send + 'static,
+    {
+        let (tx, rx) = channel();
+
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch() {
+                Ok(segments) => (Some(segments), interval),
+                Err(err) => {
+                    log::warn!("{}: {:#}", name, err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self::with_updates(rx)
+    }
+
+    /// Creates the ticker, getting its line from `updates` whenever someone
+    /// else has a new one, rather than fetching it. `None` leaves the line as
+    /// it is, which lets the sender find out whether the ticker is still
+    /// around without changing it.
+    pub fn with_updates(updates: Receiver<Option<Vec<Segment>>>) -> Self {
+        Self {
+            updates,
+            segments: Vec::new(),
+            offset_x: 0,
+        }
+    }
+
+    fn width(&self) -> u32 {
+        self.segments.iter().map(Segment::width).sum()
+    }
+}
+
+impl Animation for Ticker {
+    fn tick(&mut self) {
+        self.offset_x = (self.offset_x + 1) % self.width().max(1);
+
+        if self.offset_x == 0 {
+            if let Some(segments) = self.updates.try_iter().flatten().last() {
+                self.segments = segments;
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let mut x = -(self.offset_x as i32);
+
+        for segment in &self.segments {
+            segment.draw(x, frame);
+            x += segment.width() as i32;
+        }
+    }
+}
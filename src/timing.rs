@@ -0,0 +1,79 @@
+use anyhow::Result;
+use std::{
+    fs::File,
+    hint,
+    io::{self, Read},
+    os::fd::{FromRawFd, OwnedFd},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How much earlier than asked [`sleep`] wakes up from sleeping, to busy wait
+/// the rest. Waking up on Linux commonly takes tens of microseconds longer
+/// than asked for, so waits shorter than this are busy waited entirely.
+const SPIN_THRESHOLD: Duration = Duration::from_micros(100);
+
+/// Waits for `duration` precisely, by sleeping for most of it and busy
+/// waiting for the rest.
+///
+/// `thread::sleep` alone can oversleep a wait of a few microseconds many
+/// times over, which makes the brightness of the LEDs wander.
+pub fn sleep(duration: Duration) {
+    let deadline = Instant::now() + duration;
+
+    if duration > SPIN_THRESHOLD {
+        thread::sleep(duration - SPIN_THRESHOLD);
+    }
+
+    while Instant::now() < deadline {
+        hint::spin_loop();
+    }
+}
+
+/// A periodic timer, which fires at a fixed rate no matter how late it is
+/// waited for, unlike sleeping for the period in a loop which drifts by
+/// however long the rest of the loop takes.
+pub struct Timer {
+    file: File,
+}
+
+impl Timer {
+    pub fn new(period: Duration) -> Result<Self> {
+        // SAFETY: plain system call, whose result is checked
+        let fd = unsafe { libc::timerfd_create(libc::CLOCK_MONOTONIC, libc::TFD_CLOEXEC) };
+
+        if fd == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        // SAFETY: `fd` was just created and isn't owned by anything else
+        let file = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+
+        let interval = libc::timespec {
+            tv_sec: period.as_secs() as libc::time_t,
+            tv_nsec: period.subsec_nanos() as libc::c_long,
+        };
+        let spec = libc::itimerspec {
+            it_interval: interval,
+            it_value: interval,
+        };
+
+        // SAFETY: `fd` is a timer and `spec` is a valid, initialized struct
+        if unsafe { libc::timerfd_settime(fd, 0, &spec, std::ptr::null_mut()) } == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(Self { file })
+    }
+
+    /// Waits until the timer fires, and returns how many periods went by
+    /// since it was last waited for, which is more than one if it was waited
+    /// for late.
+    pub fn wait(&mut self) -> Result<u64> {
+        let mut expirations = [0; 8];
+
+        self.file.read_exact(&mut expirations)?;
+
+        Ok(u64::from_ne_bytes(expirations))
+    }
+}
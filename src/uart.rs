@@ -0,0 +1,218 @@
+use crate::{
+    command::{DisplayCommand, DisplayHandle},
+    config::UartConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+    log,
+};
+use anyhow::{anyhow, Result};
+use rppal::uart::{Parity, Uart};
+use std::{thread, time::Duration};
+
+/// Byte every frame starts with, which the stream is searched for to find
+/// the start of the next frame.
+const SYNC: u8 = 0xA5;
+
+/// Sent back once a frame was handled, or when it was rejected.
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+
+// Commands, which are the second byte of a frame
+const SET_TEXT: u8 = 0x01;
+const SHOW_FRAME: u8 = 0x02;
+const SET_BRIGHTNESS: u8 = 0x03;
+const CLEAR: u8 = 0x04;
+const PAUSE: u8 = 0x05;
+const RESUME: u8 = 0x06;
+const POWER: u8 = 0x07;
+
+/// How long the rest of a frame can take to arrive after its first byte.
+const BYTE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long to wait before reading again after the serial port failed.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Starts listening for commands on a serial port on a new thread, sending
+/// them to the display.
+///
+/// Every command is sent as a frame of bytes:
+///
+/// ```text
+/// 0xA5 | command | length | payload (length bytes) | checksum
+/// ```
+///
+/// The checksum is the low byte of the sum of the command, the length and the
+/// payload. The commands are:
+///
+/// - `0x01`: show the UTF-8 text of the payload as the message.
+/// - `0x02`: show the 64 levels of the payload, row by row, as the frame.
+/// - `0x03`: set the brightness to the single byte of the payload.
+/// - `0x04`: go back to the mode after a frame was shown, with no payload.
+/// - `0x05`: freeze the animation, with no payload.
+/// - `0x06`: let the animation continue, with no payload.
+/// - `0x07`: turn the display off if the single byte of the payload is `0`,
+///   and on otherwise.
+///
+/// Every frame is answered with `0x06` once it was handled, or with `0x15` if
+/// it was rejected.
+pub fn spawn(config: UartConfig, display: DisplayHandle) -> Result<()> {
+    let mut uart = Uart::with_path(&config.path, config.baud_rate, Parity::None, 8, 1)?;
+
+    uart.set_read_mode(0, BYTE_TIMEOUT)?;
+    uart.set_write_mode(true)?;
+
+    log::info!("Listening for commands on {}", config.path.display());
+
+    thread::spawn(move || loop {
+        if let Err(err) = serve(&mut uart, &display) {
+            log::warn!("UART: {:#}", err);
+
+            thread::sleep(RETRY_DELAY);
+        }
+    });
+
+    Ok(())
+}
+
+/// Reads the next frame and handles it, failing only if the serial port or
+/// the display does.
+fn serve(uart: &mut Uart, display: &DisplayHandle) -> Result<()> {
+    // Anything before the start of a frame is noise, or the rest of a frame
+    // which was given up on
+    while read_byte(uart)? != Some(SYNC) {}
+
+    match read_frame(uart).and_then(|(command, payload)| parse_command(command, &payload)) {
+        Ok(command) => {
+            display.send(command)?;
+            uart.write(&[ACK])?;
+        }
+        Err(err) => {
+            log::warn!("UART: rejected a frame: {:#}", err);
+            uart.write(&[NAK])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the rest of a frame after its sync byte, returning its command and
+/// payload.
+fn read_frame(uart: &mut Uart) -> Result<(u8, Vec<u8>)> {
+    let mut next = || read_byte(uart)?.ok_or_else(|| anyhow!("the frame was cut short"));
+
+    let command = next()?;
+    let length = next()?;
+
+    let mut payload = Vec::with_capacity(length as usize);
+
+    for _ in 0..length {
+        payload.push(next()?);
+    }
+
+    let checksum = payload
+        .iter()
+        .fold(command.wrapping_add(length), |sum, &byte| {
+            sum.wrapping_add(byte)
+        });
+
+    if next()? != checksum {
+        return Err(anyhow!("wrong checksum of the frame"));
+    }
+
+    Ok((command, payload))
+}
+
+/// Returns the display command of a frame.
+fn parse_command(command: u8, payload: &[u8]) -> Result<DisplayCommand> {
+    let command = match (command, payload) {
+        (SET_TEXT, text) => DisplayCommand::SetText(String::from_utf8(text.to_vec())?),
+        (SHOW_FRAME, levels) => {
+            DisplayCommand::ShowFrame(FrameBuffer::from_levels(levels).ok_or_else(|| {
+                anyhow!(
+                    "a frame has {} levels, not {}",
+                    WIDTH * HEIGHT,
+                    levels.len()
+                )
+            })?)
+        }
+        (SET_BRIGHTNESS, &[level]) => DisplayCommand::SetBrightness(level),
+        (CLEAR, []) => DisplayCommand::Clear,
+        (PAUSE, []) => DisplayCommand::Pause,
+        (RESUME, []) => DisplayCommand::Resume,
+        (POWER, &[0]) => DisplayCommand::PowerOff,
+        (POWER, &[_]) => DisplayCommand::PowerOn,
+        _ => {
+            return Err(anyhow!(
+                "invalid command {:#04x} with {} bytes",
+                command,
+                payload.len()
+            ))
+        }
+    };
+
+    Ok(command)
+}
+
+/// Reads a byte, or returns nothing if none came in time.
+fn read_byte(uart: &mut Uart) -> Result<Option<u8>> {
+    let mut byte = [0];
+
+    Ok((uart.read(&mut byte)? == 1).then_some(byte[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert!(matches!(
+            parse_command(SET_TEXT, b"Hi"),
+            Ok(DisplayCommand::SetText(text)) if text == "Hi"
+        ));
+        assert!(matches!(
+            parse_command(SHOW_FRAME, &[0; WIDTH * HEIGHT]),
+            Ok(DisplayCommand::ShowFrame(_))
+        ));
+        assert!(matches!(
+            parse_command(SET_BRIGHTNESS, &[7]),
+            Ok(DisplayCommand::SetBrightness(7))
+        ));
+        assert!(matches!(
+            parse_command(CLEAR, &[]),
+            Ok(DisplayCommand::Clear)
+        ));
+        assert!(matches!(
+            parse_command(PAUSE, &[]),
+            Ok(DisplayCommand::Pause)
+        ));
+        assert!(matches!(
+            parse_command(RESUME, &[]),
+            Ok(DisplayCommand::Resume)
+        ));
+        assert!(matches!(
+            parse_command(POWER, &[0]),
+            Ok(DisplayCommand::PowerOff)
+        ));
+        assert!(matches!(
+            parse_command(POWER, &[1]),
+            Ok(DisplayCommand::PowerOn)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_payloads() {
+        for (command, payload) in [
+            (SET_TEXT, &[0xff][..]),
+            (SHOW_FRAME, &[0; 3][..]),
+            (SET_BRIGHTNESS, &[][..]),
+            (CLEAR, &[1][..]),
+            (PAUSE, &[1][..]),
+            (RESUME, &[1][..]),
+            (POWER, &[][..]),
+            (POWER, &[0, 1][..]),
+            (0x7f, &[][..]),
+        ] {
+            assert!(parse_command(command, payload).is_err(), "{:#04x}", command);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    animation::Animation,
+    audio::Capture,
+    config::AudioConfig,
+    framebuffer::{FrameBuffer, HEIGHT, WIDTH},
+};
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use std::time::{Duration, Instant};
+
+/// Sound level, in dBFS, lighting the first column. Every column above it is
+/// as many decibels louder.
+const FLOOR_DB: f32 = -48.0;
+
+/// How fast the meter falls back once the sound gets quieter, in columns per
+/// second. It rises right away.
+const FALL_RATE: f32 = 24.0;
+
+/// How long the peak stays lit before it falls, and then how long it takes to
+/// fall by a column.
+const PEAK_HOLD: Duration = Duration::from_secs(1);
+const PEAK_FALL: Duration = Duration::from_millis(80);
+
+/// Sound level indicator, showing how loud a microphone hears it.
+///
+/// The louder the sound, the more columns are lit from the left, every one
+/// taller than the one before it. The top of the loudest column recently lit
+/// stays lit for a moment as the peak.
+pub struct VuMeter {
+    capture: Capture,
+    // Columns lit, as a fraction to fall smoothly
+    level: f32,
+    peak: usize,
+    // When the peak last rose or fell
+    peak_since: Instant,
+    last_tick: Instant,
+}
+
+impl VuMeter {
+    pub fn new(config: &AudioConfig) -> Result<Self> {
+        Ok(Self {
+            capture: Capture::new(config)?,
+            level: 0.0,
+            peak: 0,
+            peak_since: Instant::now(),
+            last_tick: Instant::now(),
+        })
+    }
+}
+
+/// Returns how many columns the loudness of a block lights.
+fn columns(block: &[f32]) -> f32 {
+    let rms = (block.iter().map(|sample| sample * sample).sum::<f32>() / block.len() as f32).sqrt();
+    let db = 20.0 * rms.max(f32::MIN_POSITIVE).log10();
+
+    ((db - FLOOR_DB) / -FLOOR_DB * WIDTH as f32).clamp(0.0, WIDTH as f32)
+}
+
+impl Animation for VuMeter {
+    fn tick(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f32();
+        self.last_tick = Instant::now();
+
+        let loudest = self
+            .capture
+            .blocks()
+            .map(|block| columns(&block))
+            .fold(0.0, f32::max);
+
+        self.level = loudest.max(self.level - FALL_RATE * elapsed);
+
+        let lit = self.level.round() as usize;
+
+        if lit >= self.peak {
+            self.peak = lit;
+            self.peak_since = Instant::now();
+        } else if self.peak_since.elapsed() >= PEAK_HOLD {
+            self.peak -= 1;
+            // Falling further only waits for the fall of one column
+            self.peak_since = Instant::now() - PEAK_HOLD + PEAK_FALL;
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let style = PrimitiveStyle::with_fill(BinaryColor::On);
+        let height = |column: usize| (column + 1) * HEIGHT / WIDTH;
+
+        for column in 0..self.level.round() as usize {
+            let Ok(()) = Rectangle::new(
+                Point::new(column as i32, (HEIGHT - height(column)) as i32),
+                Size::new(1, height(column) as u32),
+            )
+            .into_styled(style)
+            .draw(frame);
+        }
+
+        if self.peak > 0 {
+            let column = self.peak - 1;
+
+            let Ok(()) = Pixel(
+                Point::new(column as i32, (HEIGHT - height(column)) as i32),
+                BinaryColor::On,
+            )
+            .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::log;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// How a supervised thread stopped working.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Failure {
+    Panicked,
+    /// The thread didn't beat for longer than the timeout.
+    Stalled,
+}
+
+/// Handed to a supervised thread, which has to call [`Heartbeat::beat`]
+/// regularly to show that it's still making progress.
+#[derive(Clone, Default)]
+pub struct Heartbeat {
+    beats: Arc<AtomicU64>,
+    abandoned: Arc<AtomicBool>,
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        self.beats.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns whether the supervisor gave up on the thread and started
+    /// another one in its place, in which case the thread should return as
+    /// soon as it can.
+    pub fn abandoned(&self) -> bool {
+        self.abandoned.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs a function on a thread, and notices when the thread panics or stops
+/// beating its [`Heartbeat`] so that it can be started again.
+///
+/// A stalled thread can't be stopped from the outside, so it is only told
+/// that it was abandoned and left alone.
+pub struct Watchdog {
+    name: &'static str,
+    timeout: Duration,
+    run: Arc<dyn Fn(Heartbeat) + Send + Sync>,
+    thread: JoinHandle<()>,
+    heartbeat: Heartbeat,
+    // Beats seen at the last check, and when they last changed
+    beats: u64,
+    last_beat: Instant,
+}
+
+impl Watchdog {
+    pub fn spawn(
+        name: &'static str,
+        timeout: Duration,
+        run: impl Fn(Heartbeat) + Send + Sync + 'static,
+    ) -> Self {
+        let run: Arc<dyn Fn(Heartbeat) + Send + Sync> = Arc::new(run);
+        let heartbeat = Heartbeat::default();
+
+        Self {
+            name,
+            timeout,
+            thread: Self::start(&run, heartbeat.clone()),
+            run,
+            heartbeat,
+            beats: 0,
+            last_beat: Instant::now(),
+        }
+    }
+
+    fn start(run: &Arc<dyn Fn(Heartbeat) + Send + Sync>, heartbeat: Heartbeat) -> JoinHandle<()> {
+        let run = run.clone();
+
+        thread::spawn(move || run(heartbeat))
+    }
+
+    /// Returns how the thread stopped working, if it did since it was last
+    /// started.
+    pub fn check(&mut self) -> Option<Failure> {
+        if self.thread.is_finished() {
+            return Some(Failure::Panicked);
+        }
+
+        let beats = self.heartbeat.beats.load(Ordering::Relaxed);
+
+        if beats != self.beats {
+            self.beats = beats;
+            self.last_beat = Instant::now();
+        }
+
+        (self.last_beat.elapsed() > self.timeout).then_some(Failure::Stalled)
+    }
+
+    /// Abandons the current thread and starts a new one in its place.
+    pub fn restart(&mut self) {
+        log::info!("Restarting the {} thread", self.name);
+
+        self.heartbeat.abandoned.store(true, Ordering::Relaxed);
+        self.heartbeat = Heartbeat::default();
+        self.beats = 0;
+        self.last_beat = Instant::now();
+        self.thread = Self::start(&self.run, self.heartbeat.clone());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    animation::Animation,
+    config::WeatherConfig,
+    framebuffer::FrameBuffer,
+    http,
+    icons::{self, Icon, CLOUD, RAIN, SNOW, SUN},
+    json::Json,
+    log,
+    widget::show_number,
+};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::*;
+use std::{
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Open-Meteo's forecast API, which needs no API key.
+const API_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// How long to wait before trying again after a failed fetch.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Number of frames the icon and the temperature are each shown for before
+/// switching to the other one.
+const VIEW_FRAMES: u32 = 15;
+
+/// Number of frames each frame of the icon's animation is shown for.
+const ICON_FRAMES: u32 = 3;
+
+/// Kind of weather, simplified from the WMO weather codes Open-Meteo uses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl Condition {
+    fn from_wmo_code(code: u32) -> Self {
+        match code {
+            0 | 1 => Condition::Clear,
+            2 => Condition::PartlyCloudy,
+            3 => Condition::Cloudy,
+            45 | 48 => Condition::Fog,
+            51..=57 => Condition::Drizzle,
+            71..=77 | 85 | 86 => Condition::Snow,
+            95..=99 => Condition::Thunderstorm,
+            _ => Condition::Rain,
+        }
+    }
+
+    /// Frames of the animated icon of the condition.
+    fn icon(self) -> &'static [Icon] {
+        match self {
+            Condition::Clear => &SUN,
+            Condition::PartlyCloudy | Condition::Cloudy | Condition::Fog => &CLOUD,
+            Condition::Drizzle | Condition::Rain | Condition::Thunderstorm => &RAIN,
+            Condition::Snow => &SNOW,
+        }
+    }
+}
+
+/// Current weather at a location.
+#[derive(Clone, Copy, Debug)]
+pub struct Weather {
+    /// Temperature, in degrees Celsius.
+    pub celsius: f32,
+    pub condition: Condition,
+}
+
+/// Fetches the current weather at the configured location.
+fn fetch(config: &WeatherConfig) -> Result<Weather> {
+    let url = format!(
+        "{}?latitude={}&longitude={}&current=temperature_2m,weather_code",
+        API_URL, config.latitude, config.longitude
+    );
+
+    let response = Json::parse(&http::get(&url)?)?;
+    let current = response
+        .get("current")
+        .ok_or_else(|| anyhow!("no current weather in the answer of Open-Meteo"))?;
+
+    let field = |name| {
+        current
+            .get(name)
+            .and_then(Json::as_f64)
+            .ok_or_else(|| anyhow!("no {} in the answer of Open-Meteo", name))
+    };
+
+    Ok(Weather {
+        celsius: field("temperature_2m")? as f32,
+        condition: Condition::from_wmo_code(field("weather_code")? as u32),
+    })
+}
+
+/// Shows the current weather, fetched from Open-Meteo.
+///
+/// It alternates between an animated icon of the kind of weather and the
+/// temperature in degrees Celsius. While offline, the last weather which
+/// could be fetched keeps being shown.
+pub struct WeatherReport {
+    // Every fetch, with nothing for the failed ones
+    updates: Receiver<Option<Weather>>,
+    weather: Option<Weather>,
+    frame_count: u32,
+}
+
+impl WeatherReport {
+    pub fn new(config: WeatherConfig) -> Self {
+        let (tx, rx) = channel();
+
+        // Fetching takes a while, so it happens on its own thread
+        thread::spawn(move || loop {
+            let (update, delay) = match fetch(&config) {
+                Ok(weather) => (
+                    Some(weather),
+                    Duration::from_secs(config.interval_mins * 60),
+                ),
+                Err(err) => {
+                    log::warn!("Weather: {:#}", err);
+
+                    (None, RETRY_DELAY)
+                }
+            };
+
+            // Failures are sent too, so that the thread stops once the mode
+            // is gone even while they go on
+            if tx.send(update).is_err() {
+                return;
+            }
+
+            thread::sleep(delay);
+        });
+
+        Self {
+            updates: rx,
+            weather: None,
+            frame_count: 0,
+        }
+    }
+}
+
+impl Animation for WeatherReport {
+    fn tick(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if let Some(weather) = self.updates.try_iter().flatten().last() {
+            self.weather = Some(weather);
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        let Some(weather) = self.weather else {
+            return;
+        };
+
+        if (self.frame_count / VIEW_FRAMES).is_multiple_of(2) {
+            let frames = weather.condition.icon();
+            let icon = &frames[(self.frame_count / ICON_FRAMES) as usize % frames.len()];
+
+            icons::draw_icon(icon, Point::zero(), frame);
+        } else {
+            let Ok(()) = show_number(weather.celsius.round() as i64)
+                .at_frame(self.frame_count % VIEW_FRAMES)
+                .draw(frame);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    command::DisplayCommand,
+    config::{WebhookConfig, WebhookRule},
+    json::{Json, JsonPath},
+    server::Request,
+    template,
+    text::Priority,
+};
+use anyhow::{anyhow, Result};
+
+/// Turns the JSON payloads other systems post to `/webhook`, like GitHub or
+/// Grafana alerts, into announcements, following the configured rules.
+pub struct Webhook {
+    token: String,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    when: Option<JsonPath>,
+    equals: Option<String>,
+    message: String,
+    priority: Priority,
+}
+
+impl Rule {
+    fn new(config: &WebhookRule) -> Result<Self> {
+        Ok(Self {
+            when: config.when.as_deref().map(JsonPath::parse).transpose()?,
+            equals: config.equals.clone(),
+            message: config.message.clone(),
+            priority: config.priority,
+        })
+    }
+
+    fn matches(&self, payload: &Json) -> bool {
+        let Some(when) = &self.when else {
+            return true;
+        };
+
+        match (when.select(payload), &self.equals) {
+            (None | Some(Json::Null), _) => false,
+            (Some(_), None) => true,
+            (Some(Json::String(value)), Some(expected)) => value == expected,
+            (Some(value), Some(expected)) => value.to_string() == *expected,
+        }
+    }
+}
+
+impl Webhook {
+    /// Fails if a rule has an invalid JSONPath, so that it's found out
+    /// before the first payload comes, or if the token is empty, which
+    /// `?token=` would give.
+    pub fn new(config: &WebhookConfig) -> Result<Self> {
+        if config.token.trim().is_empty() {
+            return Err(anyhow!("the token of the webhook can't be empty"));
+        }
+
+        Ok(Self {
+            token: config.token.clone(),
+            rules: config.rules.iter().map(Rule::new).collect::<Result<_>>()?,
+        })
+    }
+
+    /// Returns whether `request` has the token.
+    pub fn authorized(&self, request: &Request) -> bool {
+        [request.query_param("token"), request.bearer_token()].contains(&Some(self.token.as_str()))
+    }
+
+    /// Returns the announcement of the first rule which matches `payload`,
+    /// if any does.
+    pub fn command(&self, payload: &Json) -> Option<DisplayCommand> {
+        let rule = self.rules.iter().find(|rule| rule.matches(payload))?;
+
+        let message = template::render(&rule.message, |path| {
+            JsonPath::parse(path).ok()?.select(payload)
+        });
+
+        Some(DisplayCommand::Alert(message, rule.priority))
+    }
+}
@@ -0,0 +1,281 @@
+use crate::server::Request;
+use anyhow::{anyhow, Result};
+use std::{
+    io::{BufReader, Read, Write},
+    net::TcpStream,
+};
+
+/// Appended to the key of the client to prove the server speaks WebSocket.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest message which is accepted, in bytes.
+const MAX_MESSAGE_LENGTH: u64 = 64 * 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// The server's end of a WebSocket connection, as much of it as is needed to
+/// receive text messages from a browser.
+pub struct WebSocket {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+
+impl WebSocket {
+    /// Answers `request` to upgrade its connection to a WebSocket.
+    pub fn accept(
+        request: &Request,
+        reader: BufReader<TcpStream>,
+        mut stream: TcpStream,
+    ) -> Result<Self> {
+        let upgrade = request.header("upgrade").unwrap_or_default();
+
+        if !upgrade.eq_ignore_ascii_case("websocket") {
+            return Err(anyhow!("not a WebSocket request"));
+        }
+
+        let key = request
+            .header("sec-websocket-key")
+            .ok_or_else(|| anyhow!("missing WebSocket key"))?;
+
+        let accept = accept_key(key);
+
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            accept
+        )?;
+
+        Ok(Self { reader, stream })
+    }
+
+    /// Waits for the next text message, or returns nothing once the
+    /// connection is closed.
+    ///
+    /// Pings are answered while waiting, and binary messages are skipped.
+    pub fn read_message(&mut self) -> Result<Option<String>> {
+        let mut message = Vec::new();
+        let mut opcode = None;
+
+        loop {
+            let (fin, frame_opcode, payload) = self.read_frame()?;
+
+            match frame_opcode {
+                OPCODE_CLOSE => {
+                    // Echoing the close frame completes the closing handshake
+                    let _ = self.write_frame(OPCODE_CLOSE, &payload);
+                    return Ok(None);
+                }
+                OPCODE_PING => self.write_frame(OPCODE_PONG, &payload)?,
+                OPCODE_PONG => {}
+                OPCODE_TEXT | OPCODE_BINARY | OPCODE_CONTINUATION => {
+                    if frame_opcode != OPCODE_CONTINUATION {
+                        opcode = Some(frame_opcode);
+                        message.clear();
+                    }
+
+                    message.extend_from_slice(&payload);
+
+                    if message.len() as u64 > MAX_MESSAGE_LENGTH {
+                        return Err(anyhow!("WebSocket message is too large"));
+                    }
+
+                    if fin && opcode == Some(OPCODE_TEXT) {
+                        return Ok(Some(String::from_utf8(message)?));
+                    }
+                }
+                _ => return Err(anyhow!("unknown WebSocket opcode {}", frame_opcode)),
+            }
+        }
+    }
+
+    /// Reads a frame, returning whether it is the last of its message, its
+    /// opcode and its unmasked payload.
+    fn read_frame(&mut self) -> Result<(bool, u8, Vec<u8>)> {
+        let mut header = [0; 2];
+        self.reader.read_exact(&mut header)?;
+
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0F;
+        let masked = header[1] & 0x80 != 0;
+
+        let length = match header[1] & 0x7F {
+            126 => {
+                let mut length = [0; 2];
+                self.reader.read_exact(&mut length)?;
+                u16::from_be_bytes(length) as u64
+            }
+            127 => {
+                let mut length = [0; 8];
+                self.reader.read_exact(&mut length)?;
+                u64::from_be_bytes(length)
+            }
+            length => length as u64,
+        };
+
+        if length > MAX_MESSAGE_LENGTH {
+            return Err(anyhow!("WebSocket frame of {} bytes is too large", length));
+        }
+
+        // Browsers always mask what they send
+        if !masked {
+            return Err(anyhow!("unmasked WebSocket frame"));
+        }
+
+        let mut mask = [0; 4];
+        self.reader.read_exact(&mut mask)?;
+
+        let mut payload = vec![0; length as usize];
+        self.reader.read_exact(&mut payload)?;
+
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        Ok((fin, opcode, payload))
+    }
+
+    /// Writes a frame, which the server never masks.
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<()> {
+        let mut frame = vec![0x80 | opcode];
+
+        match payload.len() {
+            length @ 0..=125 => frame.push(length as u8),
+            length @ 126..=0xFFFF => {
+                frame.push(126);
+                frame.extend_from_slice(&(length as u16).to_be_bytes());
+            }
+            length => {
+                frame.push(127);
+                frame.extend_from_slice(&(length as u64).to_be_bytes());
+            }
+        }
+
+        frame.extend_from_slice(payload);
+        self.stream.write_all(&frame)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the key proving the server speaks WebSocket, for the key of the
+/// client.
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()))
+}
+
+/// Returns the SHA-1 hash of `data`, which the handshake is built on.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    // The message is padded with a one bit, zeros, and its length in bits,
+    // up to a multiple of 64 bytes
+    let mut message = data.to_vec();
+    message.push(0x80);
+
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (h, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(value);
+        }
+    }
+
+    let mut hash = [0; 20];
+
+    for (bytes, h) in hash.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&h.to_be_bytes());
+    }
+
+    hash
+}
+
+/// Encodes `data` as standard, padded base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_of_the_rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    animation::Animation,
+    framebuffer::FrameBuffer,
+    input::{Button, InputEvent},
+    text::ScrollingText,
+};
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use rand::{seq::SliceRandom, Rng};
+
+/// Frames the first mole stays up for.
+const START_FRAMES: u32 = 10;
+
+/// Moles never stay up for fewer frames than this.
+const MIN_FRAMES: u32 = 3;
+
+/// Every this many whacked moles, the time limit gets one frame shorter.
+const SPEEDUP_EVERY: u32 = 3;
+
+/// Whack-a-mole game.
+///
+/// A random cell lights up and has to be whacked by pressing the button of
+/// the quadrant it is in before it disappears. The game gets faster with every
+/// few moles, and ends on the first miss.
+pub struct Whack {
+    state: State,
+}
+
+enum State {
+    Playing {
+        mole: Point,
+        button: Button,
+        frames_left: u32,
+        score: u32,
+    },
+    // The score is scrolled until any button is pressed
+    GameOver(ScrollingText),
+}
+
+impl Whack {
+    pub fn new() -> Self {
+        Self {
+            state: next_mole(0),
+        }
+    }
+
+    fn game_over(&mut self, score: u32) {
+        let message = format!("Missed! Score: {}", score);
+
+        self.state = State::GameOver(ScrollingText::new(message));
+    }
+}
+
+/// Puts up a new mole, with a time limit based on the current score.
+fn next_mole(score: u32) -> State {
+    let mut rng = rand::thread_rng();
+
+    let button = *Button::ALL.choose(&mut rng).unwrap();
+    // Every button in `Button::ALL` has a quadrant
+    let quadrant = button.quadrant().unwrap();
+    let mole = quadrant.top_left
+        + Point::new(
+            rng.gen_range(0..quadrant.size.width as i32),
+            rng.gen_range(0..quadrant.size.height as i32),
+        );
+
+    State::Playing {
+        mole,
+        button,
+        frames_left: START_FRAMES
+            .saturating_sub(score / SPEEDUP_EVERY)
+            .max(MIN_FRAMES),
+        score,
+    }
+}
+
+impl Animation for Whack {
+    fn tick(&mut self) {
+        match &mut self.state {
+            State::Playing {
+                frames_left, score, ..
+            } => {
+                *frames_left -= 1;
+
+                if *frames_left == 0 {
+                    let score = *score;
+
+                    self.game_over(score);
+                }
+            }
+            State::GameOver(text) => text.tick(),
+        }
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer) {
+        match &self.state {
+            State::Playing { mole, .. } => {
+                let Ok(()) = Pixel(*mole, BinaryColor::On).draw(frame);
+            }
+            State::GameOver(text) => text.draw(frame),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn handle_input(&mut self, event: InputEvent) {
+        let InputEvent::Pressed(pressed) = event else {
+            return;
+        };
+
+        // Select isn't one of the quadrants, and is held down to leave the
+        // game
+        if pressed == Button::Select {
+            return;
+        }
+
+        match self.state {
+            State::Playing { button, score, .. } if button == pressed => {
+                self.state = next_mole(score + 1);
+            }
+            State::Playing { score, .. } => self.game_over(score),
+            State::GameOver(_) => self.state = next_mole(0),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use crate::framebuffer::{HEIGHT, WIDTH};
+use embedded_graphics::{
+    mono_font::{
+        ascii::{FONT_4X6, FONT_5X8},
+        MonoTextStyle,
+    },
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Text},
+};
+use std::collections::VecDeque;
+
+/// Returns the whole matrix as a rectangle.
+fn full_matrix() -> Rectangle {
+    Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32))
+}
+
+/// A bar which fills up with a fraction from `0` to `1`, either from left to
+/// right or from the bottom up.
+///
+/// It covers the whole matrix unless it is given an area of its own.
+#[derive(Clone, Copy)]
+pub struct ProgressBar {
+    fraction: f32,
+    area: Rectangle,
+    vertical: bool,
+    outlined: bool,
+}
+
+impl ProgressBar {
+    /// A bar which fills from left to right.
+    pub fn horizontal(fraction: f32) -> Self {
+        Self {
+            fraction,
+            area: full_matrix(),
+            vertical: false,
+            outlined: false,
+        }
+    }
+
+    /// A bar which fills from the bottom up.
+    pub fn vertical(fraction: f32) -> Self {
+        Self {
+            vertical: true,
+            ..Self::horizontal(fraction)
+        }
+    }
+
+    pub fn in_area(self, area: Rectangle) -> Self {
+        Self { area, ..self }
+    }
+
+    /// Draws an outline around the bar, which then fills the inside of it.
+    pub fn outlined(self) -> Self {
+        Self {
+            outlined: true,
+            ..self
+        }
+    }
+}
+
+impl Drawable for ProgressBar {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let inside = if self.outlined {
+            self.area
+                .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                .draw(target)?;
+
+            self.area.offset(-1)
+        } else {
+            self.area
+        };
+
+        let fraction = self.fraction.clamp(0.0, 1.0);
+
+        let fill = if self.vertical {
+            let height = (fraction * inside.size.height as f32).round() as u32;
+
+            Rectangle::new(
+                inside.top_left + Point::new(0, (inside.size.height - height) as i32),
+                Size::new(inside.size.width, height),
+            )
+        } else {
+            let width = (fraction * inside.size.width as f32).round() as u32;
+
+            Rectangle::new(inside.top_left, Size::new(width, inside.size.height))
+        };
+
+        fill.into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)
+    }
+}
+
+/// Vertical bars for a few values, one column each, scaled so that the
+/// largest value fills the height unless a maximum is given.
+///
+/// Only the last values which fit in the width are drawn, the last one on the
+/// right. It covers the whole matrix unless it is given an area of its own.
+#[derive(Clone)]
+pub struct BarGraph {
+    values: Vec<f32>,
+    max: Option<f32>,
+    area: Rectangle,
+}
+
+impl BarGraph {
+    pub fn new(values: impl IntoIterator<Item = f32>) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+            max: None,
+            area: full_matrix(),
+        }
+    }
+
+    /// Scales the bars so that `max` fills the height, rather than the largest
+    /// value.
+    pub fn with_max(self, max: f32) -> Self {
+        Self {
+            max: Some(max),
+            ..self
+        }
+    }
+
+    pub fn in_area(self, area: Rectangle) -> Self {
+        Self { area, ..self }
+    }
+}
+
+impl Drawable for BarGraph {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let max = self
+            .max
+            .unwrap_or_else(|| self.values.iter().copied().fold(0.0, f32::max));
+
+        if max <= 0.0 {
+            return Ok(());
+        }
+
+        let Size { width, height } = self.area.size;
+        let shown = &self.values[self.values.len().saturating_sub(width as usize)..];
+        let first_column = self.area.top_left.x + (width as usize - shown.len()) as i32;
+        let bottom = self.area.top_left.y + height as i32;
+
+        for (column, value) in shown.iter().enumerate() {
+            let bar = ((value / max).clamp(0.0, 1.0) * height as f32).round() as u32;
+
+            Rectangle::new(
+                Point::new(first_column + column as i32, bottom - bar as i32),
+                Size::new(1, bar),
+            )
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A line through the most recent samples of something, one pixel per column
+/// with the newest sample on the right.
+///
+/// It keeps as many samples as there are columns, and scales them from the
+/// smallest one on the bottom row to the largest one on the top row.
+#[derive(Clone)]
+pub struct Sparkline {
+    // The newest sample at the back
+    samples: VecDeque<f32>,
+}
+
+impl Sparkline {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(WIDTH),
+        }
+    }
+
+    /// Adds a sample, dropping the oldest one if every column is taken.
+    pub fn push(&mut self, sample: f32) {
+        if self.samples.len() == WIDTH {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Returns the newest sample.
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().copied()
+    }
+}
+
+impl Drawable for Sparkline {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let (min, max) = self
+            .samples
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), &sample| {
+                (min.min(sample), max.max(sample))
+            });
+
+        let first_column = WIDTH - self.samples.len();
+
+        let pixels = self.samples.iter().enumerate().map(|(column, &sample)| {
+            // All samples are the same if there is no range to scale them to
+            let fraction = if max > min {
+                ((sample - min) / (max - min)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            let x = (first_column + column) as i32;
+            let y = HEIGHT as i32 - 1 - (fraction * (HEIGHT - 1) as f32).round() as i32;
+
+            Pixel(Point::new(x, y), BinaryColor::On)
+        });
+
+        target.draw_iter(pixels)
+    }
+}
+
+/// Fills the whole matrix one pixel at a time with a fraction from `0` to
+/// `1`, row by row from the bottom up, which is finer than a bar.
+#[derive(Clone, Copy)]
+pub struct Gauge {
+    fraction: f32,
+}
+
+impl Gauge {
+    pub fn new(fraction: f32) -> Self {
+        Self { fraction }
+    }
+}
+
+impl Drawable for Gauge {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let lit = (self.fraction.clamp(0.0, 1.0) * (WIDTH * HEIGHT) as f32).round() as usize;
+
+        let pixels = (0..lit).map(|i| {
+            let x = (i % WIDTH) as i32;
+            let y = (HEIGHT - 1 - i / WIDTH) as i32;
+
+            Pixel(Point::new(x, y), BinaryColor::On)
+        });
+
+        target.draw_iter(pixels)
+    }
+}
+
+/// Number of frames every page of a paginated number is shown for.
+const PAGE_FRAMES: u32 = 5;
+
+/// Returns a number drawn as large as it fits in the middle of the matrix.
+///
+/// A single digit is drawn in a large font, and two characters, like `42` or
+/// `-7`, in a font small enough for both of them to fit. Numbers of up to
+/// four characters are shown two at a time, the last two on the last page,
+/// followed by a blank page so that it is clear where they start again.
+/// Longer numbers are scrolled.
+///
+/// Numbers which don't fit are animated, so they have to be told for how
+/// many frames they were shown.
+pub fn show_number(value: i64) -> BigNumber {
+    BigNumber { value, frame: 0 }
+}
+
+#[derive(Clone, Copy)]
+pub struct BigNumber {
+    value: i64,
+    frame: u32,
+}
+
+impl BigNumber {
+    /// Draws the number as it is after being shown for `frame` frames.
+    pub fn at_frame(self, frame: u32) -> Self {
+        Self { frame, ..self }
+    }
+}
+
+impl Drawable for BigNumber {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let text = self.value.to_string();
+        let small = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+        let large = MonoTextStyle::new(&FONT_5X8, BinaryColor::On);
+
+        match text.len() {
+            1 => {
+                Text::with_alignment(&text, Point::new(4, 7), large, Alignment::Center)
+                    .draw(target)?;
+            }
+            2 => {
+                Text::with_alignment(&text, Point::new(4, 6), small, Alignment::Center)
+                    .draw(target)?;
+            }
+            3..=4 => {
+                // Padded so that the last page is full, and the page after
+                // it is blank
+                let text = format!("{:>4}", text);
+                let page = (self.frame / PAGE_FRAMES % 3) as usize;
+
+                if let Some(page) = text.get(page * 2..page * 2 + 2) {
+                    Text::with_alignment(page, Point::new(4, 6), small, Alignment::Center)
+                        .draw(target)?;
+                }
+            }
+            _ => {
+                let text = Text::new(&text, Point::new(0, 7), large);
+                // Scrolled all the way out before starting over
+                let width = text.bounding_box().size.width + WIDTH as u32;
+
+                text.translate(Point::new(-((self.frame % width) as i32), 0))
+                    .draw(target)?;
+            }
+        }
+
+        Ok(())
+    }
+}